@@ -0,0 +1,11 @@
+#![no_main]
+
+use bankai_client::sync_committee::SyncCommitteeUpdate;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(update) = serde_json::from_slice::<SyncCommitteeUpdate>(data) {
+        let json = serde_json::to_vec(&update).unwrap();
+        serde_json::from_slice::<SyncCommitteeUpdate>(&json).unwrap();
+    }
+});
@@ -0,0 +1,11 @@
+#![no_main]
+
+use bankai_client::epoch_batch::EpochUpdateBatch;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(batch) = serde_json::from_slice::<EpochUpdateBatch>(data) {
+        let json = serde_json::to_vec(&batch).unwrap();
+        serde_json::from_slice::<EpochUpdateBatch>(&json).unwrap();
+    }
+});
@@ -0,0 +1,13 @@
+#![no_main]
+
+use bankai_client::epoch_update::G2Point;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    // Points that decode must encode to an equal point
+    if let Ok(point) = serde_json::from_slice::<G2Point>(data) {
+        let json = serde_json::to_vec(&point).unwrap();
+        let decoded: G2Point = serde_json::from_slice(&json).unwrap();
+        assert_eq!(decoded.0, point.0);
+    }
+});
@@ -0,0 +1,179 @@
+use std::collections::BTreeMap;
+
+use alloy_primitives::FixedBytes;
+use serde::Serialize;
+use tracing::info;
+
+use crate::client::{BankaiClient, ProverKind};
+use crate::epoch_batch::EpochUpdateBatch;
+use crate::epoch_update::EpochUpdate;
+use crate::traits::ProofType;
+use crate::utils::event_index::EventIndex;
+use crate::utils::hashing::get_committee_hash;
+use crate::utils::step_model::{BatchStrategy, StepModel};
+use crate::Error;
+
+const SLOTS_PER_EPOCH: u64 = 32;
+/// Slots signed by the same sync committee
+const SLOTS_PER_PERIOD: u64 = 0x2000;
+
+/// Where to look up which epochs are already available in the contract
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum EpochLookup {
+    /// The local event index, which also knows the epochs that can be decommitted from batches
+    Index,
+    /// `get_epoch_proof` calls, which only see epochs stored directly
+    Contract,
+}
+
+/// The missing epochs of a slot range, grouped by the committee period that signed them
+#[derive(Debug, Default, Serialize)]
+pub struct BackfillPlan {
+    /// Missing epoch slots per committee id, for committees stored in the contract
+    pub periods: BTreeMap<u64, Vec<u64>>,
+    /// Missing epoch slots of committees the contract doesn't know yet. These require a
+    /// committee update before they can be backfilled.
+    pub unknown_committees: BTreeMap<u64, Vec<u64>>,
+    /// Committee hashes stored in the contract
+    pub committee_hashes: BTreeMap<u64, FixedBytes<32>>,
+    /// Maximum number of epochs per batch, chosen by the step model
+    pub batch_size: usize,
+}
+
+impl BackfillPlan {
+    pub async fn new(
+        bankai: &BankaiClient,
+        from: u64,
+        to: u64,
+        lookup: EpochLookup,
+        strategy: BatchStrategy,
+    ) -> Result<BackfillPlan, Error> {
+        let index = match lookup {
+            EpochLookup::Index => {
+                let mut index = EventIndex::load(&bankai.config.event_index_path)?;
+                bankai
+                    .starknet_client
+                    .index_events(&mut index, &bankai.config)
+                    .await?;
+                index.save(&bankai.config.event_index_path)?;
+                Some(index)
+            }
+            EpochLookup::Contract => None,
+        };
+
+        let model = StepModel::load(&bankai.config.step_model_path)?;
+        let mut plan = BackfillPlan {
+            batch_size: model.batch_size(strategy.step_budget(&bankai.config)) as usize,
+            ..Default::default()
+        };
+        let first_epoch = from.div_ceil(SLOTS_PER_EPOCH) * SLOTS_PER_EPOCH;
+        for slot in (first_epoch..=to).step_by(SLOTS_PER_EPOCH as usize) {
+            let available = match &index {
+                Some(index) => {
+                    index.epochs.contains_key(&slot) || index.batch_root_for_slot(slot).is_some()
+                }
+                None => !bankai
+                    .starknet_client
+                    .get_epoch_proof(slot, &bankai.config)
+                    .await?
+                    .header_root
+                    .is_zero(),
+            };
+            if available {
+                continue;
+            }
+
+            let committee_id = slot / SLOTS_PER_PERIOD;
+            if !plan.committee_hashes.contains_key(&committee_id)
+                && !plan.unknown_committees.contains_key(&committee_id)
+            {
+                let committee_hash = bankai
+                    .starknet_client
+                    .get_committee_hash(committee_id, &bankai.config)
+                    .await?;
+                if committee_hash.is_zero() {
+                    plan.unknown_committees.insert(committee_id, vec![]);
+                } else {
+                    plan.committee_hashes.insert(committee_id, committee_hash);
+                }
+            }
+
+            match plan.unknown_committees.get_mut(&committee_id) {
+                Some(slots) => slots.push(slot),
+                None => plan.periods.entry(committee_id).or_default().push(slot),
+            }
+        }
+        Ok(plan)
+    }
+
+    /// Summary of the plan, one line per committee
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = vec![];
+        for (committee_id, slots) in self.periods.iter() {
+            lines.push(format!(
+                "Committee {}: {} missing epochs in {} batches ({} to {})",
+                committee_id,
+                slots.len(),
+                slots.len().div_ceil(self.batch_size),
+                slots.first().unwrap_or(&0),
+                slots.last().unwrap_or(&0)
+            ));
+        }
+        for (committee_id, slots) in self.unknown_committees.iter() {
+            lines.push(format!(
+                "Committee {}: {} missing epochs skipped, the committee is not stored in the contract",
+                committee_id,
+                slots.len()
+            ));
+        }
+        lines
+    }
+
+    /// Generates and submits a batch for every chunk of missing epochs.
+    /// Returns the submitted batch ids with the slot of their latest epoch.
+    pub async fn run(
+        &self,
+        bankai: &BankaiClient,
+        prover: ProverKind,
+    ) -> Result<Vec<(String, u64)>, Error> {
+        if self.periods.is_empty() {
+            return Ok(vec![]);
+        }
+        bankai.ensure_program_hash(ProofType::EpochBatch).await?;
+
+        let mut submitted = vec![];
+        for (committee_id, slots) in self.periods.iter() {
+            for chunk in slots.chunks(self.batch_size) {
+                let mut epochs = vec![];
+                for slot in chunk {
+                    let epoch = EpochUpdate::new(&bankai.client, *slot).await?;
+                    self.check_committee(*committee_id, &epoch)?;
+                    epochs.push(epoch);
+                }
+
+                let batch = EpochUpdateBatch::from_epochs(epochs)?;
+                let latest_slot = batch.expected_circuit_outputs.latest_batch_output.slot;
+                let batch_id = bankai.submit_proof(prover, &batch).await?;
+                info!(
+                    n_epochs = chunk.len(),
+                    latest_slot,
+                    %batch_id,
+                    "Batch submitted"
+                );
+                submitted.push((batch_id, latest_slot));
+            }
+        }
+        Ok(submitted)
+    }
+
+    /// Ensures the epoch was signed by the committee stored in the contract
+    fn check_committee(&self, committee_id: u64, epoch: &EpochUpdate) -> Result<(), Error> {
+        let stored = self.committee_hashes[&committee_id];
+        let signer = get_committee_hash(epoch.circuit_inputs.aggregate_pub.0);
+        if signer != stored {
+            return Err(Error::CommitteeHashMismatch(committee_id, stored, signer));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,264 @@
+//! The client behind the CLI commands and the API server: the beacon and Starknet clients,
+//! the prover backends and the artifact store, set up from the config and the environment.
+
+use starknet::core::types::Felt;
+use tracing::field::Empty;
+use tracing::{info, instrument, warn, Span};
+
+use crate::config::{config_path, required_env, BankaiConfig};
+use crate::contract_init::ContractInitializationData;
+use crate::epoch_update::{EpochUpdate, ExpectedEpochUpdateOutputs};
+use crate::sync_committee::SyncCommitteeUpdate;
+use crate::traits::{ProofType, Provable, ProverBackend, StarkProof};
+use crate::utils::{
+    artifact_store::ArtifactKind, atlantic_client::AtlanticClient, cairo_runner::CairoRunner,
+    job_log::JobLog, metrics::metrics, mmr::EpochAccumulator, program_hash::ProgramHashReport,
+    rpc::BeaconRpcClient, starknet_client::StarknetClient, step_model::StepModel,
+    stone_prover::LocalStoneProver,
+};
+use crate::{ArtifactStore, Error};
+
+/// Everything needed to generate, prove and submit updates
+pub struct BankaiClient {
+    pub client: BeaconRpcClient,
+    pub starknet_client: StarknetClient,
+    pub config: BankaiConfig,
+    pub config_path: String,
+    pub atlantic_client: AtlanticClient,
+    pub local_prover: LocalStoneProver,
+    pub store: ArtifactStore,
+}
+
+/// The backend used for proving and wrapping
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum ProverKind {
+    /// Prove using the Atlantic API
+    Atlantic,
+    /// Prove on this machine using the Stone prover
+    Local,
+}
+
+impl ProverKind {
+    /// Name of the backend as recorded in the job log
+    pub fn name(&self) -> &'static str {
+        match self {
+            ProverKind::Atlantic => "atlantic",
+            ProverKind::Local => "local",
+        }
+    }
+
+    /// The backend recorded under `name` in the job log
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "atlantic" => Some(ProverKind::Atlantic),
+            "local" => Some(ProverKind::Local),
+            _ => None,
+        }
+    }
+}
+
+impl BankaiClient {
+    /// Sets up the clients from the config at [`config_path`] and the endpoints and keys in the
+    /// environment
+    pub async fn new() -> Result<Self, Error> {
+        let config_path = config_path();
+        let config = BankaiConfig::load(&config_path)?;
+        let store = ArtifactStore::from_config(&config);
+        Ok(Self {
+            client: BeaconRpcClient::new(required_env("BEACON_RPC_URL")?),
+            starknet_client: StarknetClient::new(
+                &required_env("STARKNET_RPC_URL")?,
+                &required_env("STARKNET_ADDRESS")?,
+                &required_env("STARKNET_PRIVATE_KEY")?,
+            )
+            .await?,
+            atlantic_client: AtlanticClient::new(
+                config.atlantic_endpoint.clone(),
+                required_env("ATLANTIC_API_KEY")?,
+                store.clone(),
+            )?,
+            local_prover: LocalStoneProver::new(config.clone()),
+            store,
+            config,
+            config_path,
+        })
+    }
+
+    /// Stores the receipt of the transaction that submitted the update. The update is already
+    /// verified at this point, so failures are only logged.
+    pub async fn store_receipt(&self, id: &str, tx_hash: Felt) {
+        let stored = match self.starknet_client.get_transaction_receipt(tx_hash).await {
+            Ok(receipt) => self
+                .store
+                .put_json(
+                    id,
+                    ArtifactKind::TxReceipt,
+                    &format!("receipt_{:#x}.json", tx_hash),
+                    &receipt,
+                )
+                .map(|_| ()),
+            Err(e) => Err(e.into()),
+        };
+        if let Err(e) = stored {
+            warn!(error = %e, tx_hash = %format!("{:#x}", tx_hash), "Failed to store the receipt");
+        }
+    }
+
+    /// Appends epochs submitted to the contract to the local accumulator and the metrics
+    pub fn record_verified_epochs(
+        &self,
+        epochs: &[ExpectedEpochUpdateOutputs],
+    ) -> Result<(), Error> {
+        metrics().record_proven_epochs(epochs);
+        let mut accumulator = EpochAccumulator::load(&self.config.accumulator_path)?;
+        for epoch in epochs {
+            accumulator.append(epoch.slot, epoch.hash());
+        }
+        accumulator.save(&self.config.accumulator_path)?;
+        info!(
+            root = %format!("{:#x}", accumulator.root()),
+            n_epochs = accumulator.leaves.len(),
+            "Accumulator updated"
+        );
+        Ok(())
+    }
+
+    /// Submits the provable to the selected prover backend and returns the job id
+    #[instrument(
+        skip_all,
+        fields(id = %proof.id(), proof_type = proof.proof_type().name(), job_id = Empty)
+    )]
+    pub async fn submit_proof(
+        &self,
+        prover: ProverKind,
+        proof: &impl Provable,
+    ) -> Result<String, Error> {
+        let job_id = match prover {
+            ProverKind::Atlantic => {
+                let n_steps = CairoRunner::generate_pie(proof, &self.config)?;
+                if let (ProofType::EpochBatch, Some(n_steps)) = (proof.proof_type(), n_steps) {
+                    let mut model = StepModel::load(&self.config.step_model_path)?;
+                    model.record(proof.id(), proof.n_epochs(), n_steps);
+                    model.save(&self.config.step_model_path)?;
+                }
+                self.atlantic_client.submit(proof).await?
+            }
+            ProverKind::Local => self.local_prover.submit(proof).await?,
+        };
+
+        Span::current().record("job_id", job_id.as_str());
+        info!(prover = prover.name(), "Job submitted");
+
+        let mut jobs = JobLog::load(&self.config.job_log_path)?;
+        jobs.record(&job_id, prover.name(), proof);
+        jobs.save(&self.config.job_log_path)?;
+        Ok(job_id)
+    }
+
+    #[instrument(skip(self, prover), fields(prover = prover.name()))]
+    pub async fn proof_status(&self, prover: ProverKind, job_id: &str) -> Result<String, Error> {
+        let status = match prover {
+            ProverKind::Atlantic => self.atlantic_client.status(job_id).await?,
+            ProverKind::Local => self.local_prover.status(job_id).await?,
+        };
+
+        // The first time a job is seen as done, its queue time is recorded
+        if status == "DONE" {
+            let mut jobs = JobLog::load(&self.config.job_log_path)?;
+            if let Some(job) = jobs.complete(job_id) {
+                let queue_time = job.completed_at.unwrap_or_default() - job.submitted_at;
+                metrics()
+                    .prover_queue_time
+                    .with_label_values(&[job.proof_type.name()])
+                    .observe(queue_time as f64);
+                jobs.save(&self.config.job_log_path)?;
+            }
+        }
+        Ok(status)
+    }
+
+    /// Updates the metrics comparing the beacon head with the state of the contract
+    pub async fn update_chain_metrics(&self) -> Result<(), Error> {
+        let head_slot = self.client.get_head_slot().await?;
+        let latest_epoch = self
+            .starknet_client
+            .get_latest_epoch_slot(&self.config)
+            .await?;
+        let latest_committee_id = self
+            .starknet_client
+            .get_latest_committee_id(&self.config)
+            .await?;
+        metrics().update_chain_state(head_slot, latest_epoch, latest_committee_id);
+        Ok(())
+    }
+
+    pub async fn fetch_proof(&self, prover: ProverKind, job_id: &str) -> Result<StarkProof, Error> {
+        match prover {
+            ProverKind::Atlantic => self.atlantic_client.fetch_proof(job_id).await,
+            ProverKind::Local => self.local_prover.fetch_proof(job_id).await,
+        }
+    }
+
+    /// Submits the proof to the wrapper and returns the job id
+    pub async fn wrap_proof(&self, prover: ProverKind, proof: StarkProof) -> Result<String, Error> {
+        match prover {
+            ProverKind::Atlantic => self.atlantic_client.wrap(proof).await,
+            ProverKind::Local => self.local_prover.wrap(proof).await,
+        }
+    }
+
+    pub async fn get_sync_committee_update(
+        &self,
+        mut slot: u64,
+    ) -> Result<SyncCommitteeUpdate, Error> {
+        let mut attempts = 0;
+        const MAX_ATTEMPTS: u8 = 3;
+
+        // Before we start generating the proof, we ensure the slot was not missed
+        let _header = loop {
+            match self.client.get_header(slot).await {
+                Ok(header) => break header,
+                Err(Error::EmptySlotDetected(_)) => {
+                    attempts += 1;
+                    if attempts >= MAX_ATTEMPTS {
+                        return Err(Error::EmptySlotDetected(slot));
+                    }
+                    slot += 1;
+                    warn!(
+                        slot,
+                        attempt = attempts,
+                        "Empty slot detected, fetching the next slot"
+                    );
+                }
+                Err(e) => return Err(e), // Propagate other errors immediately
+            }
+        };
+
+        let proof: SyncCommitteeUpdate = SyncCommitteeUpdate::new(&self.client, slot).await?;
+
+        Ok(proof)
+    }
+
+    pub async fn get_epoch_proof(&self, slot: u64) -> Result<EpochUpdate, Error> {
+        let epoch_proof = EpochUpdate::new(&self.client, slot).await?;
+        Ok(epoch_proof)
+    }
+
+    /// Ensures the local circuit build, the config and the contract agree on the program hash.
+    /// Proofs generated with a mismatching circuit would never verify on-chain.
+    pub async fn ensure_program_hash(&self, proof_type: ProofType) -> Result<(), Error> {
+        ProgramHashReport::new(proof_type, &self.starknet_client, &self.config)
+            .await
+            .ensure_consistent()
+    }
+
+    pub async fn get_contract_initialization_data(
+        &self,
+        slot: u64,
+        config: &BankaiConfig,
+    ) -> Result<ContractInitializationData, Error> {
+        let contract_init = ContractInitializationData::new(&self.client, slot, config).await?;
+        Ok(contract_init)
+    }
+}
@@ -0,0 +1,652 @@
+//! The commands of the CLI. Each one runs against a [`BankaiClient`], or only the local config
+//! for the offline ones, and returns the [`Report`] printed by the binary.
+
+use alloy_primitives::{Address, FixedBytes};
+use tracing::info;
+
+use crate::backfill::{BackfillPlan, EpochLookup};
+use crate::client::{BankaiClient, ProverKind};
+use crate::config::{config_path, BankaiConfig};
+use crate::epoch_batch::{BatchPlan, EpochUpdateBatch};
+use crate::epoch_update::{EpochUpdate, ExpectedEpochUpdateOutputs};
+use crate::execution_header::{ExecutionHeaderProof, ExecutionPayloadFieldsProof};
+use crate::historical_proof::HistoricalBlockProof;
+use crate::input_verifier::ExportedUpdate;
+use crate::output::Report;
+#[cfg(feature = "server")]
+use crate::server;
+use crate::storage_proof::{AccountStorageProof, CommittedExecutionHeader};
+use crate::sync_committee::SyncCommitteeUpdate;
+use crate::traits::{ProofType, Provable};
+use crate::utils::{
+    artifact_store::{ArtifactKind, ArtifactManifest, ArtifactQuery},
+    event_index::EventIndex,
+    execution_rpc::ExecutionRpcClient,
+    mmr::EpochAccumulator,
+    program_hash::ProgramHashReport,
+    proof_archive::ProofArchive,
+    ssz_proof::SszContainer,
+    step_model::BatchStrategy,
+};
+use crate::{ArtifactStore, Error};
+
+/// The containers `ssz-proof` can prove fields of
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum SszContainerKind {
+    BeaconState,
+    BlockBody,
+}
+
+/// Where `storage-proof` gets the execution RPC responses from
+pub enum ExecutionSource {
+    /// The node at `EXECUTION_RPC_URL`, optionally recording the responses into a directory
+    Rpc { record: Option<String> },
+    /// Responses recorded in a directory. The committed block is then read from the stored
+    /// epoch update instead of the contract.
+    Fixtures(String),
+}
+
+/// Runs the offline checks of stored inputs
+pub fn verify_inputs(file: &str) -> Result<Report, Error> {
+    let update = ExportedUpdate::load(file)?;
+    Report::verification(&update.verify())
+}
+
+/// One line per stored update, e.g. `epoch_batch 6709248..6710272 <id> (3 files, 1024 bytes)`
+fn artifact_line(manifest: &ArtifactManifest) -> String {
+    format!(
+        "{} {}..{} {} ({} files, {} bytes)",
+        manifest.proof_type.name(),
+        manifest.first_slot,
+        manifest.slot,
+        manifest.id,
+        manifest.artifacts.len(),
+        manifest.size()
+    )
+}
+
+pub fn list_artifacts(query: &ArtifactQuery) -> Result<Report, Error> {
+    let config = BankaiConfig::load(&config_path())?;
+    let manifests = ArtifactStore::from_config(&config).list(query)?;
+    let mut report = Report::success().field("updates", &manifests)?;
+    for manifest in manifests.iter() {
+        report = report.line(artifact_line(manifest));
+    }
+    Ok(report.line(format!("{} updates stored", manifests.len())))
+}
+
+/// Removes the selected updates, or only the given kinds of their files
+pub fn prune_artifacts(
+    query: &ArtifactQuery,
+    kinds: &[ArtifactKind],
+    dry_run: bool,
+) -> Result<Report, Error> {
+    if query.is_empty() {
+        return Err(Error::InvalidResponse(
+            "Select the updates to prune by type, slot range or batch root".into(),
+        ));
+    }
+    let config = BankaiConfig::load(&config_path())?;
+    let pruned = ArtifactStore::from_config(&config).prune(query, kinds, dry_run)?;
+    let mut report = Report::success()
+        .field("pruned", &pruned)?
+        .field("dry_run", dry_run)?;
+    for manifest in pruned.iter() {
+        report = report.line(artifact_line(manifest));
+    }
+    let freed: usize = pruned
+        .iter()
+        .flat_map(|manifest| manifest.artifacts.iter())
+        .filter(|artifact| kinds.is_empty() || kinds.contains(&artifact.kind))
+        .map(|artifact| artifact.size)
+        .sum();
+    let verb = if dry_run { "Would prune" } else { "Pruned" };
+    Ok(report.line(format!(
+        "{} {} updates, {} bytes",
+        verb,
+        pruned.len(),
+        freed
+    )))
+}
+
+pub async fn execution_header(bankai: &BankaiClient, block: u64) -> Result<Report, Error> {
+    let proof = ExecutionHeaderProof::fetch_proof(&bankai.client, block).await?;
+    Report::success().data(&proof, None)
+}
+
+pub async fn execution_fields_proof(
+    bankai: &BankaiClient,
+    slot: u64,
+    fields: &[String],
+    check_contract: bool,
+    export: Option<String>,
+) -> Result<Report, Error> {
+    let proof = ExecutionPayloadFieldsProof::fetch_proof(&bankai.client, slot, fields).await?;
+    if check_contract {
+        let stored_root = bankai
+            .starknet_client
+            .get_epoch_proof(slot, &bankai.config)
+            .await?
+            .header_root;
+        proof.verify(stored_root)?;
+        info!(%stored_root, "Header root matches the contract");
+    }
+
+    Report::success().data(&proof, export)
+}
+
+pub async fn storage_proof(
+    bankai: &BankaiClient,
+    slot: u64,
+    address: Address,
+    storage_keys: &[FixedBytes<32>],
+    source: ExecutionSource,
+    calldata: bool,
+    export: Option<String>,
+) -> Result<Report, Error> {
+    let (client, committed_header) = match source {
+        ExecutionSource::Fixtures(dir) => {
+            let outputs = bankai
+                .store
+                .load_by_slot::<EpochUpdate>(ProofType::Epoch, slot)?
+                .expected_circuit_outputs;
+            let committed_header = CommittedExecutionHeader {
+                hash: outputs.execution_header_hash,
+                height: outputs.execution_header_height,
+            };
+            (ExecutionRpcClient::from_fixtures(dir), committed_header)
+        }
+        ExecutionSource::Rpc { record } => {
+            let rpc_url = std::env::var("EXECUTION_RPC_URL").map_err(|_| Error::MissingRpcUrl)?;
+            let mut client = ExecutionRpcClient::new(rpc_url);
+            if let Some(dir) = record {
+                client = client.with_recording(dir);
+            }
+            let epoch = bankai
+                .starknet_client
+                .get_epoch_proof(slot, &bankai.config)
+                .await?;
+            if epoch.execution_hash.is_zero() {
+                return Err(Error::InvalidResponse(format!(
+                    "No epoch stored in the contract for slot {}",
+                    slot
+                )));
+            }
+            let committed_header = CommittedExecutionHeader {
+                hash: epoch.execution_hash,
+                height: epoch.execution_height,
+            };
+            (client, committed_header)
+        }
+    };
+
+    let proof =
+        AccountStorageProof::fetch_proof(&client, committed_header, address, storage_keys).await?;
+    if calldata {
+        let calldata: Vec<String> = proof
+            .to_calldata()
+            .iter()
+            .map(|felt| format!("{:#x}", felt))
+            .collect();
+        Report::success().data(&calldata, export)
+    } else {
+        Report::success().data(&proof, export)
+    }
+}
+
+pub async fn historical_proof(
+    bankai: &BankaiClient,
+    target_slot: u64,
+    anchor_slot: u64,
+    check_contract: bool,
+    export: Option<String>,
+) -> Result<Report, Error> {
+    let proof = HistoricalBlockProof::fetch_proof(&bankai.client, target_slot, anchor_slot).await?;
+    if check_contract {
+        let stored_root = bankai
+            .starknet_client
+            .get_epoch_proof(anchor_slot, &bankai.config)
+            .await?
+            .beacon_state_root;
+        proof.verify(stored_root)?;
+        info!(%stored_root, "State root matches the contract");
+    }
+
+    Report::success().data(&proof, export)
+}
+
+pub fn accumulator_proof(
+    bankai: &BankaiClient,
+    slot: u64,
+    export: Option<String>,
+) -> Result<Report, Error> {
+    let accumulator = EpochAccumulator::load(&bankai.config.accumulator_path)?;
+    let proof = accumulator.prove(slot)?;
+
+    Report::success().data(&proof, export)
+}
+
+/// Proves the fields of the container, or lists them if none are given
+pub async fn ssz_proof(
+    bankai: &BankaiClient,
+    slot: u64,
+    container: SszContainerKind,
+    fields: &[String],
+    export: Option<String>,
+) -> Result<Report, Error> {
+    let tree = match container {
+        SszContainerKind::BeaconState => bankai.client.get_beacon_state(slot).await?.ssz_tree()?,
+        SszContainerKind::BlockBody => bankai.client.get_block_body(slot).await?.ssz_tree()?,
+    };
+
+    if fields.is_empty() {
+        Ok(Report::success()
+            .field("fields", tree.fields())?
+            .line(format!("Fields: {}", tree.fields().join(", "))))
+    } else if fields.len() == 1 {
+        let proof = tree.prove_field(&fields[0])?;
+        proof.verify()?;
+        Report::success().data(&proof, export)
+    } else {
+        let paths: Vec<&str> = fields.iter().map(String::as_str).collect();
+        let proof = tree.prove_fields(&paths)?;
+        proof.verify()?;
+        Report::success().data(&proof, export)
+    }
+}
+
+pub async fn committee_update(
+    bankai: &BankaiClient,
+    slot: u64,
+    export: Option<String>,
+) -> Result<Report, Error> {
+    info!(slot, "Generating sync committee update");
+    let proof = bankai.get_sync_committee_update(slot).await?;
+    Report::success().data(&proof, export)
+}
+
+pub async fn epoch_update(
+    bankai: &BankaiClient,
+    slot: u64,
+    export: Option<String>,
+) -> Result<Report, Error> {
+    info!(slot, "Generating epoch update");
+    let proof = bankai.get_epoch_proof(slot).await?;
+    Report::success().data(&proof, export)
+}
+
+pub async fn contract_init(
+    bankai: &BankaiClient,
+    slot: u64,
+    export: Option<String>,
+) -> Result<Report, Error> {
+    info!(slot, "Generating contract initialization data");
+    let contract_init = bankai
+        .get_contract_initialization_data(slot, &bankai.config)
+        .await?;
+    Report::success().data(&contract_init, export)
+}
+
+/// Deploys the contract initialized at the slot and saves its address to the config
+pub async fn deploy_contract(bankai: &mut BankaiClient, slot: u64) -> Result<Report, Error> {
+    let contract_init = bankai
+        .get_contract_initialization_data(slot, &bankai.config)
+        .await?;
+    let deployment = bankai
+        .starknet_client
+        .deploy_contract(contract_init, &bankai.config)
+        .await?;
+    bankai.config.contract_class_hash = deployment.class_hash;
+    bankai.config.contract_address = deployment.contract_address;
+    bankai.config.save(&bankai.config_path)?;
+    info!(path = %bankai.config_path, "Config updated");
+
+    Ok(Report::success()
+        .field("class_hash", format!("{:#x}", deployment.class_hash))?
+        .field(
+            "contract_address",
+            format!("{:#x}", deployment.contract_address),
+        )?
+        .field(
+            "declare_tx",
+            deployment.declare_tx.map(|tx| format!("{:#x}", tx)),
+        )?
+        .field(
+            "deploy_tx",
+            deployment.deploy_tx.map(|tx| format!("{:#x}", tx)),
+        )?
+        .line(format!("Class hash: {:#x}", deployment.class_hash))
+        .line(format!(
+            "Contract address: {:#x}",
+            deployment.contract_address
+        )))
+}
+
+pub async fn check_batch_status(
+    bankai: &BankaiClient,
+    prover: ProverKind,
+    batch_id: String,
+) -> Result<Report, Error> {
+    let status = bankai.proof_status(prover, batch_id.as_str()).await?;
+    match status.as_str() {
+        "DONE" => Ok(Report::success()
+            .field("job_id", &batch_id)?
+            .field("job_status", &status)?
+            .line(format!("Batch Status: {}", status))),
+        "FAILED" => Err(Error::JobFailed(batch_id)),
+        _ => Ok(Report::not_ready(&batch_id, &status)),
+    }
+}
+
+pub async fn prove_next_committee(
+    bankai: &BankaiClient,
+    prover: ProverKind,
+) -> Result<Report, Error> {
+    let latest_committee_id = bankai
+        .starknet_client
+        .get_latest_committee_id(&bankai.config)
+        .await?;
+    let lowest_committee_update_slot = latest_committee_id.saturating_mul(0x2000);
+    info!(%lowest_committee_update_slot, "Min slot required");
+    let latest_epoch = bankai
+        .starknet_client
+        .get_latest_epoch_slot(&bankai.config)
+        .await?;
+    info!(%latest_epoch, "Latest epoch");
+    if latest_epoch < lowest_committee_update_slot {
+        return Err(Error::RequiresNewerEpoch(latest_epoch));
+    }
+    bankai.ensure_program_hash(ProofType::SyncCommittee).await?;
+    let update = bankai.get_sync_committee_update(latest_epoch).await?;
+    let batch_id = bankai.submit_proof(prover, &update).await?;
+    Report::submitted(&batch_id, &update)
+}
+
+pub async fn prove_next_epoch(bankai: &BankaiClient, prover: ProverKind) -> Result<Report, Error> {
+    let latest_epoch = bankai
+        .starknet_client
+        .get_latest_epoch_slot(&bankai.config)
+        .await?;
+    info!(%latest_epoch, "Latest epoch");
+    // make sure next_epoch % 32 == 0
+    let next_epoch = (latest_epoch / 32) * 32 + 32;
+    bankai.ensure_program_hash(ProofType::Epoch).await?;
+    info!(next_epoch, "Fetching inputs for epoch");
+    let proof = bankai.get_epoch_proof(next_epoch).await?;
+    let batch_id = bankai.submit_proof(prover, &proof).await?;
+    Report::submitted(&batch_id, &proof)
+}
+
+/// Proves the next batch of epochs, or the committee update the batch is waiting for
+pub async fn prove_next_epoch_batch(
+    bankai: &BankaiClient,
+    prover: ProverKind,
+    strategy: BatchStrategy,
+) -> Result<Report, Error> {
+    match BatchPlan::next(&bankai.starknet_client, &bankai.config, strategy).await? {
+        BatchPlan::Epochs {
+            committee_hash,
+            start_slot,
+            end_slot,
+        } => {
+            bankai.ensure_program_hash(ProofType::EpochBatch).await?;
+            let proof =
+                EpochUpdateBatch::new(&bankai.client, committee_hash, start_slot, end_slot).await?;
+            let batch_id = bankai.submit_proof(prover, &proof).await?;
+            Report::submitted(&batch_id, &proof)?
+                .field("start_slot", start_slot)?
+                .field("end_slot", end_slot)
+        }
+        BatchPlan::CommitteeUpdate { committee_id, slot } => {
+            info!(
+                committee_id,
+                "Committee is not stored yet, proving the committee update first"
+            );
+            bankai.ensure_program_hash(ProofType::SyncCommittee).await?;
+            let update = bankai.get_sync_committee_update(slot).await?;
+            let batch_id = bankai.submit_proof(prover, &update).await?;
+            Ok(Report::submitted(&batch_id, &update)?.line(format!(
+                "Verify with: verify-committee --batch-id {} --slot {}",
+                batch_id, update.circuit_inputs.beacon_slot
+            )))
+        }
+    }
+}
+
+pub async fn verify_epoch(
+    bankai: &BankaiClient,
+    prover: ProverKind,
+    batch_id: String,
+    slot: u64,
+) -> Result<Report, Error> {
+    let status = bankai.proof_status(prover, batch_id.as_str()).await?;
+    match status.as_str() {
+        "DONE" => {
+            let update = bankai
+                .store
+                .load_by_slot::<EpochUpdate>(ProofType::Epoch, slot)?;
+            let id = update.id();
+            let outputs = update.expected_circuit_outputs;
+            let tx_hash = bankai
+                .starknet_client
+                .submit_update(outputs.clone(), &bankai.config)
+                .await?;
+            info!("Successfully submitted epoch update");
+            bankai.store_receipt(&id, tx_hash).await;
+            bankai.record_verified_epochs(&[outputs])?;
+            Report::verified(&batch_id, slot, tx_hash)
+        }
+        "FAILED" => Err(Error::JobFailed(batch_id)),
+        _ => Ok(Report::not_ready(&batch_id, &status)),
+    }
+}
+
+pub async fn verify_epoch_batch(
+    bankai: &BankaiClient,
+    prover: ProverKind,
+    batch_id: String,
+    slot: u64,
+) -> Result<Report, Error> {
+    let status = bankai.proof_status(prover, batch_id.as_str()).await?;
+    match status.as_str() {
+        "DONE" => {
+            let update = bankai
+                .store
+                .load_by_slot::<EpochUpdateBatch>(ProofType::EpochBatch, slot)?;
+            let id = update.id();
+            let epochs: Vec<ExpectedEpochUpdateOutputs> = update
+                .circuit_inputs
+                .epochs
+                .iter()
+                .map(|epoch| epoch.expected_circuit_outputs.clone())
+                .collect();
+            let tx_hash = bankai
+                .starknet_client
+                .submit_update(update.expected_circuit_outputs, &bankai.config)
+                .await?;
+            info!("Successfully submitted epoch update");
+            bankai.store_receipt(&id, tx_hash).await;
+            bankai.record_verified_epochs(&epochs)?;
+            Report::verified(&batch_id, slot, tx_hash)?
+                .field("start_slot", epochs.first().map(|epoch| epoch.slot))?
+                .field("end_slot", epochs.last().map(|epoch| epoch.slot))
+        }
+        "FAILED" => Err(Error::JobFailed(batch_id)),
+        _ => Ok(Report::not_ready(&batch_id, &status)),
+    }
+}
+
+pub async fn verify_committee(
+    bankai: &BankaiClient,
+    prover: ProverKind,
+    batch_id: String,
+    slot: u64,
+) -> Result<Report, Error> {
+    let status = bankai.proof_status(prover, batch_id.as_str()).await?;
+    match status.as_str() {
+        "DONE" => {
+            let update = bankai
+                .store
+                .load_by_slot::<SyncCommitteeUpdate>(ProofType::SyncCommittee, slot)?;
+            let id = update.id();
+            let tx_hash = bankai
+                .starknet_client
+                .submit_update(update.expected_circuit_outputs, &bankai.config)
+                .await?;
+            info!("Successfully submitted sync committee update");
+            bankai.store_receipt(&id, tx_hash).await;
+            Report::verified(&batch_id, slot, tx_hash)
+        }
+        "FAILED" => Err(Error::JobFailed(batch_id)),
+        _ => Ok(Report::not_ready(&batch_id, &status)),
+    }
+}
+
+/// Compares the program hashes of the local circuits with the config and the contract
+pub async fn doctor(bankai: &BankaiClient) -> Result<Report, Error> {
+    let mut report = Report::success();
+    for proof_type in [
+        ProofType::Epoch,
+        ProofType::EpochBatch,
+        ProofType::SyncCommittee,
+    ] {
+        let hashes =
+            ProgramHashReport::new(proof_type, &bankai.starknet_client, &bankai.config).await;
+        report = report
+            .field(proof_type.name(), hashes.is_consistent())?
+            .line(hashes.to_string());
+    }
+    Ok(report)
+}
+
+/// Plans the missing epochs between two slots and, unless it is a dry run, submits their
+/// batches
+pub async fn backfill(
+    bankai: &BankaiClient,
+    prover: ProverKind,
+    from: u64,
+    to: u64,
+    lookup: EpochLookup,
+    strategy: BatchStrategy,
+    dry_run: bool,
+) -> Result<Report, Error> {
+    if from > to {
+        return Err(Error::InvalidResponse(format!(
+            "Invalid range: {} is after {}",
+            from, to
+        )));
+    }
+    let plan = BackfillPlan::new(bankai, from, to, lookup, strategy).await?;
+    let mut report = Report::success().field("plan", &plan)?;
+    for line in plan.lines() {
+        report = report.line(line);
+    }
+    if !dry_run {
+        let submitted = plan.run(bankai, prover).await?;
+        for (batch_id, slot) in submitted.iter() {
+            report = report.line(format!(
+                "Verify with: verify-epoch-batch --batch-id {} --slot {}",
+                batch_id, slot
+            ));
+        }
+        report = report.field("submitted", submitted)?;
+    }
+    Ok(report)
+}
+
+#[cfg(feature = "server")]
+pub async fn serve(bankai: BankaiClient, addr: &str) -> Result<Report, Error> {
+    server::serve(bankai, addr).await?;
+    Ok(Report::success())
+}
+
+/// Syncs the local index of contract events
+pub async fn index_events(bankai: &BankaiClient) -> Result<Report, Error> {
+    let mut index = EventIndex::load(&bankai.config.event_index_path)?;
+    let indexed = bankai
+        .starknet_client
+        .index_events(&mut index, &bankai.config)
+        .await?;
+    index.save(&bankai.config.event_index_path)?;
+    Ok(Report::success()
+        .field("indexed", indexed)?
+        .field("committees", index.committees.len())?
+        .field("epochs", index.epochs.len())?
+        .field("batches", index.batches.len())?
+        .line(format!("Indexed {} new events", indexed))
+        .line(format!(
+            "Committees: {}, Epochs: {}, Batches: {}",
+            index.committees.len(),
+            index.epochs.len(),
+            index.batches.len()
+        )))
+}
+
+/// Wraps the proof of a finished job, or the proof archived in `archive_dir`
+pub async fn submit_wrapped_proof(
+    bankai: &BankaiClient,
+    prover: ProverKind,
+    batch_id: String,
+    archive_dir: Option<String>,
+) -> Result<Report, Error> {
+    if let Some(dir) = archive_dir {
+        let archive = ProofArchive::load(&dir, &batch_id)?;
+        let proof = archive.load_proof()?;
+        let wrapper_id = bankai.wrap_proof(prover, proof).await?;
+        return Ok(Report::success()
+            .field("job_id", &wrapper_id)?
+            .line(format!("Batch Submitted: {}", wrapper_id)));
+    }
+
+    let status = bankai.proof_status(prover, batch_id.as_str()).await?;
+    match status.as_str() {
+        "DONE" => {
+            let proof = bankai.fetch_proof(prover, batch_id.as_str()).await?;
+            let wrapper_id = bankai.wrap_proof(prover, proof).await?;
+            Ok(Report::success()
+                .field("job_id", &wrapper_id)?
+                .field("wrapped_job_id", &batch_id)?
+                .line(format!("Batch Submitted: {}", wrapper_id)))
+        }
+        "FAILED" => Err(Error::JobFailed(batch_id)),
+        _ => Ok(Report::not_ready(&batch_id, &status)),
+    }
+}
+
+/// Downloads the artifacts of an Atlantic query into the directory of the stored update
+pub async fn archive_proof(
+    bankai: &BankaiClient,
+    batch_id: &str,
+    wrapper_id: Option<&str>,
+    proof_type: ProofType,
+    slot: u64,
+) -> Result<Report, Error> {
+    let id = bankai.store.find_by_slot(proof_type, slot)?.id;
+    let dir = bankai.store.dir(&id);
+    let archive = bankai
+        .atlantic_client
+        .archive_query(batch_id, wrapper_id, &dir)
+        .await?;
+    let manifest = ProofArchive::manifest_path(&dir, batch_id);
+    bankai.store.add_file(
+        &id,
+        ArtifactKind::Metadata,
+        &format!("archive_{}.json", batch_id),
+    )?;
+    let mut report = Report::success();
+    for artifact in archive.artifacts.iter() {
+        let name = artifact.path.trim_start_matches(&format!("{}/", dir));
+        bankai
+            .store
+            .add_file(&id, archive.artifact_kind(artifact), name)?;
+        report = report.line(format!(
+            "{} ({} bytes, sha256 {})",
+            artifact.path, artifact.size, artifact.sha256
+        ));
+    }
+    Ok(report
+        .field("artifacts", &archive.artifacts)?
+        .field("manifest", &manifest)?
+        .line(format!("Archive manifest: {}", manifest)))
+}
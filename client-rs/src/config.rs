@@ -1,6 +1,27 @@
-use starknet::core::types::Felt;
+use std::path::Path;
+use std::{env, fs};
 
-#[derive(Clone)]
+use serde::{Deserialize, Serialize};
+use starknet_crypto::Felt;
+
+use crate::traits::ProofType;
+use crate::Error;
+
+/// Default location of the config file. Values missing from the file fall back to the defaults.
+pub const DEFAULT_CONFIG_PATH: &str = "bankai_config.json";
+
+/// Path of the config, `BANKAI_CONFIG_PATH` or the default path
+pub fn config_path() -> String {
+    env::var("BANKAI_CONFIG_PATH").unwrap_or_else(|_| DEFAULT_CONFIG_PATH.to_string())
+}
+
+/// Reads a variable that has to be set in the environment
+pub fn required_env(name: &'static str) -> Result<String, Error> {
+    env::var(name).map_err(|_| Error::MissingEnvVar(name))
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct BankaiConfig {
     pub contract_class_hash: Felt,
     pub contract_address: Felt,
@@ -8,10 +29,32 @@ pub struct BankaiConfig {
     pub epoch_update_program_hash: Felt,
     pub epoch_batch_program_hash: Felt,
     pub contract_path: String,
+    pub contract_casm_path: String,
+    pub deployment_salt: Felt,
     pub epoch_circuit_path: String,
     pub epoch_batch_circuit_path: String,
     pub committee_circuit_path: String,
     pub atlantic_endpoint: String,
+    pub event_index_path: String,
+    pub local_prover_dir: String,
+    pub dynamic_params_path: String,
+    pub stone_prover_config_path: String,
+    pub stone_prover_params_path: String,
+    pub proof_wrapper_program_path: String,
+    /// Measured step counts of epoch batches, see `StepModel`
+    pub step_model_path: String,
+    /// Maximum steps of a batch accepted by the prover
+    pub max_batch_steps: u64,
+    /// Step budget of a batch when favouring latency
+    pub latency_batch_steps: u64,
+    /// Peaks and leaves of the accumulator over all verified epochs
+    pub accumulator_path: String,
+    /// Proofs submitted to the prover backends, see `JobLog`
+    pub job_log_path: String,
+    /// Root of the `ArtifactStore`
+    pub artifact_store_dir: String,
+    /// Network the artifacts are stored for. The circuits verify Sepolia signatures.
+    pub network: String,
 }
 
 impl Default for BankaiConfig {
@@ -39,10 +82,62 @@ impl Default for BankaiConfig {
             .unwrap(),
             contract_path: "../contract/target/release/bankai_BankaiContract.contract_class.json"
                 .to_string(),
+            contract_casm_path:
+                "../contract/target/release/bankai_BankaiContract.compiled_contract_class.json"
+                    .to_string(),
+            deployment_salt: Felt::from(1337),
             epoch_circuit_path: "../cairo/build/epoch_update.json".to_string(),
             epoch_batch_circuit_path: "../cairo/build/epoch_batch.json".to_string(),
             committee_circuit_path: "../cairo/build/committee_update.json".to_string(),
             atlantic_endpoint: "https://atlantic.api.herodotus.cloud".to_string(),
+            event_index_path: "batches/event_index.json".to_string(),
+            local_prover_dir: "batches/local_prover".to_string(),
+            dynamic_params_path: "../dynamic_params.json".to_string(),
+            stone_prover_config_path: "../stone/cpu_air_prover_config.json".to_string(),
+            stone_prover_params_path: "../stone/cpu_air_params.json".to_string(),
+            proof_wrapper_program_path: "../cairo/build/proof_wrapper.json".to_string(),
+            step_model_path: "batches/step_model.json".to_string(),
+            max_batch_steps: 1 << 24,
+            latency_batch_steps: 1 << 22,
+            accumulator_path: "batches/epoch_accumulator.json".to_string(),
+            job_log_path: "batches/jobs.json".to_string(),
+            artifact_store_dir: "batches/artifacts".to_string(),
+            network: "sepolia".to_string(),
+        }
+    }
+}
+
+impl BankaiConfig {
+    /// Loads the config file, falling back to the defaults if it doesn't exist
+    pub fn load(path: &str) -> Result<Self, Error> {
+        if !Path::new(path).exists() {
+            return Ok(Self::default());
+        }
+        let json = fs::read_to_string(path).map_err(Error::IoError)?;
+        serde_json::from_str(&json).map_err(|e| Error::DeserializeError(e.to_string()))
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), Error> {
+        let json = serde_json::to_string_pretty(&self)
+            .map_err(|e| Error::DeserializeError(e.to_string()))?;
+        fs::write(path, json).map_err(Error::IoError)
+    }
+
+    /// Path of the compiled circuit used for the given proof type
+    pub fn circuit_path(&self, proof_type: ProofType) -> &str {
+        match proof_type {
+            ProofType::Epoch => &self.epoch_circuit_path,
+            ProofType::SyncCommittee => &self.committee_circuit_path,
+            ProofType::EpochBatch => &self.epoch_batch_circuit_path,
+        }
+    }
+
+    /// Expected program hash of the circuit used for the given proof type
+    pub fn program_hash(&self, proof_type: ProofType) -> Felt {
+        match proof_type {
+            ProofType::Epoch => self.epoch_update_program_hash,
+            ProofType::SyncCommittee => self.committee_update_program_hash,
+            ProofType::EpochBatch => self.epoch_batch_program_hash,
         }
     }
 }
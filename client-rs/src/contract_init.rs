@@ -5,11 +5,11 @@ use starknet::core::types::Felt;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ContractInitializationData {
-    pub(crate) committee_id: u64,
-    pub(crate) committee_hash: FixedBytes<32>,
-    pub(crate) committee_update_program_hash: Felt,
-    pub(crate) epoch_update_program_hash: Felt,
-    pub(crate) epoch_batch_program_hash: Felt,
+    pub committee_id: u64,
+    pub committee_hash: FixedBytes<32>,
+    pub committee_update_program_hash: Felt,
+    pub epoch_update_program_hash: Felt,
+    pub epoch_batch_program_hash: Felt,
 }
 
 impl ContractInitializationData {
@@ -1,19 +1,30 @@
 use crate::epoch_update::{EpochUpdate, ExpectedEpochUpdateOutputs};
-use crate::traits::{Provable, Submittable};
-use crate::utils::hashing::get_committee_hash;
+use crate::traits::{ProofType, Provable, Submittable};
+use crate::utils::artifact_store::{ArtifactQuery, ArtifactStore};
+use crate::utils::hashing::selector;
 use crate::utils::merkle::poseidon::{compute_paths, compute_root, hash_path};
-use crate::{BankaiClient, Error};
+use crate::utils::rpc::BeaconRpcClient;
+#[cfg(feature = "starknet-client")]
+use crate::utils::starknet_client::StarknetClient;
+#[cfg(feature = "starknet-client")]
+use crate::utils::step_model::{BatchStrategy, StepModel};
+#[cfg(feature = "starknet-client")]
+use crate::BankaiConfig;
+use crate::Error;
 use alloy_primitives::FixedBytes;
 use hex;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
-use starknet::macros::selector;
 use starknet_crypto::Felt;
-use std::fs;
+#[cfg(feature = "starknet-client")]
+use tracing::debug;
+use tracing::info;
 
-const TARGET_BATCH_SIZE: u64 = 32;
 const SLOTS_PER_EPOCH: u64 = 32;
+const SLOTS_PER_PERIOD: u64 = 0x2000;
 
+/// A batch of epoch updates signed by the same committee, committed to by a Poseidon root of
+/// their outputs
 #[derive(Debug, Serialize, Deserialize)]
 pub struct EpochUpdateBatch {
     pub circuit_inputs: EpochUpdateBatchInputs,
@@ -33,40 +44,155 @@ pub struct ExpectedEpochBatchOutputs {
     pub latest_batch_output: ExpectedEpochUpdateOutputs,
 }
 
-impl EpochUpdateBatch {
-    pub(crate) async fn new(bankai: &BankaiClient) -> Result<EpochUpdateBatch, Error> {
-        let (start_slot, mut end_slot) = bankai
-            .starknet_client
-            .get_batching_range(&bankai.config)
-            .await?;
-        println!("Slots in Term: Start {}, End {}", start_slot, end_slot);
-        let epoch_gap = (end_slot - start_slot) / SLOTS_PER_EPOCH;
-        println!("Available Epochs: {}", epoch_gap);
+/// The data to decommit an epoch from a verified batch, i.e. the arguments of
+/// `decommit_batched_epoch`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EpochDecommitment {
+    pub batch_root: Felt,
+    pub merkle_index: usize,
+    pub merkle_path: Vec<Felt>,
+    pub epoch: ExpectedEpochUpdateOutputs,
+}
+
+impl EpochDecommitment {
+    pub fn to_calldata(&self) -> Vec<Felt> {
+        let mut calldata = vec![
+            self.batch_root,
+            Felt::from(self.merkle_index),
+            Felt::from(self.merkle_path.len()),
+        ];
+        calldata.extend(self.merkle_path.iter().copied());
+        calldata.extend(self.epoch.to_calldata());
+        calldata
+    }
+}
+
+/// The next update that advances the contract
+#[derive(Debug)]
+pub enum BatchPlan {
+    /// Prove the epochs in `[start_slot, end_slot)`, signed by the stored committee
+    Epochs {
+        committee_hash: FixedBytes<32>,
+        start_slot: u64,
+        end_slot: u64,
+    },
+    /// The committee signing the next epoch is not stored yet. It must be proven with a
+    /// committee update from the latest verified epoch first.
+    CommitteeUpdate { committee_id: u64, slot: u64 },
+}
+
+#[cfg(feature = "starknet-client")]
+impl BatchPlan {
+    /// Plans the next update from the latest epoch and the committees stored in the contract
+    pub async fn next(
+        starknet_client: &StarknetClient,
+        config: &BankaiConfig,
+        strategy: BatchStrategy,
+    ) -> Result<BatchPlan, Error> {
+        let (start_slot, terms_end) = starknet_client.get_batching_range(config).await?;
+        debug!(start_slot, terms_end, "Slots in term");
 
-        // if the gap is smaller then x2 the target size, use the entire gap
-        if epoch_gap >= TARGET_BATCH_SIZE * 2 {
-            end_slot = start_slot + TARGET_BATCH_SIZE * SLOTS_PER_EPOCH;
+        let committee_id = start_slot / SLOTS_PER_PERIOD;
+        let committee_hash = starknet_client
+            .get_committee_hash(committee_id, config)
+            .await?;
+        if committee_hash.is_zero() {
+            // The state of the latest epoch only commits to the committee of the next term,
+            // so we can only recover when the batch is about to cross into that term.
+            if start_slot % SLOTS_PER_PERIOD != 0 {
+                return Err(Error::MissingCommittee(committee_id));
+            }
+            return Ok(BatchPlan::CommitteeUpdate {
+                committee_id,
+                slot: start_slot - SLOTS_PER_EPOCH,
+            });
         }
 
-        println!("Selected Slots: Start {}, End {}", start_slot, end_slot);
-        println!("Epoch Count: {}", (end_slot - start_slot) / SLOTS_PER_EPOCH);
+        let epoch_gap = (terms_end - start_slot) / SLOTS_PER_EPOCH;
+        debug!(epoch_gap, "Available epochs");
+
+        let model = StepModel::load(&config.step_model_path)?;
+        let batch_size = model
+            .batch_size(strategy.step_budget(config))
+            .min(epoch_gap);
+        info!(
+            batch_size,
+            estimated_steps = model.estimate(batch_size),
+            samples = model.samples.len(),
+            "Planned batch"
+        );
+        let end_slot = start_slot + batch_size * SLOTS_PER_EPOCH;
+
+        Ok(BatchPlan::Epochs {
+            committee_hash,
+            start_slot,
+            end_slot,
+        })
+    }
+}
+
+impl EpochUpdateBatch {
+    /// Fetches the epochs in `[start_slot, end_slot)` and ensures they were signed by the
+    /// given (stored) committee
+    pub async fn new(
+        client: &BeaconRpcClient,
+        committee_hash: FixedBytes<32>,
+        start_slot: u64,
+        end_slot: u64,
+    ) -> Result<EpochUpdateBatch, Error> {
+        info!(
+            start_slot,
+            end_slot,
+            n_epochs = (end_slot - start_slot) / SLOTS_PER_EPOCH,
+            "Fetching epochs of the batch"
+        );
 
         let mut epochs = vec![];
 
         // Fetch epochs sequentially from start_slot to end_slot, incrementing by 32 each time
         let mut current_slot = start_slot;
         while current_slot < end_slot {
-            let epoch_update = EpochUpdate::new(&bankai.client, current_slot).await?;
+            let epoch_update = EpochUpdate::new(client, current_slot).await?;
             epochs.push(epoch_update);
             current_slot += 32;
         }
 
+        let batch = Self::from_epochs(epochs)?;
+        if batch.circuit_inputs.committee_hash != committee_hash {
+            return Err(Error::CommitteeHashMismatch(
+                start_slot / SLOTS_PER_PERIOD,
+                committee_hash,
+                batch.circuit_inputs.committee_hash,
+            ));
+        }
+        Ok(batch)
+    }
+
+    /// Builds a batch from epoch updates signed by the same committee
+    pub fn from_epochs(epochs: Vec<EpochUpdate>) -> Result<EpochUpdateBatch, Error> {
+        let committee_hash = epochs
+            .first()
+            .ok_or(Error::EmptyBatch)?
+            .expected_circuit_outputs
+            .committee_hash;
+        // The circuit verifies all signatures against a single committee
+        for epoch in epochs.iter() {
+            let outputs = &epoch.expected_circuit_outputs;
+            if outputs.committee_hash != committee_hash {
+                return Err(Error::MixedCommitteeBatch(
+                    outputs.slot,
+                    committee_hash,
+                    outputs.committee_hash,
+                ));
+            }
+        }
+
         let circuit_inputs = EpochUpdateBatchInputs {
-            committee_hash: get_committee_hash(epochs[0].circuit_inputs.aggregate_pub.0),
+            committee_hash,
             epochs,
         };
 
-        let expected_circuit_outputs = ExpectedEpochBatchOutputs::from_inputs(&circuit_inputs);
+        let expected_circuit_outputs = ExpectedEpochBatchOutputs::from_inputs(&circuit_inputs)?;
 
         let epoch_hashes = circuit_inputs
             .epochs
@@ -74,13 +200,13 @@ impl EpochUpdateBatch {
             .map(|epoch| epoch.expected_circuit_outputs.hash())
             .collect::<Vec<Felt>>();
 
-        let (root, paths) = compute_paths(epoch_hashes.clone());
+        let (root, paths) = compute_paths(epoch_hashes.clone())?;
 
         // Verify each path matches the root
         for (index, path) in paths.iter().enumerate() {
             let computed_root = hash_path(epoch_hashes[index], path, index);
             if computed_root != root {
-                panic!("Path {} does not match root", index);
+                return Err(Error::InvalidBatchPath(index));
             }
         }
 
@@ -92,6 +218,43 @@ impl EpochUpdateBatch {
 
         Ok(batch)
     }
+
+    /// Loads the stored batch that contains the epoch at `slot`
+    pub fn find_containing(store: &ArtifactStore, slot: u64) -> Result<EpochUpdateBatch, Error> {
+        let batches = store.list(&ArtifactQuery::containing(ProofType::EpochBatch, slot))?;
+        match batches.len() {
+            0 => Err(Error::EpochNotBatched(slot)),
+            1 => store.load_inputs(&batches[0].id),
+            _ => Err(Error::AmbiguousArtifact(
+                format!("epoch at slot {}", slot),
+                batches.into_iter().map(|batch| batch.id).collect(),
+            )),
+        }
+    }
+
+    /// Returns the merkle path of the epoch at `slot` to the batch root
+    pub fn decommitment(&self, slot: u64) -> Result<EpochDecommitment, Error> {
+        let merkle_index = self
+            .circuit_inputs
+            .epochs
+            .iter()
+            .position(|epoch| epoch.expected_circuit_outputs.slot == slot)
+            .ok_or(Error::EpochNotBatched(slot))?;
+        let merkle_path = self
+            .merkle_paths
+            .get(merkle_index)
+            .ok_or(Error::InvalidBatchPath(merkle_index))?
+            .clone();
+
+        Ok(EpochDecommitment {
+            batch_root: self.expected_circuit_outputs.batch_root,
+            merkle_index,
+            merkle_path,
+            epoch: self.circuit_inputs.epochs[merkle_index]
+                .expected_circuit_outputs
+                .clone(),
+        })
+    }
 }
 
 impl Provable for EpochUpdateBatch {
@@ -102,89 +265,33 @@ impl Provable for EpochUpdateBatch {
         hex::encode(hasher.finalize().as_slice())
     }
 
-    fn export(&self) -> Result<String, Error> {
-        let json = serde_json::to_string_pretty(&self).unwrap();
-        let first_slot = self
-            .circuit_inputs
-            .epochs
-            .first()
-            .unwrap()
-            .circuit_inputs
-            .header
-            .slot;
-        let last_slot = self
-            .circuit_inputs
-            .epochs
-            .last()
-            .unwrap()
-            .circuit_inputs
-            .header
-            .slot;
-        let dir_path = format!("batches/epoch_batch/{}_to_{}", first_slot, last_slot);
-        fs::create_dir_all(dir_path.clone()).map_err(Error::IoError)?;
-        let path = format!(
-            "{}/input_batch_{}_to_{}.json",
-            dir_path, first_slot, last_slot
-        );
-        fs::write(path.clone(), json).map_err(Error::IoError)?;
-        Ok(path)
-    }
-
-    fn from_json<T>(slot: u64) -> Result<T, Error>
-    where
-        T: serde::de::DeserializeOwned,
-    {
-        // Pattern match for files like: batches/epoch_batch/6709248_to_6710272/input_batch_6709248_to_6710272.json
-        let path = format!(
-            "batches/epoch_batch/*_to_{}/input_batch_*_to_{}.json",
-            slot, slot
-        );
-        let glob_pattern = glob::glob(&path)
-            .map_err(|e| Error::IoError(std::io::Error::new(std::io::ErrorKind::Other, e)))?;
-
-        // Take the first matching file
-        let path = glob_pattern.take(1).next().ok_or_else(|| {
-            Error::IoError(std::io::Error::new(
-                std::io::ErrorKind::NotFound,
-                "No matching file found",
-            ))
-        })?;
-
-        let json = fs::read_to_string(path.unwrap()).map_err(Error::IoError)?;
-        serde_json::from_str(&json).map_err(|e| Error::DeserializeError(e.to_string()))
-    }
-
     fn proof_type(&self) -> crate::traits::ProofType {
         crate::traits::ProofType::EpochBatch
     }
 
-    fn pie_path(&self) -> String {
-        let first_slot = self
-            .circuit_inputs
+    fn slot(&self) -> u64 {
+        self.expected_circuit_outputs.latest_batch_output.slot
+    }
+
+    fn first_slot(&self) -> u64 {
+        self.circuit_inputs
             .epochs
             .first()
-            .unwrap()
-            .circuit_inputs
-            .header
-            .slot;
-        let last_slot = self
-            .circuit_inputs
-            .epochs
-            .last()
-            .unwrap()
-            .circuit_inputs
-            .header
-            .slot;
-        format!(
-            "batches/epoch_batch/{}_to_{}/pie_batch_{}_to_{}.zip",
-            first_slot, last_slot, first_slot, last_slot
-        )
+            .map_or(self.slot(), |epoch| epoch.circuit_inputs.header.slot)
+    }
+
+    fn batch_root(&self) -> Option<Felt> {
+        Some(self.expected_circuit_outputs.batch_root)
+    }
+
+    fn n_epochs(&self) -> u64 {
+        self.circuit_inputs.epochs.len() as u64
     }
 }
 
 impl Submittable<EpochUpdateBatchInputs> for ExpectedEpochBatchOutputs {
     fn get_contract_selector(&self) -> Felt {
-        selector!("verify_epoch_batch")
+        selector("verify_epoch_batch")
     }
 
     fn to_calldata(&self) -> Vec<Felt> {
@@ -224,25 +331,25 @@ impl Submittable<EpochUpdateBatchInputs> for ExpectedEpochBatchOutputs {
         ]
     }
 
-    fn from_inputs(circuit_inputs: &EpochUpdateBatchInputs) -> Self {
+    fn from_inputs(circuit_inputs: &EpochUpdateBatchInputs) -> Result<Self, Error> {
+        let last_epoch_output = circuit_inputs
+            .epochs
+            .last()
+            .ok_or(Error::EmptyBatch)?
+            .expected_circuit_outputs
+            .clone();
+
         let epoch_hashes = circuit_inputs
             .epochs
             .iter()
             .map(|epoch| epoch.expected_circuit_outputs.hash())
             .collect::<Vec<Felt>>();
 
-        let batch_root = compute_root(epoch_hashes.clone());
-
-        let last_epoch_output = circuit_inputs
-            .epochs
-            .last()
-            .unwrap()
-            .expected_circuit_outputs
-            .clone();
+        let batch_root = compute_root(epoch_hashes)?;
 
-        Self {
+        Ok(Self {
             batch_root,
             latest_batch_output: last_epoch_output,
-        }
+        })
     }
 }
@@ -1,9 +1,10 @@
-use std::fs;
-
 use crate::{
     execution_header::ExecutionHeaderProof,
     traits::{ProofType, Provable, Submittable},
-    utils::{hashing::get_committee_hash, rpc::BeaconRpcClient},
+    utils::{
+        hashing::{get_committee_hash, selector},
+        rpc::BeaconRpcClient,
+    },
     Error,
 };
 use alloy_primitives::FixedBytes;
@@ -13,11 +14,13 @@ use alloy_rpc_types_beacon::{
 use bls12_381::{G1Affine, G1Projective, G2Affine};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
-use starknet::{core::types::Felt, macros::selector};
-use starknet_crypto::poseidon_hash_many;
+use starknet_crypto::{poseidon_hash_many, Felt};
+use tracing::warn;
 use tree_hash::TreeHash;
 use tree_hash_derive::TreeHash;
 
+/// The inputs and expected outputs of the epoch update circuit, which verifies the sync
+/// committee signature over the header of an epoch
 #[derive(Debug, Serialize, Deserialize)]
 pub struct EpochUpdate {
     pub circuit_inputs: EpochCircuitInputs,
@@ -25,9 +28,10 @@ pub struct EpochUpdate {
 }
 
 impl EpochUpdate {
-    pub(crate) async fn new(client: &BeaconRpcClient, slot: u64) -> Result<Self, Error> {
+    /// Fetches the header, signature, committee and execution header proof of the epoch at `slot`
+    pub async fn new(client: &BeaconRpcClient, slot: u64) -> Result<Self, Error> {
         let circuit_inputs = EpochCircuitInputs::generate_epoch_proof(client, slot).await?;
-        let expected_circuit_outputs = ExpectedEpochUpdateOutputs::from_inputs(&circuit_inputs);
+        let expected_circuit_outputs = ExpectedEpochUpdateOutputs::from_inputs(&circuit_inputs)?;
         Ok(Self {
             circuit_inputs,
             expected_circuit_outputs,
@@ -43,36 +47,16 @@ impl Provable for EpochUpdate {
         hex::encode(hasher.finalize().as_slice())
     }
 
-    fn export(&self) -> Result<String, Error> {
-        let json = serde_json::to_string_pretty(&self).unwrap();
-        let dir_path = format!("batches/epoch/{}", self.circuit_inputs.header.slot);
-        fs::create_dir_all(dir_path.clone()).map_err(Error::IoError)?;
-        let path = format!(
-            "{}/input_{}.json",
-            dir_path, self.circuit_inputs.header.slot
-        );
-        fs::write(path.clone(), json).map_err(Error::IoError)?;
-        Ok(path)
-    }
-
-    fn from_json<T>(slot: u64) -> Result<T, Error>
-    where
-        T: serde::de::DeserializeOwned,
-    {
-        let path = format!("batches/epoch/{}/input_{}.json", slot, slot);
-        let json = fs::read_to_string(path).map_err(Error::IoError)?;
-        serde_json::from_str(&json).map_err(|e| Error::DeserializeError(e.to_string()))
+    fn proof_type(&self) -> ProofType {
+        ProofType::Epoch
     }
 
-    fn pie_path(&self) -> String {
-        format!(
-            "batches/epoch/{}/pie_{}.zip",
-            self.circuit_inputs.header.slot, self.circuit_inputs.header.slot
-        )
+    fn slot(&self) -> u64 {
+        self.circuit_inputs.header.slot
     }
 
-    fn proof_type(&self) -> ProofType {
-        ProofType::Epoch
+    fn n_epochs(&self) -> u64 {
+        1
     }
 }
 
@@ -123,7 +107,9 @@ impl SyncCommitteeValidatorPubs {
     }
 }
 
-impl From<Vec<String>> for SyncCommitteeValidatorPubs {
+impl TryFrom<Vec<String>> for SyncCommitteeValidatorPubs {
+    type Error = Error;
+
     /// Converts a vector of hex-encoded public key strings into `SyncCommitteeValidatorPubs`.
     ///
     /// # Arguments
@@ -132,17 +118,22 @@ impl From<Vec<String>> for SyncCommitteeValidatorPubs {
     ///
     /// # Returns
     ///
-    /// A new `SyncCommitteeValidatorPubs` instance with parsed public keys.
-    fn from(validator_pubs: Vec<String>) -> Self {
+    /// A new `SyncCommitteeValidatorPubs` instance with parsed public keys, or an error naming
+    /// the index of the first invalid key.
+    fn try_from(validator_pubs: Vec<String>) -> Result<Self, Error> {
         let validator_pubs = validator_pubs
             .iter()
-            .map(|s| {
+            .enumerate()
+            .map(|(index, s)| {
                 let mut bytes = [0u8; 48];
                 let hex_str = s.trim_start_matches("0x");
-                hex::decode_to_slice(hex_str, &mut bytes).unwrap();
-                G1Affine::from_compressed(&bytes).unwrap()
+                hex::decode_to_slice(hex_str, &mut bytes)
+                    .map_err(|e| Error::InvalidValidatorPubkey(index, e.to_string()))?;
+                Option::from(G1Affine::from_compressed(&bytes)).ok_or_else(|| {
+                    Error::InvalidValidatorPubkey(index, "not a valid G1 point".into())
+                })
             })
-            .collect::<Vec<_>>();
+            .collect::<Result<Vec<_>, Error>>()?;
 
         // Aggregate all public keys into a single G1Projective point
         let aggregate_pub = validator_pubs
@@ -150,15 +141,15 @@ impl From<Vec<String>> for SyncCommitteeValidatorPubs {
             .fold(G1Projective::identity(), |acc, pubkey| {
                 acc.add_mixed(pubkey)
             });
-        Self {
+        Ok(Self {
             validator_pubs,
             aggregate_pub: aggregate_pub.into(),
-        }
+        })
     }
 }
 
 impl EpochCircuitInputs {
-    pub(crate) async fn generate_epoch_proof(
+    pub async fn generate_epoch_proof(
         client: &BeaconRpcClient,
         mut slot: u64,
     ) -> Result<EpochCircuitInputs, Error> {
@@ -174,7 +165,11 @@ impl EpochCircuitInputs {
                         return Err(Error::EmptySlotDetected(slot));
                     }
                     slot += 1;
-                    println!("Empty slot detected! Attempt {}/{}. Fetching slot: {}", attempts, MAX_ATTEMPTS, slot);
+                    warn!(
+                        slot,
+                        attempt = attempts,
+                        "Empty slot detected, fetching the next slot"
+                    );
                 }
                 Err(e) => return Err(e), // Propagate other errors immediately
             }
@@ -316,13 +311,18 @@ impl<'de> Deserialize<'de> for G1Point {
         let y_bytes = hex::decode(y_hex)
             .map_err(|e| serde::de::Error::custom(format!("invalid y hex: {}", e)))?;
 
+        if x_bytes.len() != 48 || y_bytes.len() != 48 {
+            return Err(serde::de::Error::custom("G1 coordinates must be 48 bytes"));
+        }
+
         // Combine into uncompressed format
         let mut uncompressed = [0u8; 96];
         uncompressed[0..48].copy_from_slice(&x_bytes);
         uncompressed[48..96].copy_from_slice(&y_bytes);
 
         // Convert to G1Affine point
-        let point = G1Affine::from_uncompressed(&uncompressed).unwrap();
+        let point = Option::from(G1Affine::from_uncompressed(&uncompressed))
+            .ok_or_else(|| serde::de::Error::custom("invalid G1 point"))?;
 
         Ok(G1Point(point))
     }
@@ -366,6 +366,13 @@ impl<'de> Deserialize<'de> for G2Point {
         let y1_bytes = hex::decode(y1_hex)
             .map_err(|e| serde::de::Error::custom(format!("invalid y1 hex: {}", e)))?;
 
+        if [&x0_bytes, &x1_bytes, &y0_bytes, &y1_bytes]
+            .iter()
+            .any(|bytes| bytes.len() != 48)
+        {
+            return Err(serde::de::Error::custom("G2 coordinates must be 48 bytes"));
+        }
+
         // Combine into uncompressed format
         let mut uncompressed = [0u8; 192];
         uncompressed[0..48].copy_from_slice(&x1_bytes);
@@ -374,7 +381,8 @@ impl<'de> Deserialize<'de> for G2Point {
         uncompressed[144..192].copy_from_slice(&y0_bytes);
 
         // Convert to G2Affine point
-        let point = G2Affine::from_uncompressed(&uncompressed).unwrap();
+        let point = Option::from(G2Affine::from_uncompressed(&uncompressed))
+            .ok_or_else(|| serde::de::Error::custom("invalid G2 point"))?;
 
         Ok(G2Point(point))
     }
@@ -399,7 +407,7 @@ impl ExpectedEpochUpdateOutputs {
 }
 
 impl Submittable<EpochCircuitInputs> for ExpectedEpochUpdateOutputs {
-    fn from_inputs(circuit_inputs: &EpochCircuitInputs) -> Self {
+    fn from_inputs(circuit_inputs: &EpochCircuitInputs) -> Result<Self, Error> {
         let block_hash: FixedBytes<32> = FixedBytes::from_slice(
             circuit_inputs
                 .execution_header_proof
@@ -408,7 +416,7 @@ impl Submittable<EpochCircuitInputs> for ExpectedEpochUpdateOutputs {
                 .into_root()
                 .as_bytes(),
         );
-        Self {
+        Ok(Self {
             beacon_header_root: circuit_inputs.header.tree_hash_root(),
             beacon_state_root: circuit_inputs.header.state_root,
             slot: circuit_inputs.header.slot,
@@ -419,7 +427,7 @@ impl Submittable<EpochCircuitInputs> for ExpectedEpochUpdateOutputs {
                 .execution_header_proof
                 .execution_payload_header
                 .block_number(),
-        }
+        })
     }
 
     fn to_calldata(&self) -> Vec<Felt> {
@@ -445,6 +453,6 @@ impl Submittable<EpochCircuitInputs> for ExpectedEpochUpdateOutputs {
     }
 
     fn get_contract_selector(&self) -> Felt {
-        selector!("verify_epoch_update")
+        selector("verify_epoch_update")
     }
 }
@@ -1,5 +1,7 @@
+use crate::epoch_update::BeaconHeader;
 use crate::utils::merkle::sha256::{generate_path, hash_path};
 use crate::utils::rpc::BeaconRpcClient;
+use crate::utils::ssz_proof::{SszContainer, SszProof};
 use crate::Error;
 use alloy_primitives::FixedBytes;
 use beacon_state_proof::state_proof_fetcher::TreeHash;
@@ -7,7 +9,15 @@ use serde::{Deserialize, Serialize};
 use types::{BeaconBlockBody, ExecPayload, ExecutionPayloadHeader, MainnetEthSpec};
 
 /// Index of the execution payload in the beacon block body merkle tree
-const EXECUTION_PAYLOAD_LEAF_INDEX: usize = 9;
+pub const EXECUTION_PAYLOAD_LEAF_INDEX: usize = 9;
+
+/// Payload fields proven by `ExecutionPayloadFieldsProof` unless others are requested
+pub const DEFAULT_PAYLOAD_FIELDS: [&str; 4] = [
+    "state_root",
+    "receipts_root",
+    "timestamp",
+    "withdrawals_root",
+];
 
 /// Represents a proof of inclusion for an execution payload header in a beacon block
 ///
@@ -39,19 +49,27 @@ impl ExecutionHeaderProof {
     /// # Returns
     /// * `Result<ExecutionHeaderProof, Error>` - The constructed proof or an error
     /// ```
-    pub(crate) async fn fetch_proof(
+    pub async fn fetch_proof(
         client: &BeaconRpcClient,
         slot: u64,
     ) -> Result<ExecutionHeaderProof, Error> {
         // Fetch the beacon block body for the specified slot
         let beacon_block_body: BeaconBlockBody<MainnetEthSpec> =
             client.get_block_body(slot).await?;
+        Self::from_body(&beacon_block_body, slot)
+    }
+
+    /// Constructs the proof from an already fetched beacon block body
+    fn from_body(
+        beacon_block_body: &BeaconBlockBody<MainnetEthSpec>,
+        slot: u64,
+    ) -> Result<ExecutionHeaderProof, Error> {
         let root = beacon_block_body.tree_hash_root();
 
         // Extract the execution payload header
         let payload: ExecutionPayloadHeader<MainnetEthSpec> = beacon_block_body
             .execution_payload()
-            .unwrap()
+            .map_err(|_| Error::MissingExecutionPayload(slot))?
             .to_execution_payload_header();
 
         // Generate merkle proof components
@@ -62,12 +80,14 @@ impl ExecutionHeaderProof {
             .map(|leaf| FixedBytes::from_slice(leaf.as_bytes()))
             .collect();
 
-        let path = generate_path(leafs.clone(), EXECUTION_PAYLOAD_LEAF_INDEX).unwrap();
+        let path = generate_path(leafs.clone(), EXECUTION_PAYLOAD_LEAF_INDEX)?;
         let leaf = leafs[EXECUTION_PAYLOAD_LEAF_INDEX];
 
         // Verify the merkle proof
         let computed_root = hash_path(path.clone(), leaf, EXECUTION_PAYLOAD_LEAF_INDEX as u64);
-        assert_eq!(computed_root.as_slice(), root.as_bytes());
+        if computed_root.as_slice() != root.as_bytes() {
+            return Err(Error::InvalidExecutionPayloadProof(slot));
+        }
 
         // Construct and return the proof
         let proof = ExecutionHeaderProof {
@@ -82,3 +102,88 @@ impl ExecutionHeaderProof {
         Ok(proof)
     }
 }
+
+/// Proof of a single execution payload field
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PayloadFieldProof {
+    /// Name of the field in the execution payload header
+    pub name: String,
+    /// The field value, as serialized by the beacon API
+    pub value: serde_json::Value,
+    /// Branch from the field root through the payload and body roots up to the header root
+    pub proof: SszProof,
+}
+
+/// Extends an `ExecutionHeaderProof` with proofs of individual payload fields
+/// (e.g. the execution state root) against the beacon block header root.
+///
+/// The header root is what the contract stores for verified epochs, so the fields can be
+/// trusted once the header root matches the stored one.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExecutionPayloadFieldsProof {
+    /// The beacon block header the fields are proven against
+    pub header: BeaconHeader,
+    /// SSZ root of the beacon block header
+    pub header_root: FixedBytes<32>,
+    /// Proof of the whole payload header against the body root
+    pub execution_header_proof: ExecutionHeaderProof,
+    pub fields: Vec<PayloadFieldProof>,
+}
+
+impl ExecutionPayloadFieldsProof {
+    /// Fetches the block at the slot and proves the given payload fields
+    pub async fn fetch_proof(
+        client: &BeaconRpcClient,
+        slot: u64,
+        fields: &[String],
+    ) -> Result<ExecutionPayloadFieldsProof, Error> {
+        let header: BeaconHeader = client.get_header(slot).await?.into();
+        let beacon_block_body: BeaconBlockBody<MainnetEthSpec> =
+            client.get_block_body(slot).await?;
+        let execution_header_proof = ExecutionHeaderProof::from_body(&beacon_block_body, slot)?;
+
+        // The body tree contains the tree of the execution payload
+        let tree = header
+            .ssz_tree()?
+            .with_subtree("body_root", beacon_block_body.ssz_tree()?)?;
+        let payload = serde_json::to_value(&execution_header_proof.execution_payload_header)
+            .map_err(|e| Error::DeserializeError(e.to_string()))?;
+
+        let fields = fields
+            .iter()
+            .map(|name| {
+                let proof = tree.prove_field(&format!("body_root.execution_payload.{}", name))?;
+                Ok(PayloadFieldProof {
+                    name: name.clone(),
+                    value: payload[name.as_str()].clone(),
+                    proof,
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let proof = ExecutionPayloadFieldsProof {
+            header,
+            header_root: tree.root(),
+            execution_header_proof,
+            fields,
+        };
+        proof.verify(proof.header_root)?;
+        Ok(proof)
+    }
+
+    /// Verifies all field proofs against the given (e.g. stored) header root
+    pub fn verify(&self, header_root: FixedBytes<32>) -> Result<(), Error> {
+        if self.header_root != header_root
+            || self.header.body_root != self.execution_header_proof.root
+        {
+            return Err(Error::InvalidMerkleTree);
+        }
+        for field in self.fields.iter() {
+            if field.proof.root != header_root {
+                return Err(Error::InvalidMerkleTree);
+            }
+            field.proof.verify()?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,201 @@
+use alloy_primitives::FixedBytes;
+use beacon_state_proof::state_proof_fetcher::TreeHash as LighthouseTreeHash;
+use serde::{Deserialize, Serialize};
+use tree_hash::TreeHash;
+use types::{BeaconState, MainnetEthSpec};
+
+use crate::epoch_update::BeaconHeader;
+use crate::utils::rpc::BeaconRpcClient;
+use crate::utils::ssz_proof::{ChunkTree, GeneralizedIndex, SszContainer, SszProof};
+use crate::Error;
+
+/// Length of the `block_roots` and `state_roots` vectors
+const SLOTS_PER_HISTORICAL_ROOT: u64 = 8192;
+/// Depth of the `block_roots` vector
+const BLOCK_ROOTS_DEPTH: u32 = 13;
+/// Depth of the `historical_summaries` list (limit 2^24)
+const HISTORICAL_SUMMARIES_DEPTH: u32 = 24;
+/// Field indices of `block_roots` and `historical_summaries` in the beacon state
+const BLOCK_ROOTS_FIELD: u64 = 5;
+const HISTORICAL_SUMMARIES_FIELD: u64 = 27;
+
+/// How the target block is reached from the anchor state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum HistoricalProofPath {
+    /// The block is one of the last 8192 blocks of the anchor state (`block_roots`)
+    BlockRoots,
+    /// The block is older and reached through `historical_summaries[summary_index]`,
+    /// whose `block_summary_root` is the `block_roots` vector of the summary state
+    HistoricalSummaries {
+        summary_index: usize,
+        summary_slot: u64,
+    },
+}
+
+/// Proves an older beacon block against a (recent) beacon state root, e.g. one stored by
+/// the contract, without the block having been proven explicitly.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HistoricalBlockProof {
+    /// Header of the proven block. Its SSZ root is the proof leaf.
+    pub target_header: BeaconHeader,
+    pub anchor_slot: u64,
+    pub anchor_state_root: FixedBytes<32>,
+    pub path: HistoricalProofPath,
+    /// Branch from the target block root up to the anchor state root
+    pub proof: SszProof,
+}
+
+impl HistoricalBlockProof {
+    pub async fn fetch_proof(
+        client: &BeaconRpcClient,
+        target_slot: u64,
+        anchor_slot: u64,
+    ) -> Result<HistoricalBlockProof, Error> {
+        if target_slot >= anchor_slot {
+            return Err(Error::InvalidHistoricalProof(format!(
+                "Target slot {} is not older than the anchor slot {}",
+                target_slot, anchor_slot
+            )));
+        }
+
+        let target_header: BeaconHeader = client.get_header(target_slot).await?.into();
+        let anchor_state = client.get_beacon_state(anchor_slot).await?;
+        let anchor_tree = anchor_state.ssz_tree()?;
+
+        let (path, proof) = if anchor_slot - target_slot <= SLOTS_PER_HISTORICAL_ROOT {
+            let block_roots = block_roots_tree(&anchor_state)?;
+            let proof = block_roots
+                .prove((target_slot % SLOTS_PER_HISTORICAL_ROOT) as usize)?
+                .extend(&anchor_tree.prove_field("block_roots")?)?;
+            (HistoricalProofPath::BlockRoots, proof)
+        } else {
+            // The summary of a period is appended when transitioning into the next period,
+            // so the first state of the next period holds the same roots as the summary.
+            let period = target_slot / SLOTS_PER_HISTORICAL_ROOT;
+            let summary_slot = (period + 1) * SLOTS_PER_HISTORICAL_ROOT;
+            let summaries = anchor_state
+                .historical_summaries()
+                .map_err(|_| Error::InvalidHistoricalProof("Anchor state is pre Capella".into()))?;
+            let completed_periods = anchor_slot / SLOTS_PER_HISTORICAL_ROOT;
+            let summary_index = (summaries.len() + period as usize)
+                .checked_sub(completed_periods as usize)
+                .ok_or_else(|| {
+                    Error::InvalidHistoricalProof(format!(
+                        "Slot {} predates the historical summaries",
+                        target_slot
+                    ))
+                })?;
+
+            let summary_state = client.get_beacon_state(summary_slot).await?;
+            let block_roots = block_roots_tree(&summary_state)?;
+            let state_summary_root =
+                FixedBytes::from_slice(summary_state.state_roots().tree_hash_root().as_bytes());
+            let summary_tree = ChunkTree::vector(vec![block_roots.root(), state_summary_root], 1)?;
+
+            let summaries_tree = ChunkTree::list(
+                summaries
+                    .iter()
+                    .map(|summary| FixedBytes::from_slice(summary.tree_hash_root().as_bytes()))
+                    .collect(),
+                HISTORICAL_SUMMARIES_DEPTH,
+            )?;
+            let proof = block_roots
+                .prove((target_slot % SLOTS_PER_HISTORICAL_ROOT) as usize)?
+                .extend(&summary_tree.prove(0)?)?
+                .extend(&summaries_tree.prove(summary_index)?)?
+                .extend(&anchor_tree.prove_field("historical_summaries")?)?;
+            (
+                HistoricalProofPath::HistoricalSummaries {
+                    summary_index,
+                    summary_slot,
+                },
+                proof,
+            )
+        };
+
+        let historical_proof = HistoricalBlockProof {
+            target_header,
+            anchor_slot,
+            anchor_state_root: anchor_tree.root(),
+            path,
+            proof,
+        };
+        historical_proof.verify(historical_proof.anchor_state_root)?;
+        Ok(historical_proof)
+    }
+
+    /// Verifies the target header against the given (e.g. stored) anchor state root
+    pub fn verify(&self, anchor_state_root: FixedBytes<32>) -> Result<(), Error> {
+        if self.proof.leaf != self.target_header.tree_hash_root() {
+            // Empty slots repeat the root of the previous block
+            return Err(Error::InvalidHistoricalProof(format!(
+                "Block root at slot {} does not match the header",
+                self.target_header.slot
+            )));
+        }
+        if self.proof.root != anchor_state_root || self.anchor_state_root != anchor_state_root {
+            return Err(Error::InvalidHistoricalProof(format!(
+                "Proof is not anchored in state root {}",
+                anchor_state_root
+            )));
+        }
+        self.verify_gindex()?;
+        self.proof.verify()
+    }
+
+    /// Ensures the proof leaf is the `block_roots` entry of the target slot. Block roots are
+    /// repeated for empty slots, so a matching leaf alone doesn't pin the slot.
+    fn verify_gindex(&self) -> Result<(), Error> {
+        let block_root_index = self.target_header.slot % SLOTS_PER_HISTORICAL_ROOT;
+        let (field, relative_index, relative_depth) = match self.path {
+            HistoricalProofPath::BlockRoots => {
+                (BLOCK_ROOTS_FIELD, block_root_index, BLOCK_ROOTS_DEPTH)
+            }
+            HistoricalProofPath::HistoricalSummaries {
+                summary_index,
+                summary_slot,
+            } => {
+                let period = self.target_header.slot / SLOTS_PER_HISTORICAL_ROOT;
+                if summary_slot != (period + 1) * SLOTS_PER_HISTORICAL_ROOT {
+                    return Err(Error::InvalidHistoricalProof(format!(
+                        "Summary slot {} does not follow the period of slot {}",
+                        summary_slot, self.target_header.slot
+                    )));
+                }
+                // Below the summary list root: the list data (left of the length), the summary
+                // at `summary_index`, its `block_summary_root` (first field) and the block root
+                let index = ((summary_index as u64) << (BLOCK_ROOTS_DEPTH + 1)) | block_root_index;
+                (
+                    HISTORICAL_SUMMARIES_FIELD,
+                    index,
+                    BLOCK_ROOTS_DEPTH + 1 + HISTORICAL_SUMMARIES_DEPTH + 1,
+                )
+            }
+        };
+
+        let gindex = self.proof.gindex;
+        let field_gindex = GeneralizedIndex(gindex.0 >> relative_depth);
+        if gindex.depth() <= relative_depth
+            || gindex.0 & ((1 << relative_depth) - 1) != relative_index
+            || field_gindex.index_in_level() != field
+        {
+            return Err(Error::InvalidHistoricalProof(format!(
+                "Generalized index {} does not lead to the block root of slot {}",
+                gindex, self.target_header.slot
+            )));
+        }
+        Ok(())
+    }
+}
+
+fn block_roots_tree(state: &BeaconState<MainnetEthSpec>) -> Result<ChunkTree, Error> {
+    ChunkTree::vector(
+        state
+            .block_roots()
+            .iter()
+            .map(|root| FixedBytes::from_slice(root.as_bytes()))
+            .collect(),
+        BLOCK_ROOTS_DEPTH,
+    )
+}
@@ -0,0 +1,355 @@
+//! Offline verification of exported update inputs, e.g. files received from other operators.
+//!
+//! The expected circuit outputs are recomputed from the circuit inputs, and the merkle paths
+//! and the sync committee signature are verified natively. Nothing is fetched, so the result
+//! doesn't depend on trusting the client or the beacon node that generated the file.
+//!
+//! Like the circuits, the signature checks only know the Sepolia forks up to Deneb. Updates
+//! from Electra on fail with [`Error::UnsupportedFork`].
+
+use std::fmt;
+use std::fs;
+
+use crate::epoch_batch::{EpochUpdateBatch, ExpectedEpochBatchOutputs};
+use crate::epoch_update::{
+    BeaconHeader, EpochUpdate, ExpectedEpochUpdateOutputs, G1Point, G2Point,
+};
+use crate::execution_header::EXECUTION_PAYLOAD_LEAF_INDEX;
+use crate::sync_committee::{ExpectedCircuitOutputs, SyncCommitteeUpdate};
+use crate::traits::{ProofType, Provable, Submittable};
+use crate::utils::merkle;
+use crate::utils::ssz_proof::NEXT_SYNC_COMMITTEE_GINDEX;
+use crate::Error;
+use alloy_primitives::{b256, FixedBytes};
+use beacon_state_proof::state_proof_fetcher::TreeHash;
+use bls12_381::hash_to_curve::{ExpandMsgXmd, HashToCurve};
+use bls12_381::{pairing, G1Affine, G1Projective, G2Affine, G2Projective};
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+/// Genesis validators root of Sepolia, the network the circuits are built for
+const GENESIS_VALIDATORS_ROOT: FixedBytes<32> =
+    b256!("d8ea171f3c94aea21ebc42a1ed61052acf3f9209c00e4efbaaddac09ed9b8078");
+
+/// Activation slot and version of the Sepolia forks, as in `cairo/src/domain.cairo`
+const FORK_VERSIONS: [(u64, [u8; 4]); 5] = [
+    (0, [0x90, 0x00, 0x00, 0x69]),
+    (1600, [0x90, 0x00, 0x00, 0x70]),
+    (3200, [0x90, 0x00, 0x00, 0x71]),
+    (1818624, [0x90, 0x00, 0x00, 0x72]),
+    (4243456, [0x90, 0x00, 0x00, 0x73]),
+];
+
+/// First slot of Electra on Sepolia, whose fork version the circuits don't know yet
+pub const ELECTRA_ACTIVATION_SLOT: u64 = 7118848;
+
+const DOMAIN_SYNC_COMMITTEE: [u8; 4] = [0x07, 0x00, 0x00, 0x00];
+
+/// Domain separation tag of the proof of possession scheme used by Ethereum
+const SIGNATURE_DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
+/// Domain of the sync committee signatures at `slot`
+pub fn sync_committee_domain(slot: u64) -> Result<FixedBytes<32>, Error> {
+    if slot >= ELECTRA_ACTIVATION_SLOT {
+        return Err(Error::UnsupportedFork(slot));
+    }
+    let (_, fork_version) = FORK_VERSIONS
+        .iter()
+        .rev()
+        .find(|(activation_slot, _)| slot >= *activation_slot)
+        .unwrap_or(&FORK_VERSIONS[0]);
+
+    // The fork data root is the root of the container (current_version, genesis_validators_root)
+    let mut fork_data = [0u8; 64];
+    fork_data[..4].copy_from_slice(fork_version);
+    fork_data[32..].copy_from_slice(GENESIS_VALIDATORS_ROOT.as_slice());
+    let fork_data_root = Sha256::digest(fork_data);
+
+    let mut domain = [0u8; 32];
+    domain[..4].copy_from_slice(&DOMAIN_SYNC_COMMITTEE);
+    domain[4..].copy_from_slice(&fork_data_root[..28]);
+    Ok(FixedBytes::from(domain))
+}
+
+/// The message signed by the sync committee for the header root at `slot`
+pub fn signing_root(header_root: FixedBytes<32>, slot: u64) -> Result<FixedBytes<32>, Error> {
+    let mut data = [0u8; 64];
+    data[..32].copy_from_slice(header_root.as_slice());
+    data[32..].copy_from_slice(sync_committee_domain(slot)?.as_slice());
+    Ok(FixedBytes::from_slice(&Sha256::digest(data)))
+}
+
+/// Verifies the aggregate signature of the committee members that signed the header, i.e. of
+/// the committee key minus the keys of the non-signers
+pub fn verify_signature(
+    header: &BeaconHeader,
+    signature: &G2Point,
+    committee_pub: &G1Point,
+    non_signers: &[G1Point],
+) -> Result<bool, Error> {
+    let header_root = tree_hash::TreeHash::tree_hash_root(header);
+    let message = <G2Projective as HashToCurve<ExpandMsgXmd<Sha256>>>::hash_to_curve(
+        signing_root(header_root, header.slot)?.as_slice(),
+        SIGNATURE_DST,
+    );
+    let signers_pub = non_signers
+        .iter()
+        .fold(G1Projective::from(committee_pub.0), |acc, non_signer| {
+            acc - non_signer.0
+        });
+
+    Ok(
+        pairing(&G1Affine::from(signers_pub), &G2Affine::from(message))
+            == pairing(&G1Affine::generator(), &signature.0),
+    )
+}
+
+/// The outcome of a single check
+#[derive(Debug, Serialize)]
+pub struct Check {
+    pub name: String,
+    pub passed: bool,
+    /// Why the check failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.passed, &self.detail) {
+            (true, _) => write!(f, "[PASS] {}", self.name),
+            (false, Some(detail)) => write!(f, "[FAIL] {}: {}", self.name, detail),
+            (false, None) => write!(f, "[FAIL] {}", self.name),
+        }
+    }
+}
+
+/// The checks of an exported update
+#[derive(Debug, Serialize)]
+pub struct VerificationReport {
+    pub proof_type: ProofType,
+    pub slot: u64,
+    pub checks: Vec<Check>,
+}
+
+impl VerificationReport {
+    fn new(proof_type: ProofType, slot: u64) -> Self {
+        Self {
+            proof_type,
+            slot,
+            checks: vec![],
+        }
+    }
+
+    /// Whether all checks passed
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(|check| check.passed)
+    }
+
+    fn check(&mut self, name: impl Into<String>, result: Result<(), String>) {
+        self.checks.push(Check {
+            name: name.into(),
+            passed: result.is_ok(),
+            detail: result.err(),
+        });
+    }
+}
+
+/// Update inputs as kept in the `ArtifactStore` or written by `epoch-update --export`
+#[derive(Debug)]
+pub enum ExportedUpdate {
+    Epoch(EpochUpdate),
+    EpochBatch(EpochUpdateBatch),
+    SyncCommittee(SyncCommitteeUpdate),
+}
+
+impl ExportedUpdate {
+    /// Loads an exported update, telling the types apart by their fields
+    pub fn load(path: &str) -> Result<Self, Error> {
+        let json = fs::read_to_string(path).map_err(Error::IoError)?;
+        let value: Value =
+            serde_json::from_str(&json).map_err(|e| Error::DeserializeError(e.to_string()))?;
+        let update = if value.get("merkle_paths").is_some() {
+            serde_json::from_value(value).map(ExportedUpdate::EpochBatch)
+        } else if value["circuit_inputs"].get("beacon_slot").is_some() {
+            serde_json::from_value(value).map(ExportedUpdate::SyncCommittee)
+        } else {
+            serde_json::from_value(value).map(ExportedUpdate::Epoch)
+        };
+        update.map_err(|e| Error::DeserializeError(e.to_string()))
+    }
+
+    /// Runs all checks of the update
+    pub fn verify(&self) -> VerificationReport {
+        match self {
+            ExportedUpdate::Epoch(update) => {
+                let mut report = VerificationReport::new(update.proof_type(), update.slot());
+                verify_epoch(&mut report, update, "");
+                report
+            }
+            ExportedUpdate::EpochBatch(batch) => verify_batch(batch),
+            ExportedUpdate::SyncCommittee(update) => verify_committee_update(update),
+        }
+    }
+}
+
+/// Compares the recomputed with the exported outputs, naming the fields that differ
+fn compare_outputs(computed: &impl Serialize, exported: &impl Serialize) -> Result<(), String> {
+    let computed = serde_json::to_value(computed).map_err(|e| e.to_string())?;
+    let exported = serde_json::to_value(exported).map_err(|e| e.to_string())?;
+    let (Value::Object(computed), Value::Object(exported)) = (computed, exported) else {
+        return Err("outputs are not objects".to_string());
+    };
+    let mismatched: Vec<&str> = computed
+        .iter()
+        .filter(|(key, value)| exported.get(*key) != Some(*value))
+        .map(|(key, _)| key.as_str())
+        .collect();
+    if mismatched.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("mismatched {}", mismatched.join(", ")))
+    }
+}
+
+/// Checks of an epoch update. `prefix` names the epoch within a batch.
+fn verify_epoch(report: &mut VerificationReport, update: &EpochUpdate, prefix: &str) {
+    let inputs = &update.circuit_inputs;
+    let proof = &inputs.execution_header_proof;
+
+    let payload_root =
+        FixedBytes::from_slice(proof.execution_payload_header.tree_hash_root().as_bytes());
+    report.check(
+        format!("{}execution header branch", prefix),
+        if proof.index != EXECUTION_PAYLOAD_LEAF_INDEX {
+            Err(format!("index is {}", proof.index))
+        } else if proof.leaf != payload_root {
+            Err("leaf is not the root of the execution payload header".to_string())
+        } else if proof.root != inputs.header.body_root {
+            Err("root is not the body root of the header".to_string())
+        } else if merkle::sha256::hash_path(proof.path.clone(), proof.leaf, proof.index as u64)
+            != proof.root
+        {
+            Err("path does not lead to the body root".to_string())
+        } else {
+            Ok(())
+        },
+    );
+
+    report.check(
+        format!("{}sync committee signature", prefix),
+        match verify_signature(
+            &inputs.header,
+            &inputs.signature_point,
+            &inputs.aggregate_pub,
+            &inputs.non_signers,
+        ) {
+            Ok(true) => Ok(()),
+            Ok(false) => Err("signature does not match the signers".to_string()),
+            Err(e) => Err(e.to_string()),
+        },
+    );
+
+    report.check(
+        format!("{}expected outputs", prefix),
+        ExpectedEpochUpdateOutputs::from_inputs(inputs)
+            .map_err(|e| e.to_string())
+            .and_then(|computed| compare_outputs(&computed, &update.expected_circuit_outputs)),
+    );
+}
+
+fn verify_batch(batch: &EpochUpdateBatch) -> VerificationReport {
+    let mut report = VerificationReport::new(batch.proof_type(), batch.slot());
+    let epochs = &batch.circuit_inputs.epochs;
+
+    for epoch in epochs.iter() {
+        verify_epoch(&mut report, epoch, &format!("epoch {}: ", epoch.slot()));
+    }
+
+    let foreign: Vec<String> = epochs
+        .iter()
+        .filter(|epoch| {
+            epoch.expected_circuit_outputs.committee_hash != batch.circuit_inputs.committee_hash
+        })
+        .map(|epoch| epoch.slot().to_string())
+        .collect();
+    report.check(
+        "batch committee",
+        if foreign.is_empty() {
+            Ok(())
+        } else {
+            Err(format!(
+                "signed by another committee: {}",
+                foreign.join(", ")
+            ))
+        },
+    );
+
+    let batch_root = batch.expected_circuit_outputs.batch_root;
+    let invalid: Vec<String> = epochs
+        .iter()
+        .enumerate()
+        .filter(|(index, epoch)| {
+            !batch.merkle_paths.get(*index).is_some_and(|path| {
+                merkle::poseidon::hash_path(epoch.expected_circuit_outputs.hash(), path, *index)
+                    == batch_root
+            })
+        })
+        .map(|(index, _)| index.to_string())
+        .collect();
+    report.check(
+        "batch merkle paths",
+        if batch.merkle_paths.len() != epochs.len() {
+            Err(format!(
+                "{} paths for {} epochs",
+                batch.merkle_paths.len(),
+                epochs.len()
+            ))
+        } else if !invalid.is_empty() {
+            Err(format!(
+                "paths of epochs {} do not lead to the batch root",
+                invalid.join(", ")
+            ))
+        } else {
+            Ok(())
+        },
+    );
+
+    report.check(
+        "expected outputs",
+        ExpectedEpochBatchOutputs::from_inputs(&batch.circuit_inputs)
+            .map_err(|e| e.to_string())
+            .and_then(|computed| compare_outputs(&computed, &batch.expected_circuit_outputs)),
+    );
+    report
+}
+
+fn verify_committee_update(update: &SyncCommitteeUpdate) -> VerificationReport {
+    let mut report = VerificationReport::new(update.proof_type(), update.slot());
+    let inputs = &update.circuit_inputs;
+
+    let depth = NEXT_SYNC_COMMITTEE_GINDEX.depth() as usize;
+    report.check(
+        "next sync committee branch",
+        if inputs.next_sync_committee_branch.len() != depth {
+            Err(format!(
+                "branch has {} nodes, expected {}",
+                inputs.next_sync_committee_branch.len(),
+                depth
+            ))
+        } else if inputs.compute_state_root() != update.expected_circuit_outputs.state_root {
+            Err("branch does not lead to the state root".to_string())
+        } else {
+            Ok(())
+        },
+    );
+
+    report.check(
+        "expected outputs",
+        ExpectedCircuitOutputs::from_inputs(inputs)
+            .map_err(|e| e.to_string())
+            .and_then(|computed| compare_outputs(&computed, &update.expected_circuit_outputs)),
+    );
+    report
+}
@@ -0,0 +1,263 @@
+//! Generates the circuit inputs of Bankai, the Ethereum light client on Starknet, and submits
+//! the verified updates to the contract.
+//!
+//! The main entry points are the update builders, which fetch everything a circuit needs from a
+//! beacon node:
+//!
+//! - [`EpochUpdate`] proves the header of an epoch was signed by the sync committee.
+//! - [`EpochUpdateBatch`] batches the epochs of a committee period into one proof.
+//! - [`SyncCommitteeUpdate`] proves the next sync committee against a verified state root.
+//!
+//! All of them implement [`Provable`], which identifies their inputs and proofs in the
+//! [`ArtifactStore`], and their outputs implement [`Submittable`], which encodes them as
+//! contract calldata. Proofs are generated by a [`ProverBackend`].
+//!
+//! The Starknet contract client, the Atlantic client and local circuit runs are gated behind
+//! the `starknet-client`, `atlantic` and `cairo-runner` features, all enabled by default.
+//! With all three, [`BankaiClient`] combines them and the `commands` module implements the
+//! commands of the `client-rs` binary. The HTTP API of its `serve` command is gated behind the
+//! `server` feature.
+
+#[cfg(all(
+    feature = "starknet-client",
+    feature = "atlantic",
+    feature = "cairo-runner"
+))]
+pub mod backfill;
+#[cfg(all(
+    feature = "starknet-client",
+    feature = "atlantic",
+    feature = "cairo-runner"
+))]
+pub mod client;
+#[cfg(all(
+    feature = "starknet-client",
+    feature = "atlantic",
+    feature = "cairo-runner"
+))]
+pub mod commands;
+pub mod config;
+#[cfg(feature = "starknet-client")]
+pub mod contract_init;
+pub mod epoch_batch;
+pub mod epoch_update;
+pub mod execution_header;
+pub mod historical_proof;
+pub mod input_verifier;
+pub mod logging;
+pub mod output;
+#[cfg(all(
+    feature = "starknet-client",
+    feature = "atlantic",
+    feature = "cairo-runner",
+    feature = "server"
+))]
+pub mod server;
+pub mod storage_proof;
+pub mod sync_committee;
+pub mod traits;
+pub mod utils;
+
+use alloy_primitives::FixedBytes;
+use beacon_state_proof::error::Error as BeaconStateProofError;
+use starknet_crypto::Felt;
+#[cfg(feature = "starknet-client")]
+use utils::starknet_client::StarknetError;
+
+#[cfg(all(
+    feature = "starknet-client",
+    feature = "atlantic",
+    feature = "cairo-runner"
+))]
+pub use client::{BankaiClient, ProverKind};
+pub use config::BankaiConfig;
+pub use epoch_batch::EpochUpdateBatch;
+pub use epoch_update::EpochUpdate;
+pub use execution_header::ExecutionHeaderProof;
+pub use sync_committee::SyncCommitteeUpdate;
+pub use traits::{ProofType, Provable, ProverBackend, StarkProof, Submittable};
+pub use utils::artifact_store::ArtifactStore;
+pub use utils::rpc::BeaconRpcClient;
+#[cfg(feature = "starknet-client")]
+pub use utils::starknet_client::StarknetClient;
+
+/// Errors of all operations of the client
+#[derive(Debug)]
+pub enum Error {
+    InvalidProof,
+    RpcError(reqwest::Error),
+    DeserializeError(String),
+    IoError(std::io::Error),
+    #[cfg(feature = "starknet-client")]
+    StarknetError(StarknetError),
+    BeaconStateProofError(BeaconStateProofError),
+    BlockNotFound,
+    FetchSyncCommitteeError,
+    FailedFetchingBeaconState,
+    InvalidBLSPoint,
+    MissingRpcUrl,
+    /// Name of a required environment variable that is not set
+    MissingEnvVar(&'static str),
+    EmptySlotDetected(u64),
+    RequiresNewerEpoch(u64),
+    CairoRunError(String),
+    AtlanticError(reqwest::Error),
+    InvalidResponse(String),
+    InvalidMerkleTree,
+    InvalidEvent(String),
+    ArtifactIntegrityError(String),
+    /// A field path that doesn't exist in the SSZ container
+    InvalidSszPath(String),
+    InvalidExecutionProof(String),
+    InvalidHistoricalProof(String),
+    /// Committee id, stored and found committee hash
+    CommitteeHashMismatch(u64, FixedBytes<32>, FixedBytes<32>),
+    /// Slot of the epoch, batch and epoch committee hash
+    MixedCommitteeBatch(u64, FixedBytes<32>, FixedBytes<32>),
+    EpochNotAccumulated(u64),
+    InvalidAccumulatorProof(String),
+    /// Committee id that is neither stored nor provable from the latest epoch
+    MissingCommittee(u64),
+    EmptyBatch,
+    /// Expected and found program hash for the proof type
+    ProgramHashMismatch(ProofType, Felt, Felt),
+    /// Path of the circuit whose program hash couldn't be computed, and the reason
+    ProgramHashUnavailable(String, String),
+    /// Index of the epoch whose path doesn't match the batch root
+    InvalidBatchPath(usize),
+    /// Slot signed with the fork version of a fork the circuits don't support
+    UnsupportedFork(u64),
+    /// Slot of a block body without execution payload
+    MissingExecutionPayload(u64),
+    /// Slot whose execution payload branch doesn't match the block body root
+    InvalidExecutionPayloadProof(u64),
+    /// Position in the sync committee and reason
+    InvalidValidatorPubkey(usize, String),
+    /// Slot of the committee update whose aggregate pubkey is not a valid point
+    InvalidAggregatePubkey(u64),
+    /// Slot of an epoch that is not part of an exported batch
+    EpochNotBatched(u64),
+    /// Id of a prover job that failed
+    JobFailed(String),
+    /// Description of the artifact that is not stored
+    ArtifactNotFound(String),
+    /// Description of the lookup and ids of the stored updates matching it
+    AmbiguousArtifact(String, Vec<String>),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::InvalidProof => write!(f, "Invalid proof"),
+            Error::RpcError(e) => write!(f, "RPC request failed: {}", e),
+            Error::DeserializeError(e) => write!(f, "Failed to (de)serialize: {}", e),
+            Error::IoError(e) => write!(f, "IO error: {}", e),
+            #[cfg(feature = "starknet-client")]
+            Error::StarknetError(e) => write!(f, "Starknet error: {}", e),
+            Error::BeaconStateProofError(e) => write!(f, "Beacon state proof error: {:?}", e),
+            Error::BlockNotFound => write!(f, "Block not found"),
+            Error::FetchSyncCommitteeError => write!(f, "Failed to fetch the sync committee"),
+            Error::FailedFetchingBeaconState => write!(f, "Failed to fetch the beacon state"),
+            Error::InvalidBLSPoint => write!(f, "Invalid BLS point"),
+            Error::MissingRpcUrl => write!(f, "RPC URL is not set"),
+            Error::MissingEnvVar(name) => write!(f, "Environment variable {} is not set", name),
+            Error::EmptySlotDetected(slot) => write!(f, "Slot {} is empty", slot),
+            Error::RequiresNewerEpoch(slot) => {
+                write!(f, "Requires an epoch newer than slot {}", slot)
+            }
+            Error::CairoRunError(e) => write!(f, "cairo-run failed: {}", e),
+            Error::AtlanticError(e) => write!(f, "Atlantic request failed: {}", e),
+            Error::InvalidResponse(e) => write!(f, "Invalid response: {}", e),
+            Error::InvalidMerkleTree => write!(f, "Invalid merkle tree"),
+            Error::InvalidEvent(e) => write!(f, "Invalid event: {}", e),
+            Error::ArtifactIntegrityError(e) => write!(f, "Artifact integrity error: {}", e),
+            Error::InvalidSszPath(path) => write!(f, "Invalid SSZ path: {}", path),
+            Error::InvalidExecutionProof(e) => write!(f, "Invalid execution proof: {}", e),
+            Error::InvalidHistoricalProof(e) => write!(f, "Invalid historical proof: {}", e),
+            Error::CommitteeHashMismatch(committee_id, stored, found) => write!(
+                f,
+                "Committee {} is stored with hash {}, but the epoch was signed by {}",
+                committee_id, stored, found
+            ),
+            Error::MixedCommitteeBatch(slot, batch, found) => write!(
+                f,
+                "Epoch {} was signed by committee {}, the batch by {}",
+                slot, found, batch
+            ),
+            Error::EpochNotAccumulated(slot) => {
+                write!(f, "Epoch {} is not in the accumulator", slot)
+            }
+            Error::InvalidAccumulatorProof(e) => write!(f, "Invalid accumulator proof: {}", e),
+            Error::MissingCommittee(committee_id) => write!(
+                f,
+                "Committee {} is not stored and can't be proven from the latest epoch",
+                committee_id
+            ),
+            Error::EmptyBatch => write!(f, "Batch contains no epochs"),
+            Error::ProgramHashMismatch(proof_type, expected, found) => write!(
+                f,
+                "Program hash of {:?} is {:#x}, expected {:#x}",
+                proof_type, found, expected
+            ),
+            Error::ProgramHashUnavailable(path, e) => {
+                write!(f, "Failed to compute the program hash of {}: {}", path, e)
+            }
+            Error::InvalidBatchPath(index) => {
+                write!(f, "Path of epoch {} does not match the batch root", index)
+            }
+            Error::UnsupportedFork(slot) => write!(
+                f,
+                "Slot {} is after Deneb, later forks are not supported",
+                slot
+            ),
+            Error::MissingExecutionPayload(slot) => {
+                write!(f, "Block at slot {} has no execution payload", slot)
+            }
+            Error::InvalidExecutionPayloadProof(slot) => write!(
+                f,
+                "Execution payload of slot {} does not match the block body root",
+                slot
+            ),
+            Error::InvalidValidatorPubkey(position, reason) => write!(
+                f,
+                "Invalid pubkey of sync committee member {}: {}",
+                position, reason
+            ),
+            Error::InvalidAggregatePubkey(slot) => write!(
+                f,
+                "Invalid aggregate pubkey of the next sync committee at slot {}",
+                slot
+            ),
+            Error::EpochNotBatched(slot) => {
+                write!(f, "Epoch {} is not in a stored batch", slot)
+            }
+            Error::JobFailed(job_id) => write!(f, "Prover job {} failed", job_id),
+            Error::ArtifactNotFound(artifact) => write!(f, "No artifacts stored for {}", artifact),
+            Error::AmbiguousArtifact(lookup, ids) => write!(
+                f,
+                "Several updates are stored for {}: {}",
+                lookup,
+                ids.join(", ")
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::RpcError(e) | Error::AtlanticError(e) => Some(e),
+            Error::IoError(e) => Some(e),
+            #[cfg(feature = "starknet-client")]
+            Error::StarknetError(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "starknet-client")]
+impl From<StarknetError> for Error {
+    fn from(e: StarknetError) -> Self {
+        Error::StarknetError(e)
+    }
+}
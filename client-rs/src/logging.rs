@@ -0,0 +1,41 @@
+use tracing_subscriber::EnvFilter;
+
+/// Format of the logs written to stderr
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum LogFormat {
+    Text,
+    /// One JSON object per line, including the fields of the enclosing spans
+    Json,
+}
+
+/// Installs the global subscriber. Logs of the client are written at info level, which `-v`
+/// raises and `-q` lowers. Dependencies only log warnings. `RUST_LOG` overrides both.
+pub fn init(verbose: u8, quiet: u8, format: LogFormat) {
+    let level = match verbose as i16 - quiet as i16 {
+        i16::MIN..=-2 => "error",
+        -1 => "warn",
+        0 => "info",
+        1 => "debug",
+        _ => "trace",
+    };
+    let dependencies = if level == "error" { "error" } else { "warn" };
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| {
+        EnvFilter::new(format!(
+            "{},bankai_client={},client_rs={}",
+            dependencies, level, level
+        ))
+    });
+
+    let subscriber = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr);
+    match format {
+        LogFormat::Text => subscriber.init(),
+        LogFormat::Json => subscriber
+            .json()
+            .with_current_span(true)
+            .with_span_list(true)
+            .init(),
+    }
+}
@@ -1,133 +1,60 @@
-mod config;
-mod contract_init;
-pub mod epoch_batch;
-mod epoch_update;
-mod execution_header;
-mod sync_committee;
-mod traits;
-mod utils;
-
-use beacon_state_proof::error::Error as BeaconStateProofError;
-use config::BankaiConfig;
-use contract_init::ContractInitializationData;
-use epoch_batch::EpochUpdateBatch;
-use epoch_update::EpochUpdate;
-use execution_header::ExecutionHeaderProof;
-use starknet::core::types::Felt;
-use sync_committee::SyncCommitteeUpdate;
-use traits::Provable;
-use utils::{atlantic_client::AtlanticClient, cairo_runner::CairoRunner};
-use utils::{
-    rpc::BeaconRpcClient,
-    starknet_client::{StarknetClient, StarknetError},
+use alloy_primitives::{Address, FixedBytes};
+use bankai_client::backfill::EpochLookup;
+use bankai_client::commands::{self, ExecutionSource, SszContainerKind};
+use bankai_client::execution_header::DEFAULT_PAYLOAD_FIELDS;
+use bankai_client::logging::{self, LogFormat};
+use bankai_client::output::{OutputFormat, Report};
+use bankai_client::traits::ProofType;
+use bankai_client::utils::{
+    artifact_store::{ArtifactKind, ArtifactQuery},
+    metrics::metrics,
+    step_model::BatchStrategy,
 };
-// use rand::Rng;
-// use std::fs::File;
-// use std::io::Write;
-use clap::{Parser, Subcommand};
+use bankai_client::{BankaiClient, Error, ProverKind};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use dotenv::from_filename;
-use std::env;
-
-#[derive(Debug)]
-pub enum Error {
-    InvalidProof,
-    RpcError(reqwest::Error),
-    DeserializeError(String),
-    IoError(std::io::Error),
-    StarknetError(StarknetError),
-    BeaconStateProofError(BeaconStateProofError),
-    BlockNotFound,
-    FetchSyncCommitteeError,
-    FailedFetchingBeaconState,
-    InvalidBLSPoint,
-    MissingRpcUrl,
-    EmptySlotDetected(u64),
-    RequiresNewerEpoch(Felt),
-    CairoRunError(String),
-    AtlanticError(reqwest::Error),
-    InvalidResponse(String),
-    InvalidMerkleTree,
-}
-
-impl From<StarknetError> for Error {
-    fn from(e: StarknetError) -> Self {
-        Error::StarknetError(e)
-    }
-}
+use starknet::core::types::Felt;
+use std::process::ExitCode;
+use tracing::{error, warn};
 
-struct BankaiClient {
-    client: BeaconRpcClient,
-    starknet_client: StarknetClient,
-    config: BankaiConfig,
-    atlantic_client: AtlanticClient,
+#[derive(Subcommand)]
+enum Commands {
+    #[command(flatten)]
+    Offline(OfflineCommands),
+    #[command(flatten)]
+    Online(OnlineCommands),
 }
 
-impl BankaiClient {
-    pub async fn new() -> Self {
-        from_filename(".env.sepolia").ok();
-        let config = BankaiConfig::default();
-        Self {
-            client: BeaconRpcClient::new(env::var("BEACON_RPC_URL").unwrap()),
-            starknet_client: StarknetClient::new(
-                env::var("STARKNET_RPC_URL").unwrap().as_str(),
-                env::var("STARKNET_ADDRESS").unwrap().as_str(),
-                env::var("STARKNET_PRIVATE_KEY").unwrap().as_str(),
-            )
-            .await
-            .unwrap(),
-            atlantic_client: AtlanticClient::new(
-                config.atlantic_endpoint.clone(),
-                env::var("ATLANTIC_API_KEY").unwrap(),
-            ),
-            config,
-        }
-    }
-
-    pub async fn get_sync_committee_update(
-        &self,
-        mut slot: u64,
-    ) -> Result<SyncCommitteeUpdate, Error> {
-        let mut attempts = 0;
-        const MAX_ATTEMPTS: u8 = 3;
-
-        // Before we start generating the proof, we ensure the slot was not missed
-        let _header = loop {
-            match self.client.get_header(slot).await {
-                Ok(header) => break header,
-                Err(Error::EmptySlotDetected(_)) => {
-                    attempts += 1;
-                    if attempts >= MAX_ATTEMPTS {
-                        return Err(Error::EmptySlotDetected(slot));
-                    }
-                    slot += 1;
-                    println!("Empty slot detected! Attempt {}/{}. Fetching slot: {}", attempts, MAX_ATTEMPTS, slot);
-                }
-                Err(e) => return Err(e), // Propagate other errors immediately
-            }
-        };
-
-        let proof: SyncCommitteeUpdate = SyncCommitteeUpdate::new(&self.client, slot).await?;
-
-        Ok(proof)
-    }
-
-    pub async fn get_epoch_proof(&self, slot: u64) -> Result<EpochUpdate, Error> {
-        let epoch_proof = EpochUpdate::new(&self.client, slot).await?;
-        Ok(epoch_proof)
-    }
-
-    pub async fn get_contract_initialization_data(
-        &self,
-        slot: u64,
-        config: &BankaiConfig,
-    ) -> Result<ContractInitializationData, Error> {
-        let contract_init = ContractInitializationData::new(&self.client, slot, config).await?;
-        Ok(contract_init)
-    }
+/// Commands that only use the local config and artifact store
+#[derive(Subcommand)]
+enum OfflineCommands {
+    /// Check the stored inputs of an epoch, batch or committee update offline: recompute the
+    /// expected outputs and verify the merkle paths and the sync committee signature
+    VerifyInputs {
+        /// The stored inputs, e.g. `batches/artifacts/sepolia/<id>/input.json`
+        file: String,
+    },
+    /// List the updates in the artifact store and their files
+    ListArtifacts {
+        #[command(flatten)]
+        filter: ArtifactFilter,
+    },
+    /// Remove updates, or some of their files, from the artifact store
+    PruneArtifacts {
+        #[command(flatten)]
+        filter: ArtifactFilter,
+        /// Comma separated kinds of files to remove. Removes the whole update if none are given.
+        #[arg(long, value_enum, value_delimiter = ',')]
+        kinds: Vec<ArtifactKind>,
+        /// Only print the updates that would be pruned
+        #[arg(long)]
+        dry_run: bool,
+    },
 }
 
+/// Commands that need the beacon, Starknet and prover endpoints
 #[derive(Subcommand)]
-enum Commands {
+enum OnlineCommands {
     /// Generate a sync committee update proof for a given slot
     CommitteeUpdate {
         #[arg(long, short)]
@@ -158,7 +85,11 @@ enum Commands {
     },
     ProveNextCommittee,
     ProveNextEpoch,
-    ProveNextEpochBatch,
+    ProveNextEpochBatch {
+        /// Favour small batches (latency) or large batches (cost)
+        #[arg(long, value_enum, default_value_t = BatchStrategy::Cost)]
+        strategy: BatchStrategy,
+    },
     CheckBatchStatus {
         #[arg(long, short)]
         batch_id: String,
@@ -166,6 +97,21 @@ enum Commands {
     SubmitWrappedProof {
         #[arg(long, short)]
         batch_id: String,
+        /// Wrap the proof archived in this directory instead of fetching it
+        #[arg(long)]
+        archive_dir: Option<String>,
+    },
+    /// Download all artifacts of an Atlantic query into the directory of the update
+    ArchiveProof {
+        #[arg(long, short)]
+        batch_id: String,
+        /// The query that wrapped the proof
+        #[arg(long, short)]
+        wrapper_id: Option<String>,
+        #[arg(long, value_enum)]
+        proof_type: UpdateKind,
+        #[arg(long, short)]
+        slot: u64,
     },
     VerifyEpoch {
         #[arg(long, short)]
@@ -189,6 +135,151 @@ enum Commands {
         #[arg(long, short)]
         block: u64,
     },
+    /// Prove execution payload fields (e.g. the state root) against the beacon header root of a slot
+    ExecutionFieldsProof {
+        #[arg(long, short)]
+        slot: u64,
+        /// Comma separated payload fields to prove
+        #[arg(long, short, value_delimiter = ',', default_values = DEFAULT_PAYLOAD_FIELDS)]
+        fields: Vec<String>,
+        /// Check the proof against the header root stored in the contract
+        #[arg(long)]
+        check_contract: bool,
+        /// Export output to a JSON file
+        #[arg(long, short)]
+        export: Option<String>,
+    },
+    /// Prove an account and its storage slots against the execution block of a verified epoch
+    StorageProof {
+        /// Slot of the verified epoch
+        #[arg(long, short)]
+        slot: u64,
+        #[arg(long, short)]
+        address: Address,
+        /// Comma separated storage slots to prove
+        #[arg(long, short = 'k', value_delimiter = ',')]
+        storage_keys: Vec<FixedBytes<32>>,
+        /// Replay execution RPC responses recorded in this directory. The committed block is
+        /// then read from the stored epoch update instead of the contract.
+        #[arg(long)]
+        fixtures: Option<String>,
+        /// Record the execution RPC responses into this directory
+        #[arg(long, conflicts_with = "fixtures")]
+        record: Option<String>,
+        /// Output the proof as calldata for a Cairo verifier
+        #[arg(long)]
+        calldata: bool,
+        /// Export output to a JSON file
+        #[arg(long, short)]
+        export: Option<String>,
+    },
+    /// Prove an older beacon block against the state of a later slot, through `block_roots`
+    /// or `historical_summaries`
+    HistoricalProof {
+        /// Slot of the block to prove
+        #[arg(long, short)]
+        target_slot: u64,
+        /// Slot of the state the proof is anchored in, e.g. a verified epoch
+        #[arg(long, short)]
+        anchor_slot: u64,
+        /// Check the proof against the state root stored in the contract for the anchor slot
+        #[arg(long)]
+        check_contract: bool,
+        /// Export output to a JSON file
+        #[arg(long, short)]
+        export: Option<String>,
+    },
+    /// Prove a verified epoch against the latest root of the local epoch accumulator
+    AccumulatorProof {
+        #[arg(long, short)]
+        slot: u64,
+        /// Export output to a JSON file
+        #[arg(long, short)]
+        export: Option<String>,
+    },
+    /// Prove fields of the beacon state or block body at a slot against the container root
+    SszProof {
+        #[arg(long, short)]
+        slot: u64,
+        #[arg(long, value_enum)]
+        container: SszContainerKind,
+        /// Field paths, e.g. `execution_payload.state_root`. Lists the fields if none are given.
+        fields: Vec<String>,
+        /// Export output to a JSON file
+        #[arg(long, short)]
+        export: Option<String>,
+    },
+    /// Prove the epochs between two slots that are missing in the contract, in batches per
+    /// committee period
+    Backfill {
+        #[arg(long)]
+        from: u64,
+        #[arg(long)]
+        to: u64,
+        /// How to find the epochs that are already available
+        #[arg(long, value_enum, default_value_t = EpochLookup::Index)]
+        lookup: EpochLookup,
+        /// Favour small batches (latency) or large batches (cost)
+        #[arg(long, value_enum, default_value_t = BatchStrategy::Cost)]
+        strategy: BatchStrategy,
+        /// Only print the missing epochs
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Serve the update inputs, the contract state and the submitted jobs over an HTTP/JSON API
+    Serve {
+        #[arg(long, default_value = "127.0.0.1:3000")]
+        addr: String,
+    },
+    /// Sync the local index of contract events and print a summary
+    IndexEvents,
+    /// Compare the program hashes of the local circuits with the config and the contract
+    Doctor,
+}
+
+/// The update types kept in the artifact store
+#[derive(Clone, Copy, ValueEnum)]
+enum UpdateKind {
+    Epoch,
+    EpochBatch,
+    Committee,
+}
+
+impl UpdateKind {
+    fn proof_type(&self) -> ProofType {
+        match self {
+            UpdateKind::Epoch => ProofType::Epoch,
+            UpdateKind::EpochBatch => ProofType::EpochBatch,
+            UpdateKind::Committee => ProofType::SyncCommittee,
+        }
+    }
+}
+
+/// Selects stored updates by type, slot range or batch root
+#[derive(Args)]
+struct ArtifactFilter {
+    #[arg(long, value_enum)]
+    proof_type: Option<UpdateKind>,
+    /// Only updates covering this slot or later ones
+    #[arg(long)]
+    from: Option<u64>,
+    /// Only updates covering this slot or earlier ones
+    #[arg(long)]
+    to: Option<u64>,
+    /// Only the batch committing to this root
+    #[arg(long)]
+    batch_root: Option<Felt>,
+}
+
+impl ArtifactFilter {
+    fn query(&self) -> ArtifactQuery {
+        ArtifactQuery {
+            proof_type: self.proof_type.map(|kind| kind.proof_type()),
+            from_slot: self.from,
+            to_slot: self.to,
+            batch_root: self.batch_root,
+        }
+    }
 }
 
 #[derive(Parser)]
@@ -198,195 +289,199 @@ struct Cli {
     #[arg(long, short)]
     rpc_url: Option<String>,
 
+    /// The backend used for proving and wrapping
+    #[arg(long, value_enum, default_value_t = ProverKind::Atlantic, global = true)]
+    prover: ProverKind,
+
+    /// Push the metrics of the run to this Prometheus Pushgateway once the command finished
+    #[arg(long, global = true)]
+    metrics_push: Option<String>,
+
+    /// Log more details (-v debug, -vv trace)
+    #[arg(long, short, action = clap::ArgAction::Count, global = true)]
+    verbose: u8,
+
+    /// Log less (-q warnings, -qq errors only)
+    #[arg(long, short, action = clap::ArgAction::Count, global = true, conflicts_with = "verbose")]
+    quiet: u8,
+
+    /// Format of the logs written to stderr
+    #[arg(long, value_enum, default_value_t = LogFormat::Text, global = true)]
+    log_format: LogFormat,
+
+    /// Format of the command result written to stdout
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text, global = true)]
+    output: OutputFormat,
+
     #[command(subcommand)]
     command: Commands,
 }
 
 #[tokio::main]
-async fn main() -> Result<(), Error> {
+async fn main() -> ExitCode {
     // Load .env.sepolia file
     from_filename(".env.sepolia").ok();
 
     let cli = Cli::parse();
-    let bankai = BankaiClient::new().await;
+    logging::init(cli.verbose, cli.quiet, cli.log_format);
+    let format = cli.output;
 
-    match cli.command {
-        Commands::ExecutionHeader { block } => {
-            let proof = ExecutionHeaderProof::fetch_proof(&bankai.client, block).await?;
-            let json = serde_json::to_string_pretty(&proof)
-                .map_err(|e| Error::DeserializeError(e.to_string()))?;
-            println!("{}", json);
-        }
-        Commands::CommitteeUpdate { slot, export } => {
-            println!("SyncCommittee command received with slot: {}", slot);
-            let proof = bankai.get_sync_committee_update(slot).await?;
-            let json = serde_json::to_string_pretty(&proof)
-                .map_err(|e| Error::DeserializeError(e.to_string()))?;
+    let command = match cli.command {
+        Commands::Offline(command) => return finish(run_offline(command), format),
+        Commands::Online(command) => command,
+    };
 
-            if let Some(path) = export {
-                match std::fs::write(path.clone(), json) {
-                    Ok(_) => println!("Proof exported to {}", path),
-                    Err(e) => return Err(Error::IoError(e)),
-                }
-            } else {
-                println!("{}", json);
-            }
+    let bankai = match BankaiClient::new().await {
+        Ok(bankai) => bankai,
+        Err(e) => return finish(Err(e), format),
+    };
+    let result = run(command, cli.prover, bankai).await;
+    if let Some(gateway) = cli.metrics_push {
+        if let Err(e) = metrics().push(&gateway, "bankai_client").await {
+            warn!(error = %e, "Failed to push metrics");
         }
-        Commands::EpochUpdate { slot, export } => {
-            println!("Epoch command received with slot: {}", slot);
-            let proof = bankai.get_epoch_proof(slot).await?;
-            let json = serde_json::to_string_pretty(&proof)
-                .map_err(|e| Error::DeserializeError(e.to_string()))?;
+    }
+    finish(result, format)
+}
 
-            if let Some(path) = export {
-                match std::fs::write(path.clone(), json) {
-                    Ok(_) => println!("Proof exported to {}", path),
-                    Err(e) => return Err(Error::IoError(e)),
-                }
-            } else {
-                println!("{}", json);
-            }
+/// Prints the result of the command and returns its exit code
+fn finish(result: Result<Report, Error>, format: OutputFormat) -> ExitCode {
+    match result {
+        Ok(report) => report.print(format),
+        Err(e) => {
+            error!(error = %e, "Command failed");
+            Report::failed(&e).print(format)
         }
-        Commands::ContractInit { slot, export } => {
-            println!("ContractInit command received with slot: {}", slot);
-            let contract_init = bankai
-                .get_contract_initialization_data(slot, &bankai.config)
-                .await?;
-            let json = serde_json::to_string_pretty(&contract_init)
-                .map_err(|e| Error::DeserializeError(e.to_string()))?;
+    }
+}
+
+/// Checking stored inputs and managing the artifact store need no endpoint
+fn run_offline(command: OfflineCommands) -> Result<Report, Error> {
+    match command {
+        OfflineCommands::VerifyInputs { file } => commands::verify_inputs(&file),
+        OfflineCommands::ListArtifacts { filter } => commands::list_artifacts(&filter.query()),
+        OfflineCommands::PruneArtifacts {
+            filter,
+            kinds,
+            dry_run,
+        } => commands::prune_artifacts(&filter.query(), &kinds, dry_run),
+    }
+}
 
-            if let Some(path) = export {
-                match std::fs::write(path.clone(), json) {
-                    Ok(_) => println!("Contract initialization data exported to {}", path),
-                    Err(e) => return Err(Error::IoError(e)),
-                }
-            } else {
-                println!("{}", json);
-            }
+async fn run(
+    command: OnlineCommands,
+    prover: ProverKind,
+    mut bankai: BankaiClient,
+) -> Result<Report, Error> {
+    match command {
+        OnlineCommands::ExecutionHeader { block } => {
+            commands::execution_header(&bankai, block).await
+        }
+        OnlineCommands::ExecutionFieldsProof {
+            slot,
+            fields,
+            check_contract,
+            export,
+        } => commands::execution_fields_proof(&bankai, slot, &fields, check_contract, export).await,
+        OnlineCommands::StorageProof {
+            slot,
+            address,
+            storage_keys,
+            fixtures,
+            record,
+            calldata,
+            export,
+        } => {
+            let source = match fixtures {
+                Some(dir) => ExecutionSource::Fixtures(dir),
+                None => ExecutionSource::Rpc { record },
+            };
+            commands::storage_proof(
+                &bankai,
+                slot,
+                address,
+                &storage_keys,
+                source,
+                calldata,
+                export,
+            )
+            .await
         }
-        Commands::DeployContract { slot } => {
-            let contract_init = bankai
-                .get_contract_initialization_data(slot, &bankai.config)
-                .await?;
-            bankai
-                .starknet_client
-                .deploy_contract(contract_init, &bankai.config)
-                .await?;
+        OnlineCommands::HistoricalProof {
+            target_slot,
+            anchor_slot,
+            check_contract,
+            export,
+        } => {
+            commands::historical_proof(&bankai, target_slot, anchor_slot, check_contract, export)
+                .await
         }
-        Commands::CheckBatchStatus { batch_id } => {
-            let status = bankai
-                .atlantic_client
-                .check_batch_status(batch_id.as_str())
-                .await?;
-            println!("Batch Status: {}", status);
+        OnlineCommands::AccumulatorProof { slot, export } => {
+            commands::accumulator_proof(&bankai, slot, export)
         }
-        Commands::ProveNextCommittee => {
-            let latest_committee_id = bankai
-                .starknet_client
-                .get_latest_committee_id(&bankai.config)
-                .await?;
-            let lowest_committee_update_slot = (latest_committee_id) * Felt::from(0x2000);
-            println!("Min Slot Required: {}", lowest_committee_update_slot);
-            let latest_epoch = bankai
-                .starknet_client
-                .get_latest_epoch_slot(&bankai.config)
-                .await?;
-            println!("Latest epoch: {}", latest_epoch);
-            if latest_epoch < lowest_committee_update_slot {
-                return Err(Error::RequiresNewerEpoch(latest_epoch));
-            }
-            let update = bankai
-                .get_sync_committee_update(latest_epoch.try_into().unwrap())
-                .await?;
-            CairoRunner::generate_pie(&update, &bankai.config)?;
-            let batch_id = bankai.atlantic_client.submit_batch(update).await?;
-            println!("Batch Submitted: {}", batch_id);
+        OnlineCommands::SszProof {
+            slot,
+            container,
+            fields,
+            export,
+        } => commands::ssz_proof(&bankai, slot, container, &fields, export).await,
+        OnlineCommands::CommitteeUpdate { slot, export } => {
+            commands::committee_update(&bankai, slot, export).await
         }
-        Commands::ProveNextEpoch => {
-            let latest_epoch = bankai
-                .starknet_client
-                .get_latest_epoch_slot(&bankai.config)
-                .await?;
-            println!("Latest Epoch: {}", latest_epoch);
-            // make sure next_epoch % 32 == 0
-            let next_epoch = (u64::try_from(latest_epoch).unwrap() / 32) * 32 + 32;
-            println!("Fetching Inputs for Epoch: {}", next_epoch);
-            let proof = bankai.get_epoch_proof(next_epoch).await?;
-            CairoRunner::generate_pie(&proof, &bankai.config)?;
-            let batch_id = bankai.atlantic_client.submit_batch(proof).await?;
-            println!("Batch Submitted: {}", batch_id);
+        OnlineCommands::EpochUpdate { slot, export } => {
+            commands::epoch_update(&bankai, slot, export).await
         }
-        Commands::ProveNextEpochBatch => {
-            let proof = EpochUpdateBatch::new(&bankai).await?;
-            CairoRunner::generate_pie(&proof, &bankai.config)?;
-            let batch_id = bankai.atlantic_client.submit_batch(proof).await?;
-            println!("Batch Submitted: {}", batch_id);
+        OnlineCommands::ContractInit { slot, export } => {
+            commands::contract_init(&bankai, slot, export).await
         }
-        Commands::VerifyEpoch { batch_id, slot } => {
-            let status = bankai
-                .atlantic_client
-                .check_batch_status(batch_id.as_str())
-                .await?;
-            if status == "DONE" {
-                let update = EpochUpdate::from_json::<EpochUpdate>(slot)?;
-                bankai
-                    .starknet_client
-                    .submit_update(update.expected_circuit_outputs, &bankai.config)
-                    .await?;
-                println!("Successfully submitted epoch update");
-            } else {
-                println!("Batch not completed yet. Status: {}", status);
-            }
+        OnlineCommands::DeployContract { slot } => {
+            commands::deploy_contract(&mut bankai, slot).await
         }
-        Commands::VerifyEpochBatch { batch_id, slot } => {
-            let status = bankai
-                .atlantic_client
-                .check_batch_status(batch_id.as_str())
-                .await?;
-            if status == "DONE" {
-                let update = EpochUpdateBatch::from_json::<EpochUpdateBatch>(slot)?;
-                bankai
-                    .starknet_client
-                    .submit_update(update.expected_circuit_outputs, &bankai.config)
-                    .await?;
-                println!("Successfully submitted epoch update");
-            } else {
-                println!("Batch not completed yet. Status: {}", status);
-            }
+        OnlineCommands::CheckBatchStatus { batch_id } => {
+            commands::check_batch_status(&bankai, prover, batch_id).await
         }
-        Commands::VerifyCommittee { batch_id, slot } => {
-            let status = bankai
-                .atlantic_client
-                .check_batch_status(batch_id.as_str())
-                .await?;
-            if status == "DONE" {
-                let update = SyncCommitteeUpdate::from_json::<SyncCommitteeUpdate>(slot)?;
-                bankai
-                    .starknet_client
-                    .submit_update(update.expected_circuit_outputs, &bankai.config)
-                    .await?;
-                println!("Successfully submitted sync committee update");
-            } else {
-                println!("Batch not completed yet. Status: {}", status);
-            }
+        OnlineCommands::ProveNextCommittee => commands::prove_next_committee(&bankai, prover).await,
+        OnlineCommands::ProveNextEpoch => commands::prove_next_epoch(&bankai, prover).await,
+        OnlineCommands::ProveNextEpochBatch { strategy } => {
+            commands::prove_next_epoch_batch(&bankai, prover, strategy).await
         }
-        Commands::SubmitWrappedProof { batch_id } => {
-            let status = bankai
-                .atlantic_client
-                .check_batch_status(batch_id.as_str())
-                .await?;
-            if status == "DONE" {
-                let proof = bankai
-                    .atlantic_client
-                    .fetch_proof(batch_id.as_str())
-                    .await?;
-                let batch_id = bankai.atlantic_client.submit_wrapped_proof(proof).await?;
-                println!("Batch Submitted: {}", batch_id);
-            } else {
-                println!("Batch not completed yet. Status: {}", status);
-            }
+        OnlineCommands::VerifyEpoch { batch_id, slot } => {
+            commands::verify_epoch(&bankai, prover, batch_id, slot).await
+        }
+        OnlineCommands::VerifyEpochBatch { batch_id, slot } => {
+            commands::verify_epoch_batch(&bankai, prover, batch_id, slot).await
+        }
+        OnlineCommands::VerifyCommittee { batch_id, slot } => {
+            commands::verify_committee(&bankai, prover, batch_id, slot).await
+        }
+        OnlineCommands::Doctor => commands::doctor(&bankai).await,
+        OnlineCommands::Backfill {
+            from,
+            to,
+            lookup,
+            strategy,
+            dry_run,
+        } => commands::backfill(&bankai, prover, from, to, lookup, strategy, dry_run).await,
+        OnlineCommands::Serve { addr } => commands::serve(bankai, &addr).await,
+        OnlineCommands::IndexEvents => commands::index_events(&bankai).await,
+        OnlineCommands::SubmitWrappedProof {
+            batch_id,
+            archive_dir,
+        } => commands::submit_wrapped_proof(&bankai, prover, batch_id, archive_dir).await,
+        OnlineCommands::ArchiveProof {
+            batch_id,
+            wrapper_id,
+            proof_type,
+            slot,
+        } => {
+            commands::archive_proof(
+                &bankai,
+                &batch_id,
+                wrapper_id.as_deref(),
+                proof_type.proof_type(),
+                slot,
+            )
+            .await
         }
     }
-
-    Ok(())
 }
@@ -0,0 +1,188 @@
+use std::process::ExitCode;
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+use tracing::info;
+
+use starknet_crypto::Felt;
+
+use crate::input_verifier::VerificationReport;
+use crate::traits::Provable;
+use crate::Error;
+
+/// Exit code of a command that completed
+pub const EXIT_SUCCESS: u8 = 0;
+/// Exit code of a command that failed
+pub const EXIT_FAILED: u8 = 1;
+/// Exit code of a command that waits for a prover job that is not done yet. Running it again
+/// later may succeed. (2 is used for invalid arguments.)
+pub const EXIT_NOT_READY: u8 = 3;
+
+/// Format of the command results written to stdout
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum OutputFormat {
+    /// Human readable text. Proofs and inputs are printed as pretty JSON.
+    Text,
+    /// A single JSON object per command
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Success,
+    /// The prover job the command depends on is not done yet
+    NotReady,
+    Failed,
+}
+
+impl Status {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Status::Success => "success",
+            Status::NotReady => "not_ready",
+            Status::Failed => "failed",
+        }
+    }
+
+    pub fn exit_code(&self) -> ExitCode {
+        ExitCode::from(match self {
+            Status::Success => EXIT_SUCCESS,
+            Status::NotReady => EXIT_NOT_READY,
+            Status::Failed => EXIT_FAILED,
+        })
+    }
+}
+
+/// The result of a command: the fields of its JSON object and the lines printed in text mode
+pub struct Report {
+    status: Status,
+    fields: Map<String, Value>,
+    lines: Vec<String>,
+}
+
+impl Report {
+    pub fn success() -> Self {
+        Self {
+            status: Status::Success,
+            fields: Map::new(),
+            lines: vec![],
+        }
+    }
+
+    /// The job is still queued or running at the prover
+    pub fn not_ready(job_id: &str, job_status: &str) -> Self {
+        Self {
+            status: Status::NotReady,
+            fields: Map::from_iter([
+                ("job_id".to_string(), Value::from(job_id)),
+                ("job_status".to_string(), Value::from(job_status)),
+            ]),
+            lines: vec![format!("Batch not completed yet. Status: {}", job_status)],
+        }
+    }
+
+    /// A proof was submitted to the prover
+    pub fn submitted(job_id: &str, proof: &impl Provable) -> Result<Self, Error> {
+        Ok(Self::success()
+            .field("job_id", job_id)?
+            .field("proof_type", proof.proof_type().name())?
+            .field("slot", proof.slot())?
+            .field("n_epochs", proof.n_epochs())?
+            .line(format!("Batch Submitted: {}", job_id)))
+    }
+
+    /// The proven update was submitted to the contract
+    pub fn verified(job_id: &str, slot: u64, tx_hash: Felt) -> Result<Self, Error> {
+        Self::success()
+            .field("job_id", job_id)?
+            .field("slot", slot)?
+            .field("tx_hash", format!("{:#x}", tx_hash))
+    }
+
+    /// The checks of `verify-inputs`. Fails if any check failed.
+    pub fn verification(verification: &VerificationReport) -> Result<Self, Error> {
+        let status = if verification.passed() {
+            Status::Success
+        } else {
+            Status::Failed
+        };
+        let mut report = Self {
+            status,
+            fields: Map::new(),
+            lines: vec![],
+        }
+        .field("proof_type", verification.proof_type.name())?
+        .field("slot", verification.slot)?
+        .field("checks", &verification.checks)?;
+        for check in verification.checks.iter() {
+            report = report.line(check.to_string());
+        }
+        let n_passed = verification
+            .checks
+            .iter()
+            .filter(|check| check.passed)
+            .count();
+        Ok(report.line(format!(
+            "{} of {} checks passed",
+            n_passed,
+            verification.checks.len()
+        )))
+    }
+
+    /// The command failed. The error is only part of the JSON object, in text mode it is
+    /// logged.
+    pub fn failed(error: &Error) -> Self {
+        Self {
+            status: Status::Failed,
+            fields: Map::from_iter([("error".to_string(), Value::from(error.to_string()))]),
+            lines: vec![],
+        }
+    }
+
+    /// Adds a field to the JSON object
+    pub fn field(mut self, key: &str, value: impl Serialize) -> Result<Self, Error> {
+        let value =
+            serde_json::to_value(value).map_err(|e| Error::DeserializeError(e.to_string()))?;
+        self.fields.insert(key.to_string(), value);
+        Ok(self)
+    }
+
+    /// Adds a line to the text output
+    pub fn line(mut self, line: impl Into<String>) -> Self {
+        self.lines.push(line.into());
+        self
+    }
+
+    /// Adds data such as a proof, which is the `data` field of the JSON object and printed as
+    /// pretty JSON in text mode. If `export` is set, the data is written to the file instead.
+    pub fn data(self, data: &impl Serialize, export: Option<String>) -> Result<Self, Error> {
+        let json = serde_json::to_string_pretty(data)
+            .map_err(|e| Error::DeserializeError(e.to_string()))?;
+        match export {
+            Some(path) => {
+                std::fs::write(&path, json).map_err(Error::IoError)?;
+                info!(%path, "Exported");
+                self.field("exported_to", path)
+            }
+            None => self.line(json).field("data", data),
+        }
+    }
+
+    /// Writes the report to stdout and returns the exit code of its status
+    pub fn print(mut self, format: OutputFormat) -> ExitCode {
+        match format {
+            OutputFormat::Text => {
+                for line in self.lines.iter() {
+                    println!("{}", line);
+                }
+            }
+            OutputFormat::Json => {
+                self.fields
+                    .insert("status".to_string(), Value::from(self.status.name()));
+                println!("{}", Value::Object(self.fields));
+            }
+        }
+        self.status.exit_code()
+    }
+}
@@ -0,0 +1,190 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::Serialize;
+use serde_json::json;
+use tracing::{info, warn};
+
+use crate::client::{BankaiClient, ProverKind};
+use crate::epoch_batch::{EpochDecommitment, EpochUpdateBatch};
+use crate::epoch_update::EpochUpdate;
+use crate::execution_header::ExecutionHeaderProof;
+use crate::sync_committee::SyncCommitteeUpdate;
+use crate::utils::job_log::{Job, JobLog};
+use crate::utils::metrics::metrics;
+use crate::utils::starknet_client::StoredEpochProof;
+use crate::Error;
+
+type ApiResult<T> = Result<Json<T>, ApiError>;
+
+/// An error returned to the caller as `{"error": "..."}`
+struct ApiError(StatusCode, String);
+
+impl From<Error> for ApiError {
+    fn from(e: Error) -> Self {
+        let status = match &e {
+            Error::EmptySlotDetected(_)
+            | Error::BlockNotFound
+            | Error::EpochNotBatched(_)
+            | Error::ArtifactNotFound(_)
+            | Error::MissingExecutionPayload(_) => StatusCode::NOT_FOUND,
+            Error::IoError(io) if io.kind() == std::io::ErrorKind::NotFound => {
+                StatusCode::NOT_FOUND
+            }
+            Error::RpcError(_)
+            | Error::AtlanticError(_)
+            | Error::StarknetError(_)
+            | Error::FetchSyncCommitteeError
+            | Error::FailedFetchingBeaconState => StatusCode::BAD_GATEWAY,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        ApiError(status, e.to_string())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(json!({ "error": self.1 }))).into_response()
+    }
+}
+
+/// A submitted job and the status reported by its prover backend
+#[derive(Serialize)]
+struct JobStatus {
+    #[serde(flatten)]
+    job: Job,
+    status: String,
+}
+
+/// Serves the API on `addr` until the process is stopped
+pub async fn serve(bankai: BankaiClient, addr: &str) -> Result<(), Error> {
+    let app = Router::new()
+        .route("/epoch/:slot", get(epoch_update))
+        .route("/committee/:slot", get(committee_update))
+        .route("/execution-header/:block", get(execution_header))
+        .route("/state/latest-epoch", get(latest_epoch))
+        .route("/state/committees/:committee_id", get(committee_hash))
+        .route("/state/epochs/:slot", get(stored_epoch))
+        .route("/jobs", get(jobs))
+        .route("/jobs/:job_id", get(job))
+        .route("/batches/:slot/decommitment", get(decommitment))
+        .route("/metrics", get(prometheus_metrics))
+        .with_state(Arc::new(bankai));
+
+    let listener = tokio::net::TcpListener::bind(addr)
+        .await
+        .map_err(Error::IoError)?;
+    info!(addr, "Listening");
+    axum::serve(listener, app).await.map_err(Error::IoError)
+}
+
+async fn epoch_update(
+    State(bankai): State<Arc<BankaiClient>>,
+    Path(slot): Path<u64>,
+) -> ApiResult<EpochUpdate> {
+    Ok(Json(bankai.get_epoch_proof(slot).await?))
+}
+
+async fn committee_update(
+    State(bankai): State<Arc<BankaiClient>>,
+    Path(slot): Path<u64>,
+) -> ApiResult<SyncCommitteeUpdate> {
+    Ok(Json(bankai.get_sync_committee_update(slot).await?))
+}
+
+async fn execution_header(
+    State(bankai): State<Arc<BankaiClient>>,
+    Path(block): Path<u64>,
+) -> ApiResult<ExecutionHeaderProof> {
+    Ok(Json(
+        ExecutionHeaderProof::fetch_proof(&bankai.client, block).await?,
+    ))
+}
+
+async fn latest_epoch(State(bankai): State<Arc<BankaiClient>>) -> ApiResult<serde_json::Value> {
+    let slot = bankai
+        .starknet_client
+        .get_latest_epoch_slot(&bankai.config)
+        .await?;
+    Ok(Json(json!({ "slot": slot })))
+}
+
+async fn committee_hash(
+    State(bankai): State<Arc<BankaiClient>>,
+    Path(committee_id): Path<u64>,
+) -> ApiResult<serde_json::Value> {
+    let committee_hash = bankai
+        .starknet_client
+        .get_committee_hash(committee_id, &bankai.config)
+        .await?;
+    if committee_hash.is_zero() {
+        return Err(ApiError(
+            StatusCode::NOT_FOUND,
+            format!("Committee {} is not stored", committee_id),
+        ));
+    }
+    Ok(Json(json!({
+        "committee_id": committee_id,
+        "committee_hash": committee_hash,
+    })))
+}
+
+async fn stored_epoch(
+    State(bankai): State<Arc<BankaiClient>>,
+    Path(slot): Path<u64>,
+) -> ApiResult<StoredEpochProof> {
+    let epoch = bankai
+        .starknet_client
+        .get_epoch_proof(slot, &bankai.config)
+        .await?;
+    if epoch.header_root.is_zero() {
+        return Err(ApiError(
+            StatusCode::NOT_FOUND,
+            format!("No epoch stored for slot {}", slot),
+        ));
+    }
+    Ok(Json(epoch))
+}
+
+async fn jobs(State(bankai): State<Arc<BankaiClient>>) -> ApiResult<Vec<Job>> {
+    Ok(Json(JobLog::load(&bankai.config.job_log_path)?.jobs))
+}
+
+async fn job(
+    State(bankai): State<Arc<BankaiClient>>,
+    Path(job_id): Path<String>,
+) -> ApiResult<JobStatus> {
+    let log = JobLog::load(&bankai.config.job_log_path)?;
+    let job = log
+        .get(&job_id)
+        .cloned()
+        .ok_or_else(|| ApiError(StatusCode::NOT_FOUND, format!("Unknown job {}", job_id)))?;
+    let prover = ProverKind::from_name(&job.prover).ok_or_else(|| {
+        ApiError(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Unknown prover {}", job.prover),
+        )
+    })?;
+    let status = bankai.proof_status(prover, &job.job_id).await?;
+    Ok(Json(JobStatus { job, status }))
+}
+
+async fn decommitment(
+    State(bankai): State<Arc<BankaiClient>>,
+    Path(slot): Path<u64>,
+) -> ApiResult<EpochDecommitment> {
+    let batch = EpochUpdateBatch::find_containing(&bankai.store, slot)?;
+    Ok(Json(batch.decommitment(slot)?))
+}
+
+/// Metrics in the Prometheus text format. The chain state is refreshed on every scrape.
+async fn prometheus_metrics(State(bankai): State<Arc<BankaiClient>>) -> Result<String, ApiError> {
+    if let Err(e) = bankai.update_chain_metrics().await {
+        warn!(error = %e, "Failed to update chain metrics");
+    }
+    Ok(metrics().encode()?)
+}
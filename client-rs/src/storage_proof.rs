@@ -0,0 +1,152 @@
+use alloy_primitives::{keccak256, Address, Bytes, FixedBytes, U256};
+use alloy_trie::{proof::verify_proof, Nibbles, TrieAccount};
+use serde::{Deserialize, Serialize};
+use starknet_crypto::Felt;
+
+use crate::utils::execution_rpc::{AccountProofResponse, ExecutionBlockHeader, ExecutionRpcClient};
+use crate::Error;
+
+/// An execution block committed to by a verified epoch
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct CommittedExecutionHeader {
+    pub hash: FixedBytes<32>,
+    pub height: u64,
+}
+
+/// Proof of an account and some of its storage slots against the state root of an execution
+/// block whose hash was committed to by an epoch update.
+///
+/// The chain of trust is: committed block hash -> RLP encoded header -> state root -> account
+/// (MPT proof) -> storage root -> storage values (MPT proofs).
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AccountStorageProof {
+    pub committed_header: CommittedExecutionHeader,
+    pub header: ExecutionBlockHeader,
+    pub account: AccountProofResponse,
+}
+
+impl AccountStorageProof {
+    /// Fetches the header and proofs at the committed block and verifies them
+    pub async fn fetch_proof(
+        client: &ExecutionRpcClient,
+        committed_header: CommittedExecutionHeader,
+        address: Address,
+        storage_keys: &[FixedBytes<32>],
+    ) -> Result<AccountStorageProof, Error> {
+        let header = client.get_block_header(committed_header.height).await?;
+        let account = client
+            .get_proof(address, storage_keys, committed_header.height)
+            .await?;
+
+        let proof = AccountStorageProof {
+            committed_header,
+            header,
+            account,
+        };
+        proof.verify()?;
+        Ok(proof)
+    }
+
+    /// Verifies the header against the committed hash and the MPT proofs against the header
+    pub fn verify(&self) -> Result<(), Error> {
+        let block_hash = self.header.compute_hash();
+        if block_hash != self.committed_header.hash
+            || self.header.number != U256::from(self.committed_header.height)
+        {
+            return Err(Error::InvalidExecutionProof(format!(
+                "Header {} with hash {} does not match committed block {} with hash {}",
+                self.header.number,
+                block_hash,
+                self.committed_header.height,
+                self.committed_header.hash
+            )));
+        }
+
+        let nonce = u64::try_from(self.account.nonce).map_err(|_| {
+            Error::InvalidExecutionProof(format!(
+                "Nonce {} of account {} exceeds 64 bits",
+                self.account.nonce, self.account.address
+            ))
+        })?;
+        let account = TrieAccount {
+            nonce,
+            balance: self.account.balance,
+            storage_root: self.account.storage_hash,
+            code_hash: self.account.code_hash,
+        };
+        // Accounts that don't exist are proven by exclusion
+        let expected_account =
+            (account != TrieAccount::default()).then(|| alloy_rlp::encode(account));
+        verify_proof(
+            self.header.state_root,
+            Nibbles::unpack(keccak256(self.account.address)),
+            expected_account,
+            self.account.account_proof.iter(),
+        )
+        .map_err(|e| {
+            Error::InvalidExecutionProof(format!("Account {}: {}", self.account.address, e))
+        })?;
+
+        for slot in self.account.storage_proof.iter() {
+            let key = FixedBytes::<32>::from(slot.key.to_be_bytes::<32>());
+            // Zero values are not stored in the trie
+            let expected_value = (!slot.value.is_zero()).then(|| alloy_rlp::encode(slot.value));
+            verify_proof(
+                self.account.storage_hash,
+                Nibbles::unpack(keccak256(key)),
+                expected_value,
+                slot.proof.iter(),
+            )
+            .map_err(|e| Error::InvalidExecutionProof(format!("Storage slot {}: {}", key, e)))?;
+        }
+        Ok(())
+    }
+
+    /// Serializes the proof as calldata for a Cairo verifier.
+    ///
+    /// u256 values are split into (low, high) and trie nodes are encoded as
+    /// `[byte_len, n_words, words...]` with 8 byte little endian words.
+    pub fn to_calldata(&self) -> Vec<Felt> {
+        let mut calldata = vec![];
+        push_u256(
+            &mut calldata,
+            U256::from_be_bytes(self.committed_header.hash.0),
+        );
+        calldata.push(Felt::from(self.committed_header.height));
+        push_bytes(&mut calldata, &self.header.rlp());
+
+        calldata.push(Felt::from_bytes_be_slice(self.account.address.as_slice()));
+        push_nodes(&mut calldata, &self.account.account_proof);
+
+        calldata.push(Felt::from(self.account.storage_proof.len()));
+        for slot in self.account.storage_proof.iter() {
+            push_u256(&mut calldata, slot.key);
+            push_u256(&mut calldata, slot.value);
+            push_nodes(&mut calldata, &slot.proof);
+        }
+        calldata
+    }
+}
+
+fn push_u256(calldata: &mut Vec<Felt>, value: U256) {
+    let bytes = value.to_be_bytes::<32>();
+    calldata.push(Felt::from_bytes_be_slice(&bytes[16..32]));
+    calldata.push(Felt::from_bytes_be_slice(&bytes[0..16]));
+}
+
+fn push_bytes(calldata: &mut Vec<Felt>, bytes: &[u8]) {
+    calldata.push(Felt::from(bytes.len()));
+    calldata.push(Felt::from(bytes.len().div_ceil(8)));
+    for chunk in bytes.chunks(8) {
+        let mut word = [0u8; 8];
+        word[..chunk.len()].copy_from_slice(chunk);
+        calldata.push(Felt::from(u64::from_le_bytes(word)));
+    }
+}
+
+fn push_nodes(calldata: &mut Vec<Felt>, nodes: &[Bytes]) {
+    calldata.push(Felt::from(nodes.len()));
+    for node in nodes {
+        push_bytes(calldata, node);
+    }
+}
@@ -1,11 +1,13 @@
-use std::fs;
-
 use crate::traits::{ProofType, Provable};
 use crate::utils::rpc::BeaconRpcClient;
 use crate::Error;
 use crate::{
     traits::Submittable,
-    utils::{hashing::get_committee_hash, merkle},
+    utils::{
+        hashing::{get_committee_hash, selector},
+        merkle,
+        ssz_proof::NEXT_SYNC_COMMITTEE_GINDEX,
+    },
 };
 use alloy_primitives::FixedBytes;
 use beacon_state_proof::state_proof_fetcher::StateProofFetcher;
@@ -13,9 +15,10 @@ use beacon_state_proof::state_proof_fetcher::{SyncCommitteeProof, TreeHash};
 use bls12_381::G1Affine;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
-use starknet::core::types::Felt;
-use starknet::macros::selector;
+use starknet_crypto::Felt;
 
+/// The inputs and expected outputs of the committee update circuit, which proves the next sync
+/// committee against the state root of a verified slot
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SyncCommitteeUpdate {
     /// The circuit inputs
@@ -25,6 +28,7 @@ pub struct SyncCommitteeUpdate {
 }
 
 impl SyncCommitteeUpdate {
+    /// Fetches the proof of the next sync committee in the state at `slot`
     pub async fn new(client: &BeaconRpcClient, slot: u64) -> Result<SyncCommitteeUpdate, Error> {
         let state_proof_fetcher = StateProofFetcher::new(client.rpc_url.clone());
         let proof = state_proof_fetcher
@@ -33,7 +37,7 @@ impl SyncCommitteeUpdate {
             .map_err(|_| Error::FailedFetchingBeaconState)?;
 
         let circuit_inputs = CommitteeCircuitInputs::from(proof);
-        let expected_circuit_outputs = ExpectedCircuitOutputs::from_inputs(&circuit_inputs);
+        let expected_circuit_outputs = ExpectedCircuitOutputs::from_inputs(&circuit_inputs)?;
 
         Ok(SyncCommitteeUpdate {
             circuit_inputs,
@@ -50,38 +54,16 @@ impl Provable for SyncCommitteeUpdate {
         hex::encode(hasher.finalize().as_slice())
     }
 
-    fn export(&self) -> Result<String, Error> {
-        let json = serde_json::to_string_pretty(&self).unwrap();
-        let dir_path = format!("batches/committee/{}", self.circuit_inputs.beacon_slot);
-        fs::create_dir_all(&dir_path).map_err(Error::IoError)?;
-
-        let path = format!(
-            "{}/input_{}.json",
-            dir_path, self.circuit_inputs.beacon_slot
-        );
-        fs::write(path.clone(), json).map_err(Error::IoError)?;
-        Ok(path)
-    }
-
-    fn from_json<T>(slot: u64) -> Result<T, Error>
-    where
-        T: serde::de::DeserializeOwned,
-    {
-        let path = format!("batches/committee/{}/input_{}.json", slot, slot);
-        let json: String = fs::read_to_string(path).map_err(Error::IoError)?;
-        serde_json::from_str(&json).map_err(|e| Error::DeserializeError(e.to_string()))
+    fn proof_type(&self) -> ProofType {
+        ProofType::SyncCommittee
     }
 
-    fn pie_path(&self) -> String {
-        format!(
-            "batches/committee/{}/pie_{}.zip",
-            self.circuit_inputs.beacon_slot,
-            self.id()
-        )
+    fn slot(&self) -> u64 {
+        self.circuit_inputs.beacon_slot
     }
 
-    fn proof_type(&self) -> ProofType {
-        ProofType::SyncCommittee
+    fn n_epochs(&self) -> u64 {
+        0
     }
 }
 
@@ -121,7 +103,11 @@ impl CommitteeCircuitInputs {
 
         // Compute the state root using the Merkle path
 
-        merkle::sha256::hash_path(self.next_sync_committee_branch.clone(), leaf, 55)
+        merkle::sha256::hash_path(
+            self.next_sync_committee_branch.clone(),
+            leaf,
+            NEXT_SYNC_COMMITTEE_GINDEX.0,
+        )
     }
 }
 
@@ -136,17 +122,18 @@ pub struct ExpectedCircuitOutputs {
 }
 
 impl Submittable<CommitteeCircuitInputs> for ExpectedCircuitOutputs {
-    fn from_inputs(circuit_inputs: &CommitteeCircuitInputs) -> Self {
+    fn from_inputs(circuit_inputs: &CommitteeCircuitInputs) -> Result<Self, Error> {
         let mut compressed_aggregate_pubkey = [0u8; 48];
         compressed_aggregate_pubkey
             .copy_from_slice(circuit_inputs.next_aggregate_sync_committee.as_slice());
-        let committee_hash =
-            get_committee_hash(G1Affine::from_compressed(&compressed_aggregate_pubkey).unwrap());
-        Self {
+        let aggregate_pubkey =
+            Option::from(G1Affine::from_compressed(&compressed_aggregate_pubkey))
+                .ok_or(Error::InvalidAggregatePubkey(circuit_inputs.beacon_slot))?;
+        Ok(Self {
             state_root: circuit_inputs.compute_state_root(),
             slot: circuit_inputs.beacon_slot,
-            committee_hash,
-        }
+            committee_hash: get_committee_hash(aggregate_pubkey),
+        })
     }
 
     fn to_calldata(&self) -> Vec<Felt> {
@@ -162,7 +149,7 @@ impl Submittable<CommitteeCircuitInputs> for ExpectedCircuitOutputs {
     }
 
     fn get_contract_selector(&self) -> Felt {
-        selector!("verify_committee_update")
+        selector("verify_committee_update")
     }
 }
 
@@ -1,5 +1,5 @@
-use serde::Serialize;
-use starknet::core::types::Felt;
+use serde::{Deserialize, Serialize};
+use starknet_crypto::Felt;
 
 use crate::Error;
 
@@ -7,21 +7,65 @@ use crate::Error;
 pub trait Submittable<T> {
     fn get_contract_selector(&self) -> Felt;
     fn to_calldata(&self) -> Vec<Felt>;
-    fn from_inputs(circuit_inputs: &T) -> Self;
+    fn from_inputs(circuit_inputs: &T) -> Result<Self, Error>
+    where
+        Self: Sized;
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ProofType {
     Epoch,
     EpochBatch,
     SyncCommittee,
 }
 
+impl ProofType {
+    /// Name used in Atlantic external ids and metric labels
+    pub fn name(&self) -> &'static str {
+        match self {
+            ProofType::Epoch => "epoch",
+            ProofType::EpochBatch => "epoch_batch",
+            ProofType::SyncCommittee => "sync_committee",
+        }
+    }
+}
+
+/// An update that is proven by a circuit. Its inputs and artifacts are kept in the
+/// `ArtifactStore`, keyed by `id`.
 pub trait Provable: Serialize {
     fn id(&self) -> String;
-    fn export(&self) -> Result<String, Error>;
-    fn from_json<T>(slot: u64) -> Result<T, Error>
-    where
-        T: serde::de::DeserializeOwned;
     fn proof_type(&self) -> ProofType;
-    fn pie_path(&self) -> String;
+    /// The slot the stored inputs are looked up by, e.g. by the verify commands
+    fn slot(&self) -> u64;
+    /// Slot of the first epoch covered by the update
+    fn first_slot(&self) -> u64 {
+        self.slot()
+    }
+    /// Root of the epochs committed to by a batch
+    fn batch_root(&self) -> Option<Felt> {
+        None
+    }
+    /// Number of epochs verified by the proof
+    fn n_epochs(&self) -> u64;
+}
+
+/// A STARK proof as returned by a prover backend
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StarkProof {
+    pub proof: serde_json::Value,
+}
+
+/// A service (or local toolchain) that proves the PIEs generated by the `CairoRunner`
+/// and wraps the resulting proofs into a layout Integrity can verify.
+// Callers use the concrete backends, so whether a future is `Send` (as required by the `serve`
+// handlers) follows from the implementation and the trait can keep using `async fn`
+#[allow(async_fn_in_trait)]
+pub trait ProverBackend {
+    /// Submits the PIE of the provable and returns the job id
+    async fn submit(&self, batch: &impl Provable) -> Result<String, Error>;
+    /// Returns the job status. Completed jobs report "DONE".
+    async fn status(&self, job_id: &str) -> Result<String, Error>;
+    async fn fetch_proof(&self, job_id: &str) -> Result<StarkProof, Error>;
+    /// Submits a proof to the recursive wrapper and returns the job id
+    async fn wrap(&self, proof: StarkProof) -> Result<String, Error>;
 }
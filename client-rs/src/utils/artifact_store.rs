@@ -0,0 +1,387 @@
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use starknet_crypto::Felt;
+
+use crate::config::BankaiConfig;
+use crate::traits::{ProofType, Provable};
+use crate::Error;
+
+const MANIFEST_FILE: &str = "manifest.json";
+const INPUTS_FILE: &str = "input.json";
+const PIE_FILE: &str = "pie.zip";
+
+/// The kinds of files stored for an update
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "snake_case")]
+pub enum ArtifactKind {
+    /// The circuit inputs and expected outputs
+    Inputs,
+    /// The PIE of the circuit run
+    Pie,
+    Proof,
+    /// The proof of the recursive wrapper, which Integrity verifies
+    WrappedProof,
+    /// Metadata of a prover job
+    Metadata,
+    /// Receipt of the transaction submitting the update to the contract
+    TxReceipt,
+}
+
+/// A file stored for an update and its checksum
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredArtifact {
+    pub kind: ArtifactKind,
+    /// File name within the directory of the update
+    pub name: String,
+    pub sha256: String,
+    pub size: usize,
+    /// Unix timestamp of the last write
+    pub stored_at: u64,
+}
+
+/// Manifest of all files stored for an update
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArtifactManifest {
+    /// `Provable::id` of the update
+    pub id: String,
+    pub network: String,
+    pub proof_type: ProofType,
+    /// Slot of the first epoch of a batch, else equal to `slot`
+    pub first_slot: u64,
+    /// The slot the update is looked up by, as passed to the verify commands
+    pub slot: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub batch_root: Option<Felt>,
+    /// Unix timestamp at which the inputs were first stored
+    pub created_at: u64,
+    pub artifacts: Vec<StoredArtifact>,
+}
+
+impl ArtifactManifest {
+    /// Total size of the stored files
+    pub fn size(&self) -> usize {
+        self.artifacts.iter().map(|artifact| artifact.size).sum()
+    }
+
+    /// Adds the file or updates its checksum
+    fn record(&mut self, kind: ArtifactKind, name: &str, bytes: &[u8]) {
+        self.artifacts.retain(|artifact| artifact.name != name);
+        self.artifacts.push(StoredArtifact {
+            kind,
+            name: name.to_string(),
+            sha256: hex::encode(Sha256::digest(bytes)),
+            size: bytes.len(),
+            stored_at: now(),
+        });
+    }
+}
+
+/// Selects the stored updates by type, slot range and batch root. Unset fields match all.
+#[derive(Debug, Clone, Default)]
+pub struct ArtifactQuery {
+    pub proof_type: Option<ProofType>,
+    /// Updates ending before this slot are not matched
+    pub from_slot: Option<u64>,
+    /// Updates starting after this slot are not matched
+    pub to_slot: Option<u64>,
+    pub batch_root: Option<Felt>,
+}
+
+impl ArtifactQuery {
+    /// Updates covering the slot, e.g. the batches containing an epoch
+    pub fn containing(proof_type: ProofType, slot: u64) -> Self {
+        Self {
+            proof_type: Some(proof_type),
+            from_slot: Some(slot),
+            to_slot: Some(slot),
+            batch_root: None,
+        }
+    }
+
+    /// Whether the query matches all updates
+    pub fn is_empty(&self) -> bool {
+        self.proof_type.is_none()
+            && self.from_slot.is_none()
+            && self.to_slot.is_none()
+            && self.batch_root.is_none()
+    }
+
+    pub fn matches(&self, manifest: &ArtifactManifest) -> bool {
+        self.proof_type
+            .is_none_or(|proof_type| proof_type == manifest.proof_type)
+            && self.from_slot.is_none_or(|slot| manifest.slot >= slot)
+            && self.to_slot.is_none_or(|slot| manifest.first_slot <= slot)
+            && self
+                .batch_root
+                .is_none_or(|root| manifest.batch_root == Some(root))
+    }
+}
+
+/// Stores the inputs, PIEs, proofs and receipts of the updates, keyed by network and
+/// `Provable::id`. Each update has its own directory `<root>/<network>/<id>` with a manifest
+/// listing its files and their checksums.
+#[derive(Debug, Clone)]
+pub struct ArtifactStore {
+    root: String,
+    network: String,
+}
+
+impl ArtifactStore {
+    pub fn new(root: &str, network: &str) -> Self {
+        Self {
+            root: root.to_string(),
+            network: network.to_string(),
+        }
+    }
+
+    pub fn from_config(config: &BankaiConfig) -> Self {
+        Self::new(&config.artifact_store_dir, &config.network)
+    }
+
+    /// Directory of the artifacts of an update
+    pub fn dir(&self, id: &str) -> String {
+        format!("{}/{}/{}", self.root, self.network, id)
+    }
+
+    /// Path of a stored file of an update
+    pub fn path(&self, id: &str, name: &str) -> String {
+        format!("{}/{}", self.dir(id), name)
+    }
+
+    /// Path the PIE of the update is written to by `cairo-run`
+    pub fn pie_path(&self, update: &impl Provable) -> String {
+        self.path(&update.id(), PIE_FILE)
+    }
+
+    /// Stores the inputs of the update, creating its manifest. Returns the path of the inputs.
+    pub fn put_inputs(&self, update: &impl Provable) -> Result<String, Error> {
+        let id = update.id();
+        if !Path::new(&self.path(&id, MANIFEST_FILE)).exists() {
+            let manifest = ArtifactManifest {
+                id: id.clone(),
+                network: self.network.clone(),
+                proof_type: update.proof_type(),
+                first_slot: update.first_slot(),
+                slot: update.slot(),
+                batch_root: update.batch_root(),
+                created_at: now(),
+                artifacts: vec![],
+            };
+            fs::create_dir_all(self.dir(&id)).map_err(Error::IoError)?;
+            self.save_manifest(&manifest)?;
+        }
+        self.put_json(&id, ArtifactKind::Inputs, INPUTS_FILE, update)
+    }
+
+    /// Records the PIE written by `cairo-run`
+    pub fn add_pie(&self, update: &impl Provable) -> Result<(), Error> {
+        self.add_file(&update.id(), ArtifactKind::Pie, PIE_FILE)
+    }
+
+    /// Writes a JSON artifact of an update whose inputs are stored
+    pub fn put_json(
+        &self,
+        id: &str,
+        kind: ArtifactKind,
+        name: &str,
+        value: &impl Serialize,
+    ) -> Result<String, Error> {
+        let json = serde_json::to_string_pretty(value)
+            .map_err(|e| Error::DeserializeError(e.to_string()))?;
+        self.put_bytes(id, kind, name, json.as_bytes())
+    }
+
+    /// Writes an artifact of an update whose inputs are stored
+    pub fn put_bytes(
+        &self,
+        id: &str,
+        kind: ArtifactKind,
+        name: &str,
+        bytes: &[u8],
+    ) -> Result<String, Error> {
+        let path = self.path(id, name);
+        let mut manifest = self.manifest(id)?;
+        fs::write(&path, bytes).map_err(Error::IoError)?;
+        manifest.record(kind, name, bytes);
+        self.save_manifest(&manifest)?;
+        Ok(path)
+    }
+
+    /// Records a file written into the directory of the update by another tool
+    pub fn add_file(&self, id: &str, kind: ArtifactKind, name: &str) -> Result<(), Error> {
+        let bytes = fs::read(self.path(id, name)).map_err(Error::IoError)?;
+        let mut manifest = self.manifest(id)?;
+        manifest.record(kind, name, &bytes);
+        self.save_manifest(&manifest)
+    }
+
+    pub fn manifest(&self, id: &str) -> Result<ArtifactManifest, Error> {
+        let json =
+            fs::read_to_string(self.path(id, MANIFEST_FILE)).map_err(|e| match e.kind() {
+                std::io::ErrorKind::NotFound => {
+                    Error::ArtifactNotFound(format!("{} on {}", id, self.network))
+                }
+                _ => Error::IoError(e),
+            })?;
+        serde_json::from_str(&json).map_err(|e| Error::DeserializeError(e.to_string()))
+    }
+
+    fn save_manifest(&self, manifest: &ArtifactManifest) -> Result<(), Error> {
+        let json = serde_json::to_string_pretty(manifest)
+            .map_err(|e| Error::DeserializeError(e.to_string()))?;
+        fs::write(self.path(&manifest.id, MANIFEST_FILE), json).map_err(Error::IoError)
+    }
+
+    /// Loads the inputs of an update, checking them against the manifest
+    pub fn load_inputs<T: DeserializeOwned>(&self, id: &str) -> Result<T, Error> {
+        let manifest = self.manifest(id)?;
+        let bytes = self.read_verified(&manifest, INPUTS_FILE)?;
+        serde_json::from_slice(&bytes).map_err(|e| Error::DeserializeError(e.to_string()))
+    }
+
+    /// Reads a stored file, failing if it was modified since it was stored
+    pub fn read_verified(&self, manifest: &ArtifactManifest, name: &str) -> Result<Vec<u8>, Error> {
+        let artifact = manifest
+            .artifacts
+            .iter()
+            .find(|artifact| artifact.name == name)
+            .ok_or_else(|| Error::ArtifactNotFound(format!("{} of {}", name, manifest.id)))?;
+        let bytes = fs::read(self.path(&manifest.id, name)).map_err(Error::IoError)?;
+        let sha256 = hex::encode(Sha256::digest(&bytes));
+        if sha256 != artifact.sha256 {
+            return Err(Error::ArtifactIntegrityError(format!(
+                "{} of {} has checksum {}, expected {}",
+                name, manifest.id, sha256, artifact.sha256
+            )));
+        }
+        Ok(bytes)
+    }
+
+    /// Manifests of the stored updates of the network, ordered by slot
+    pub fn list(&self, query: &ArtifactQuery) -> Result<Vec<ArtifactManifest>, Error> {
+        let network_dir = format!("{}/{}", self.root, self.network);
+        if !Path::new(&network_dir).exists() {
+            return Ok(vec![]);
+        }
+        let mut manifests = vec![];
+        for entry in fs::read_dir(&network_dir).map_err(Error::IoError)? {
+            let entry = entry.map_err(Error::IoError)?;
+            let id = entry.file_name().to_string_lossy().to_string();
+            // Skip directories without a manifest, e.g. of an interrupted export
+            let manifest = match self.manifest(&id) {
+                Ok(manifest) => manifest,
+                Err(Error::ArtifactNotFound(_)) => continue,
+                Err(e) => return Err(e),
+            };
+            if query.matches(&manifest) {
+                manifests.push(manifest);
+            }
+        }
+        manifests.sort_by_key(|manifest| (manifest.slot, manifest.first_slot, manifest.created_at));
+        Ok(manifests)
+    }
+
+    /// The update of the given type that is looked up by `slot`, e.g. by the verify commands
+    pub fn find_by_slot(
+        &self,
+        proof_type: ProofType,
+        slot: u64,
+    ) -> Result<ArtifactManifest, Error> {
+        let mut manifests = self.list(&ArtifactQuery::containing(proof_type, slot))?;
+        manifests.retain(|manifest| manifest.slot == slot);
+        self.single(manifests, format!("{} at slot {}", proof_type.name(), slot))
+    }
+
+    /// The batch committing to the root
+    pub fn find_by_batch_root(&self, batch_root: Felt) -> Result<ArtifactManifest, Error> {
+        let query = ArtifactQuery {
+            batch_root: Some(batch_root),
+            ..Default::default()
+        };
+        self.single(self.list(&query)?, format!("batch {:#x}", batch_root))
+    }
+
+    /// The only one of the found updates. Several updates with the same slot can exist, e.g.
+    /// batches of different sizes ending at the same epoch, and are never picked arbitrarily.
+    fn single(
+        &self,
+        mut manifests: Vec<ArtifactManifest>,
+        description: String,
+    ) -> Result<ArtifactManifest, Error> {
+        match manifests.len() {
+            0 => Err(Error::ArtifactNotFound(format!(
+                "{} on {}",
+                description, self.network
+            ))),
+            1 => Ok(manifests.remove(0)),
+            _ => Err(Error::AmbiguousArtifact(
+                description,
+                manifests.into_iter().map(|manifest| manifest.id).collect(),
+            )),
+        }
+    }
+
+    /// Loads the inputs of the update of the given type that is looked up by `slot`
+    pub fn load_by_slot<T: DeserializeOwned>(
+        &self,
+        proof_type: ProofType,
+        slot: u64,
+    ) -> Result<T, Error> {
+        let manifest = self.find_by_slot(proof_type, slot)?;
+        self.load_inputs(&manifest.id)
+    }
+
+    /// Removes the matching updates. With `kinds`, only their files of these kinds are removed
+    /// and the manifests are kept. Returns the affected manifests as they were before.
+    pub fn prune(
+        &self,
+        query: &ArtifactQuery,
+        kinds: &[ArtifactKind],
+        dry_run: bool,
+    ) -> Result<Vec<ArtifactManifest>, Error> {
+        let mut pruned = vec![];
+        for mut manifest in self.list(query)? {
+            if !kinds.is_empty()
+                && !manifest
+                    .artifacts
+                    .iter()
+                    .any(|artifact| kinds.contains(&artifact.kind))
+            {
+                continue;
+            }
+            if !dry_run {
+                if kinds.is_empty() {
+                    fs::remove_dir_all(self.dir(&manifest.id)).map_err(Error::IoError)?;
+                } else {
+                    let before = manifest.clone();
+                    for artifact in before.artifacts.iter() {
+                        if kinds.contains(&artifact.kind) {
+                            fs::remove_file(self.path(&manifest.id, &artifact.name))
+                                .map_err(Error::IoError)?;
+                        }
+                    }
+                    manifest
+                        .artifacts
+                        .retain(|artifact| !kinds.contains(&artifact.kind));
+                    self.save_manifest(&manifest)?;
+                    manifest = before;
+                }
+            }
+            pruned.push(manifest);
+        }
+        Ok(pruned)
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
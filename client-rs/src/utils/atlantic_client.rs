@@ -1,31 +1,42 @@
-use std::{env, fs};
+use std::fs;
 
-use crate::traits::{ProofType, Provable};
+use crate::config::required_env;
+use crate::traits::{Provable, ProverBackend, StarkProof};
+use crate::utils::artifact_store::ArtifactStore;
+use crate::utils::proof_archive::{ProofArchive, QUERY_ARTIFACTS};
 use crate::Error;
 use reqwest::multipart::{Form, Part};
-use serde::{Deserialize, Serialize};
+use tracing::field::Empty;
+use tracing::{debug, error, info, instrument, warn, Span};
 pub struct AtlanticClient {
     endpoint: String,
     api_key: String,
+    /// Program hash of the proof wrapper, `PROOF_WRAPPER_PROGRAM_HASH`
+    wrapper_program_hash: String,
+    /// Base URL of the registry the query artifacts are downloaded from, `PROOF_REGISTRY`
+    proof_registry: String,
+    /// Store the PIEs are read from
+    store: ArtifactStore,
     pub client: reqwest::Client,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct StarkProof {
-    pub proof: serde_json::Value,
-}
-
 impl AtlanticClient {
-    pub fn new(endpoint: String, api_key: String) -> Self {
-        Self {
+    /// Creates the client, reading the wrapper program hash and the proof registry from the
+    /// environment
+    pub fn new(endpoint: String, api_key: String, store: ArtifactStore) -> Result<Self, Error> {
+        Ok(Self {
             endpoint,
             api_key,
+            wrapper_program_hash: required_env("PROOF_WRAPPER_PROGRAM_HASH")?,
+            proof_registry: required_env("PROOF_REGISTRY")?,
+            store,
             client: reqwest::Client::new(),
-        }
+        })
     }
 
-    pub async fn submit_batch(&self, batch: impl Provable) -> Result<String, Error> {
-        let pie_path = batch.pie_path();
+    #[instrument(name = "atlantic_submit", skip_all, fields(id = %batch.id(), query_id = Empty))]
+    pub async fn submit_batch(&self, batch: &impl Provable) -> Result<String, Error> {
+        let pie_path = self.store.pie_path(batch);
 
         // Read the file as bytes
         let file_bytes = fs::read(&pie_path).map_err(Error::IoError)?;
@@ -34,14 +45,7 @@ impl AtlanticClient {
             .mime_str("application/zip") // Specify MIME type
             .map_err(Error::AtlanticError)?;
 
-        let external_id = format!(
-            "update_{}",
-            match batch.proof_type() {
-                ProofType::Epoch => "epoch",
-                ProofType::SyncCommittee => "sync_committee",
-                ProofType::EpochBatch => "epoch_batch",
-            }
-        );
+        let external_id = format!("update_{}", batch.proof_type().name());
         // Build the form
         let form = Form::new()
             .part("pieFile", file_part)
@@ -61,9 +65,9 @@ impl AtlanticClient {
             .map_err(Error::AtlanticError)?;
 
         if !response.status().is_success() {
-            println!("Error status: {}", response.status());
+            let status = response.status();
             let error_text = response.text().await.map_err(Error::AtlanticError)?;
-            println!("Error response: {}", error_text);
+            error!(%status, response = %error_text, "Submitting the PIE failed");
             return Err(Error::InvalidResponse(format!(
                 "Request failed: {}",
                 error_text
@@ -74,14 +78,18 @@ impl AtlanticClient {
         let response_data: serde_json::Value =
             response.json().await.map_err(Error::AtlanticError)?;
 
-        Ok(response_data["atlanticQueryId"]
+        let query_id = response_data["atlanticQueryId"]
             .as_str()
             .ok_or_else(|| Error::InvalidResponse("Missing atlanticQueryId".into()))?
-            .to_string())
+            .to_string();
+        Span::current().record("query_id", query_id.as_str());
+        info!("PIE submitted");
+        Ok(query_id)
     }
 
+    #[instrument(name = "atlantic_wrap", skip_all, fields(query_id = Empty))]
     pub async fn submit_wrapped_proof(&self, proof: StarkProof) -> Result<String, Error> {
-        println!("Uploading to Atlantic...");
+        info!("Uploading to Atlantic");
         // Serialize the proof to JSON string
         let proof_json =
             serde_json::to_string(&proof).map_err(|e| Error::DeserializeError(e.to_string()))?;
@@ -94,10 +102,7 @@ impl AtlanticClient {
 
         // Build the form
         let form = Form::new()
-            .text(
-                "programHash",
-                env::var("PROOF_WRAPPER_PROGRAM_HASH").unwrap(),
-            )
+            .text("programHash", self.wrapper_program_hash.clone())
             .part("inputFile", proof_part)
             .text("cairoVersion", "0")
             .text("mockFactHash", "false")
@@ -126,33 +131,101 @@ impl AtlanticClient {
         let response_data: serde_json::Value =
             response.json().await.map_err(Error::AtlanticError)?;
 
-        Ok(response_data["atlanticQueryId"]
+        let query_id = response_data["atlanticQueryId"]
             .as_str()
             .ok_or_else(|| Error::InvalidResponse("Missing atlanticQueryId".into()))?
-            .to_string())
+            .to_string();
+        Span::current().record("query_id", query_id.as_str());
+        info!("Proof submitted to the wrapper");
+        Ok(query_id)
     }
 
+    #[instrument(name = "atlantic_query", skip(self, batch_id), fields(query_id = batch_id))]
     pub async fn fetch_proof(&self, batch_id: &str) -> Result<StarkProof, Error> {
+        let bytes = self
+            .fetch_artifact(batch_id, "proof.json")
+            .await?
+            .ok_or_else(|| {
+                Error::InvalidResponse(format!("Proof for query {} not found", batch_id))
+            })?;
+        let response_data: serde_json::Value =
+            serde_json::from_slice(&bytes).map_err(|e| Error::DeserializeError(e.to_string()))?;
+
+        Ok(StarkProof {
+            proof: response_data,
+        })
+    }
+
+    /// Downloads an artifact of a query from the proof registry.
+    /// Returns None if the registry doesn't contain the artifact.
+    #[instrument(name = "atlantic_query", skip(self, batch_id), fields(query_id = batch_id))]
+    pub async fn fetch_artifact(
+        &self,
+        batch_id: &str,
+        name: &str,
+    ) -> Result<Option<Vec<u8>>, Error> {
         let response = self
             .client
             .get(format!(
-                "{}/query_{}/proof.json",
-                env::var("PROOF_REGISTRY").unwrap(),
-                batch_id
+                "{}/query_{}/{}",
+                self.proof_registry, batch_id, name
             ))
-            .header("accept", "application/json")
             .send()
             .await
             .map_err(Error::AtlanticError)?;
 
-        let response_data: serde_json::Value =
-            response.json().await.map_err(Error::AtlanticError)?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(Error::InvalidResponse(format!(
+                "Fetching {} of query {} failed: {}",
+                name,
+                batch_id,
+                response.status()
+            )));
+        }
 
-        Ok(StarkProof {
-            proof: response_data,
-        })
+        let bytes = response.bytes().await.map_err(Error::AtlanticError)?;
+        Ok(Some(bytes.to_vec()))
+    }
+
+    /// Downloads all artifacts of a query (and optionally its wrapper query) into `dir`
+    /// and records them in an archive manifest.
+    #[instrument(name = "atlantic_query", skip(self, batch_id), fields(query_id = batch_id))]
+    pub async fn archive_query(
+        &self,
+        batch_id: &str,
+        wrapper_id: Option<&str>,
+        dir: &str,
+    ) -> Result<ProofArchive, Error> {
+        let mut archive = ProofArchive::new(batch_id, wrapper_id);
+
+        let mut queries = vec![batch_id];
+        queries.extend(wrapper_id);
+        for query_id in queries {
+            for (name, required) in QUERY_ARTIFACTS {
+                match self.fetch_artifact(query_id, name).await? {
+                    Some(bytes) => archive.store(dir, query_id, name, bytes)?,
+                    None if required => {
+                        return Err(Error::InvalidResponse(format!(
+                            "{} of query {} not found",
+                            name, query_id
+                        )))
+                    }
+                    None => warn!(
+                        artifact = name,
+                        query_id, "Artifact not available, skipping"
+                    ),
+                }
+            }
+        }
+
+        archive.save(dir)?;
+        Ok(archive)
     }
 
+    #[instrument(name = "atlantic_query", skip(self, batch_id), fields(query_id = batch_id))]
     pub async fn check_batch_status(&self, batch_id: &str) -> Result<String, Error> {
         let response = self
             .client
@@ -169,7 +242,26 @@ impl AtlanticClient {
         let status = response_data["atlanticQuery"]["status"]
             .as_str()
             .ok_or_else(|| Error::InvalidResponse("Missing status field".into()))?;
+        debug!(status, "Query status");
 
         Ok(status.to_string())
     }
 }
+
+impl ProverBackend for AtlanticClient {
+    async fn submit(&self, batch: &impl Provable) -> Result<String, Error> {
+        self.submit_batch(batch).await
+    }
+
+    async fn status(&self, job_id: &str) -> Result<String, Error> {
+        self.check_batch_status(job_id).await
+    }
+
+    async fn fetch_proof(&self, job_id: &str) -> Result<StarkProof, Error> {
+        AtlanticClient::fetch_proof(self, job_id).await
+    }
+
+    async fn wrap(&self, proof: StarkProof) -> Result<String, Error> {
+        self.submit_wrapped_proof(proof).await
+    }
+}
@@ -1,28 +1,32 @@
-use crate::traits::ProofType;
+use crate::utils::artifact_store::ArtifactStore;
+use crate::utils::metrics::metrics;
 use crate::BankaiConfig;
 use crate::{traits::Provable, Error};
+use tracing::info;
 
 pub struct CairoRunner();
 
 impl CairoRunner {
-    pub fn generate_pie(input: &impl Provable, config: &BankaiConfig) -> Result<(), Error> {
-        let input_path = input.export()?;
+    /// Runs the circuit on the stored inputs and stores the PIE. Returns the number of steps
+    /// reported by `cairo-run`.
+    pub fn generate_pie(
+        input: &impl Provable,
+        config: &BankaiConfig,
+    ) -> Result<Option<u64>, Error> {
+        let store = ArtifactStore::from_config(config);
+        let input_path = store.put_inputs(input)?;
 
-        let program_path = match input.proof_type() {
-            ProofType::Epoch => config.epoch_circuit_path.clone(),
-            ProofType::SyncCommittee => config.committee_circuit_path.clone(),
-            ProofType::EpochBatch => config.epoch_batch_circuit_path.clone(),
-        };
+        let program_path = config.circuit_path(input.proof_type());
 
-        let pie_path = input.pie_path();
-        println!("Generating trace...");
+        let pie_path = store.pie_path(input);
+        info!("Generating trace");
         let start_time = std::time::Instant::now();
 
         // Execute cairo-run command
         let output = std::process::Command::new("sh")
             .arg("-c")
             .arg(format!(
-                "source ../venv/bin/activate && cairo-run --program {} --program_input {} --cairo_pie_output {} --layout=all_cairo",
+                "source ../venv/bin/activate && cairo-run --program {} --program_input {} --cairo_pie_output {} --layout=all_cairo --print_info",
                 program_path,
                 input_path,
                 pie_path
@@ -37,9 +41,27 @@ impl CairoRunner {
                 String::from_utf8_lossy(&output.stderr).to_string(),
             ));
         } else {
-            println!("Trace generated successfully in {:.2?}!", duration);
+            info!(?duration, "Trace generated");
         }
+        store.add_pie(input)?;
+        let proof_type = input.proof_type().name();
+        metrics()
+            .trace_duration
+            .with_label_values(&[proof_type])
+            .observe(duration.as_secs_f64());
 
-        Ok(())
+        // e.g. "Number of steps: 350123 (originally, 350123)."
+        let n_steps = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .find_map(|line| line.trim().strip_prefix("Number of steps:"))
+            .and_then(|steps| steps.split_whitespace().next())
+            .and_then(|steps| steps.parse().ok());
+        if let Some(n_steps) = n_steps {
+            metrics()
+                .trace_steps
+                .with_label_values(&[proof_type])
+                .observe(n_steps as f64);
+        }
+        Ok(n_steps)
     }
 }
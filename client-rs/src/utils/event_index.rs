@@ -0,0 +1,336 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::ops::RangeInclusive;
+use std::path::Path;
+
+use alloy_primitives::FixedBytes;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "starknet-client")]
+use starknet::core::types::EmittedEvent;
+use starknet_crypto::Felt;
+
+use crate::utils::hashing::selector;
+use crate::Error;
+
+/// A decoded event emitted by the Bankai contract.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum BankaiEvent {
+    CommitteeUpdated {
+        committee_id: u64,
+        committee_hash: FixedBytes<32>,
+    },
+    EpochUpdated {
+        beacon_root: FixedBytes<32>,
+        slot: u64,
+        execution_hash: FixedBytes<32>,
+        execution_height: u64,
+    },
+    EpochBatch {
+        batch_root: Felt,
+        beacon_root: FixedBytes<32>,
+        slot: u64,
+        execution_hash: FixedBytes<32>,
+        execution_height: u64,
+    },
+    EpochDecommitted {
+        batch_root: Felt,
+        slot: u64,
+        execution_hash: FixedBytes<32>,
+        execution_height: u64,
+    },
+}
+
+impl BankaiEvent {
+    /// Decodes a raw Starknet event into a `BankaiEvent`.
+    /// The first key is the selector of the event variant, all fields are serialized into `data`.
+    /// Returns `Ok(None)` for events that are not emitted by the Bankai contract.
+    pub fn decode(keys: &[Felt], data: &[Felt]) -> Result<Option<Self>, Error> {
+        let Some(event_selector) = keys.first() else {
+            return Ok(None);
+        };
+
+        let mut reader = EventDataReader::new(data);
+        let event = if *event_selector == selector("CommitteeUpdated") {
+            BankaiEvent::CommitteeUpdated {
+                committee_id: reader.read_u64()?,
+                committee_hash: reader.read_u256()?,
+            }
+        } else if *event_selector == selector("EpochUpdated") {
+            BankaiEvent::EpochUpdated {
+                beacon_root: reader.read_u256()?,
+                slot: reader.read_u64()?,
+                execution_hash: reader.read_u256()?,
+                execution_height: reader.read_u64()?,
+            }
+        } else if *event_selector == selector("EpochBatch") {
+            BankaiEvent::EpochBatch {
+                batch_root: reader.read_felt()?,
+                beacon_root: reader.read_u256()?,
+                slot: reader.read_u64()?,
+                execution_hash: reader.read_u256()?,
+                execution_height: reader.read_u64()?,
+            }
+        } else if *event_selector == selector("EpochDecommitted") {
+            BankaiEvent::EpochDecommitted {
+                batch_root: reader.read_felt()?,
+                slot: reader.read_u64()?,
+                execution_hash: reader.read_u256()?,
+                execution_height: reader.read_u64()?,
+            }
+        } else {
+            return Ok(None);
+        };
+
+        Ok(Some(event))
+    }
+}
+
+/// Sequential reader over the serialized fields of an event
+struct EventDataReader<'a> {
+    data: &'a [Felt],
+    position: usize,
+}
+
+impl<'a> EventDataReader<'a> {
+    fn new(data: &'a [Felt]) -> Self {
+        Self { data, position: 0 }
+    }
+
+    fn read_felt(&mut self) -> Result<Felt, Error> {
+        let value = self
+            .data
+            .get(self.position)
+            .copied()
+            .ok_or_else(|| Error::InvalidEvent("Unexpected end of event data".into()))?;
+        self.position += 1;
+        Ok(value)
+    }
+
+    fn read_u64(&mut self) -> Result<u64, Error> {
+        let value = self.read_felt()?;
+        u64::try_from(value).map_err(|_| Error::InvalidEvent(format!("{} is not a u64", value)))
+    }
+
+    /// u256 values are serialized as (low, high) 128 bit limbs
+    fn read_u256(&mut self) -> Result<FixedBytes<32>, Error> {
+        let low = self.read_felt()?.to_bytes_be();
+        let high = self.read_felt()?.to_bytes_be();
+        if low[..16].iter().chain(high[..16].iter()).any(|b| *b != 0) {
+            return Err(Error::InvalidEvent("u256 limb exceeds 128 bits".into()));
+        }
+
+        let mut bytes = [0u8; 32];
+        bytes[0..16].copy_from_slice(&high[16..32]);
+        bytes[16..32].copy_from_slice(&low[16..32]);
+        Ok(FixedBytes::from(bytes))
+    }
+}
+
+/// How an epoch ended up in the contract storage
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EpochSource {
+    /// Verified with a single epoch update proof
+    Update,
+    /// Latest epoch of a verified batch
+    Batch { batch_root: Felt },
+    /// Decommitted from a previously verified batch
+    Decommitted { batch_root: Felt },
+}
+
+/// An epoch that is available in the contract storage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexedEpoch {
+    pub beacon_root: Option<FixedBytes<32>>,
+    pub execution_hash: FixedBytes<32>,
+    pub execution_height: u64,
+    pub source: EpochSource,
+    pub block_number: Option<u64>,
+}
+
+/// A batch root that was verified on-chain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexedBatch {
+    pub batch_root: Felt,
+    /// Slot of the first epoch in the batch. The events don't contain it, so it is derived
+    /// from the head the contract stored before the batch.
+    pub first_slot: u64,
+    /// Slot of the latest epoch in the batch
+    pub slot: u64,
+    pub block_number: Option<u64>,
+}
+
+/// A local index of all events emitted by the Bankai contract.
+/// It is kept up to date with `StarknetClient::index_events` and persisted as JSON.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct EventIndex {
+    /// The latest block that was fully indexed
+    pub last_indexed_block: Option<u64>,
+    /// Maps committee id to committee hash
+    pub committees: BTreeMap<u64, FixedBytes<32>>,
+    /// Maps slot to the stored epoch
+    pub epochs: BTreeMap<u64, IndexedEpoch>,
+    /// Verified batches, ordered by slot
+    pub batches: Vec<IndexedBatch>,
+}
+
+impl EventIndex {
+    /// Loads the index from disk, returning an empty index if the file does not exist yet.
+    pub fn load(path: &str) -> Result<Self, Error> {
+        if !Path::new(path).exists() {
+            return Ok(Self::default());
+        }
+        let json = fs::read_to_string(path).map_err(Error::IoError)?;
+        serde_json::from_str(&json).map_err(|e| Error::DeserializeError(e.to_string()))
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), Error> {
+        if let Some(parent) = Path::new(path).parent() {
+            fs::create_dir_all(parent).map_err(Error::IoError)?;
+        }
+        let json = serde_json::to_string_pretty(&self)
+            .map_err(|e| Error::DeserializeError(e.to_string()))?;
+        fs::write(path, json).map_err(Error::IoError)
+    }
+
+    /// The block to resume indexing from
+    pub fn next_block(&self) -> u64 {
+        self.last_indexed_block.map(|b| b + 1).unwrap_or(0)
+    }
+
+    /// Decodes and applies a raw event. Returns true if the event was emitted by the Bankai contract.
+    #[cfg(feature = "starknet-client")]
+    pub fn apply_emitted_event(&mut self, event: &EmittedEvent) -> Result<bool, Error> {
+        match BankaiEvent::decode(&event.keys, &event.data)? {
+            Some(decoded) => {
+                self.apply(decoded, event.block_number);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    pub fn apply(&mut self, event: BankaiEvent, block_number: Option<u64>) {
+        match event {
+            BankaiEvent::CommitteeUpdated {
+                committee_id,
+                committee_hash,
+            } => {
+                self.committees.insert(committee_id, committee_hash);
+            }
+            BankaiEvent::EpochUpdated {
+                beacon_root,
+                slot,
+                execution_hash,
+                execution_height,
+            } => {
+                self.epochs.insert(
+                    slot,
+                    IndexedEpoch {
+                        beacon_root: Some(beacon_root),
+                        execution_hash,
+                        execution_height,
+                        source: EpochSource::Update,
+                        block_number,
+                    },
+                );
+                self.update_batch_ranges();
+            }
+            BankaiEvent::EpochBatch {
+                batch_root,
+                beacon_root,
+                slot,
+                execution_hash,
+                execution_height,
+            } => {
+                self.epochs.insert(
+                    slot,
+                    IndexedEpoch {
+                        beacon_root: Some(beacon_root),
+                        execution_hash,
+                        execution_height,
+                        source: EpochSource::Batch { batch_root },
+                        block_number,
+                    },
+                );
+                // Batches are kept ordered by slot, as older batches can be submitted later,
+                // e.g. by a backfill
+                if !self.batches.iter().any(|b| b.batch_root == batch_root) {
+                    let position = self.batches.partition_point(|b| b.slot <= slot);
+                    self.batches.insert(
+                        position,
+                        IndexedBatch {
+                            batch_root,
+                            first_slot: slot,
+                            slot,
+                            block_number,
+                        },
+                    );
+                }
+                self.update_batch_ranges();
+            }
+            BankaiEvent::EpochDecommitted {
+                batch_root,
+                slot,
+                execution_hash,
+                execution_height,
+            } => {
+                // The decommitment event doesn't contain the header root, so we keep a known one
+                let beacon_root = self.epochs.get(&slot).and_then(|e| e.beacon_root);
+                self.epochs.insert(
+                    slot,
+                    IndexedEpoch {
+                        beacon_root,
+                        execution_hash,
+                        execution_height,
+                        source: EpochSource::Decommitted { batch_root },
+                        block_number,
+                    },
+                );
+            }
+        }
+    }
+
+    /// Derives the first slot of every batch. A batch covers all epochs after the previously
+    /// stored head, i.e. the latest epoch verified directly or as batch head.
+    fn update_batch_ranges(&mut self) {
+        for batch in self.batches.iter_mut() {
+            batch.first_slot = self
+                .epochs
+                .range(..batch.slot)
+                .rev()
+                .find(|(_, epoch)| !matches!(epoch.source, EpochSource::Decommitted { .. }))
+                .map_or(0, |(head, _)| head + 32);
+        }
+    }
+
+    /// Returns the slots in the range that were decommitted from a batch
+    pub fn decommitted_slots(&self, range: RangeInclusive<u64>) -> Vec<u64> {
+        self.epochs
+            .range(range)
+            .filter(|(_, epoch)| matches!(epoch.source, EpochSource::Decommitted { .. }))
+            .map(|(slot, _)| *slot)
+            .collect()
+    }
+
+    /// Returns all slots in the range that are available in the contract storage
+    pub fn stored_slots(&self, range: RangeInclusive<u64>) -> Vec<u64> {
+        self.epochs.range(range).map(|(slot, _)| *slot).collect()
+    }
+
+    /// Returns the root of a verified batch that contains the given slot
+    pub fn batch_root_for_slot(&self, slot: u64) -> Option<Felt> {
+        if let Some(epoch) = self.epochs.get(&slot) {
+            match &epoch.source {
+                EpochSource::Batch { batch_root } | EpochSource::Decommitted { batch_root } => {
+                    return Some(*batch_root)
+                }
+                EpochSource::Update => return None,
+            }
+        }
+
+        self.batches
+            .iter()
+            .find(|b| (b.first_slot..=b.slot).contains(&slot))
+            .map(|b| b.batch_root)
+    }
+}
@@ -0,0 +1,244 @@
+use std::fs;
+
+use alloy_primitives::{keccak256, Address, Bloom, Bytes, FixedBytes, B64, U256};
+use alloy_rlp::Encodable;
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use crate::Error;
+
+/// Where the client gets its responses from
+enum RpcSource {
+    /// A live node. Responses are also written to `record_dir` if set.
+    Remote {
+        rpc_url: String,
+        record_dir: Option<String>,
+    },
+    /// Responses previously recorded into this directory
+    Fixtures(String),
+}
+
+/// A client for the execution layer JSON-RPC API.
+/// Can record the responses it receives and replay them offline.
+pub struct ExecutionRpcClient {
+    provider: Client,
+    source: RpcSource,
+}
+
+impl ExecutionRpcClient {
+    pub fn new(rpc_url: String) -> Self {
+        Self {
+            provider: Client::new(),
+            source: RpcSource::Remote {
+                rpc_url,
+                record_dir: None,
+            },
+        }
+    }
+
+    /// Serves all requests from the fixtures recorded in `dir`
+    pub fn from_fixtures(dir: String) -> Self {
+        Self {
+            provider: Client::new(),
+            source: RpcSource::Fixtures(dir),
+        }
+    }
+
+    /// Records all responses into `dir`, so they can be replayed with `from_fixtures`
+    pub fn with_recording(mut self, dir: String) -> Self {
+        if let RpcSource::Remote { record_dir, .. } = &mut self.source {
+            *record_dir = Some(dir);
+        }
+        self
+    }
+
+    /// Fixture files are named after the method and a hash of the params
+    fn fixture_path(dir: &str, method: &str, params: &Value) -> String {
+        let params_hash = hex::encode(Sha256::digest(params.to_string().as_bytes()));
+        format!("{}/{}_{}.json", dir, method, &params_hash[..16])
+    }
+
+    async fn request<T: DeserializeOwned>(&self, method: &str, params: Value) -> Result<T, Error> {
+        let result = match &self.source {
+            RpcSource::Fixtures(dir) => {
+                let path = Self::fixture_path(dir, method, &params);
+                let json = fs::read_to_string(&path).map_err(Error::IoError)?;
+                serde_json::from_str(&json).map_err(|e| Error::DeserializeError(e.to_string()))?
+            }
+            RpcSource::Remote {
+                rpc_url,
+                record_dir,
+            } => {
+                let response: Value = self
+                    .provider
+                    .post(rpc_url)
+                    .json(&json!({
+                        "jsonrpc": "2.0",
+                        "id": 1,
+                        "method": method,
+                        "params": params,
+                    }))
+                    .send()
+                    .await
+                    .map_err(Error::RpcError)?
+                    .json()
+                    .await
+                    .map_err(Error::RpcError)?;
+
+                if let Some(error) = response.get("error") {
+                    return Err(Error::InvalidResponse(format!("{}: {}", method, error)));
+                }
+                let result = response["result"].clone();
+
+                if let Some(dir) = record_dir {
+                    fs::create_dir_all(dir).map_err(Error::IoError)?;
+                    let json = serde_json::to_string_pretty(&result)
+                        .map_err(|e| Error::DeserializeError(e.to_string()))?;
+                    fs::write(Self::fixture_path(dir, method, &params), json)
+                        .map_err(Error::IoError)?;
+                }
+                result
+            }
+        };
+
+        if result.is_null() {
+            return Err(Error::InvalidResponse(format!("{} returned null", method)));
+        }
+        serde_json::from_value(result).map_err(|e| Error::DeserializeError(e.to_string()))
+    }
+
+    /// Fetches the header of the block with the given number
+    pub async fn get_block_header(&self, block_number: u64) -> Result<ExecutionBlockHeader, Error> {
+        self.request(
+            "eth_getBlockByNumber",
+            json!([format!("{:#x}", block_number), false]),
+        )
+        .await
+    }
+
+    /// Fetches the account proof and storage proofs for the given keys at a block
+    pub async fn get_proof(
+        &self,
+        address: Address,
+        storage_keys: &[FixedBytes<32>],
+        block_number: u64,
+    ) -> Result<AccountProofResponse, Error> {
+        self.request(
+            "eth_getProof",
+            json!([address, storage_keys, format!("{:#x}", block_number)]),
+        )
+        .await
+    }
+}
+
+/// An execution block header as returned by `eth_getBlockByNumber`.
+/// Fields introduced by later forks are only present for blocks after the fork.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionBlockHeader {
+    pub hash: FixedBytes<32>,
+    pub parent_hash: FixedBytes<32>,
+    pub sha3_uncles: FixedBytes<32>,
+    pub miner: Address,
+    pub state_root: FixedBytes<32>,
+    pub transactions_root: FixedBytes<32>,
+    pub receipts_root: FixedBytes<32>,
+    pub logs_bloom: Bloom,
+    pub difficulty: U256,
+    pub number: U256,
+    pub gas_limit: U256,
+    pub gas_used: U256,
+    pub timestamp: U256,
+    pub extra_data: Bytes,
+    pub mix_hash: FixedBytes<32>,
+    pub nonce: B64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_fee_per_gas: Option<U256>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub withdrawals_root: Option<FixedBytes<32>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blob_gas_used: Option<U256>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub excess_blob_gas: Option<U256>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_beacon_block_root: Option<FixedBytes<32>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requests_hash: Option<FixedBytes<32>>,
+}
+
+impl ExecutionBlockHeader {
+    /// RLP encodes the header fields in consensus order
+    pub fn rlp(&self) -> Vec<u8> {
+        let mut fields: Vec<&dyn Encodable> = vec![
+            &self.parent_hash,
+            &self.sha3_uncles,
+            &self.miner,
+            &self.state_root,
+            &self.transactions_root,
+            &self.receipts_root,
+            &self.logs_bloom,
+            &self.difficulty,
+            &self.number,
+            &self.gas_limit,
+            &self.gas_used,
+            &self.timestamp,
+            &self.extra_data,
+            &self.mix_hash,
+            &self.nonce,
+        ];
+        // Optional fields are only ever appended, so we stop at the first missing one
+        let optional: [Option<&dyn Encodable>; 6] = [
+            self.base_fee_per_gas.as_ref().map(|v| v as &dyn Encodable),
+            self.withdrawals_root.as_ref().map(|v| v as &dyn Encodable),
+            self.blob_gas_used.as_ref().map(|v| v as &dyn Encodable),
+            self.excess_blob_gas.as_ref().map(|v| v as &dyn Encodable),
+            self.parent_beacon_block_root
+                .as_ref()
+                .map(|v| v as &dyn Encodable),
+            self.requests_hash.as_ref().map(|v| v as &dyn Encodable),
+        ];
+        fields.extend(optional.into_iter().map_while(|field| field));
+
+        let payload_length: usize = fields.iter().map(|field| field.length()).sum();
+        let mut out = Vec::with_capacity(payload_length + 9);
+        alloy_rlp::Header {
+            list: true,
+            payload_length,
+        }
+        .encode(&mut out);
+        for field in fields {
+            field.encode(&mut out);
+        }
+        out
+    }
+
+    /// Computes the block hash from the header fields, ignoring the hash reported by the node
+    pub fn compute_hash(&self) -> FixedBytes<32> {
+        keccak256(self.rlp())
+    }
+}
+
+/// Response of `eth_getProof` (EIP-1186)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountProofResponse {
+    pub address: Address,
+    pub balance: U256,
+    pub code_hash: FixedBytes<32>,
+    pub nonce: U256,
+    pub storage_hash: FixedBytes<32>,
+    pub account_proof: Vec<Bytes>,
+    pub storage_proof: Vec<StorageProofResponse>,
+}
+
+/// A single storage slot proof of an `eth_getProof` response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageProofResponse {
+    /// Nodes echo the key as requested, which may not be zero padded
+    pub key: U256,
+    pub value: U256,
+    pub proof: Vec<Bytes>,
+}
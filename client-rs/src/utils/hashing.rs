@@ -1,6 +1,7 @@
-use alloy_primitives::FixedBytes;
+use alloy_primitives::{keccak256, FixedBytes};
 use bls12_381::G1Affine;
 use sha2::{Digest, Sha256};
+use starknet_crypto::Felt;
 
 pub fn get_committee_hash(point: G1Affine) -> FixedBytes<32> {
     let mut hasher = Sha256::new();
@@ -8,3 +9,10 @@ pub fn get_committee_hash(point: G1Affine) -> FixedBytes<32> {
     hasher.update(uncompressed.as_ref());
     FixedBytes::from_slice(&hasher.finalize())
 }
+
+/// Selector of a contract entrypoint or event: the keccak hash of its name, truncated to 250 bits
+pub fn selector(name: &str) -> Felt {
+    let mut hash = keccak256(name.as_bytes()).0;
+    hash[0] &= 0x03;
+    Felt::from_bytes_be(&hash)
+}
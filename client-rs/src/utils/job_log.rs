@@ -0,0 +1,89 @@
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::traits::{ProofType, Provable};
+use crate::Error;
+
+/// A proof submitted to a prover backend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Job {
+    pub job_id: String,
+    /// Name of the prover backend, e.g. `atlantic`
+    pub prover: String,
+    pub proof_type: ProofType,
+    /// The slot the stored inputs are looked up by, as passed to the verify commands
+    pub slot: u64,
+    pub n_epochs: u64,
+    /// `Provable::id` of the update, which keys its artifacts in the `ArtifactStore`
+    #[serde(default)]
+    pub update_id: String,
+    /// Unix timestamp of the submission
+    pub submitted_at: u64,
+    /// Unix timestamp at which the prover was first seen reporting the job as done
+    #[serde(default)]
+    pub completed_at: Option<u64>,
+}
+
+/// Log of all jobs submitted by the client, used to list the jobs in the pipeline
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct JobLog {
+    pub jobs: Vec<Job>,
+}
+
+impl JobLog {
+    /// Loads the log from disk, returning an empty one if the file does not exist yet.
+    pub fn load(path: &str) -> Result<Self, Error> {
+        if !Path::new(path).exists() {
+            return Ok(Self::default());
+        }
+        let json = fs::read_to_string(path).map_err(Error::IoError)?;
+        serde_json::from_str(&json).map_err(|e| Error::DeserializeError(e.to_string()))
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), Error> {
+        if let Some(parent) = Path::new(path).parent() {
+            fs::create_dir_all(parent).map_err(Error::IoError)?;
+        }
+        let json = serde_json::to_string_pretty(&self)
+            .map_err(|e| Error::DeserializeError(e.to_string()))?;
+        fs::write(path, json).map_err(Error::IoError)
+    }
+
+    /// Records the submission of the provable
+    pub fn record(&mut self, job_id: &str, prover: &str, proof: &impl Provable) {
+        self.jobs.push(Job {
+            job_id: job_id.to_string(),
+            prover: prover.to_string(),
+            proof_type: proof.proof_type(),
+            slot: proof.slot(),
+            n_epochs: proof.n_epochs(),
+            update_id: proof.id(),
+            submitted_at: now(),
+            completed_at: None,
+        });
+    }
+
+    /// Marks the job as done. Returns the job if it was not marked before.
+    pub fn complete(&mut self, job_id: &str) -> Option<&Job> {
+        let job = self
+            .jobs
+            .iter_mut()
+            .find(|job| job.job_id == job_id && job.completed_at.is_none())?;
+        job.completed_at = Some(now());
+        Some(&*job)
+    }
+
+    pub fn get(&self, job_id: &str) -> Option<&Job> {
+        self.jobs.iter().find(|job| job.job_id == job_id)
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
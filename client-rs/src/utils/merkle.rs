@@ -1,4 +1,4 @@
-pub(crate) mod sha256 {
+pub mod sha256 {
     use crate::Error;
     use alloy_primitives::FixedBytes;
     use sha2::{Digest, Sha256};
@@ -91,10 +91,17 @@ pub(crate) mod sha256 {
     }
 }
 
-pub(crate) mod poseidon {
+pub mod poseidon {
+    use crate::Error;
     use starknet_crypto::{poseidon_hash, Felt};
 
-    pub fn compute_root(leaves: Vec<Felt>) -> Felt {
+    /// Computes the root of the leaves, padded with zeros to the next power of two.
+    /// A tree needs at least one leaf.
+    pub fn compute_root(leaves: Vec<Felt>) -> Result<Felt, Error> {
+        if leaves.is_empty() {
+            return Err(Error::InvalidMerkleTree);
+        }
+
         // Calculate the smallest power of 2 that can fit all leaves
         let mut tree_size = 1;
         while tree_size < leaves.len() {
@@ -122,10 +129,15 @@ pub(crate) mod poseidon {
         }
 
         // Return the root (the only remaining element)
-        current_level[0]
+        Ok(current_level[0])
     }
 
-    pub fn compute_paths(leaves: Vec<Felt>) -> (Felt, Vec<Vec<Felt>>) {
+    /// Computes the root and the path of every leaf. A tree needs at least one leaf.
+    pub fn compute_paths(leaves: Vec<Felt>) -> Result<(Felt, Vec<Vec<Felt>>), Error> {
+        if leaves.is_empty() {
+            return Err(Error::InvalidMerkleTree);
+        }
+
         // Calculate the smallest power of 2 that can fit all leaves
         let mut tree_size = 1;
         while tree_size < leaves.len() {
@@ -182,7 +194,7 @@ pub(crate) mod poseidon {
             paths.push(path);
         }
 
-        (root, paths)
+        Ok((root, paths))
     }
 
     pub fn hash_path(leaf: Felt, path: &[Felt], index: usize) -> Felt {
@@ -0,0 +1,200 @@
+use std::sync::LazyLock;
+
+use prometheus::{
+    exponential_buckets, CounterVec, Histogram, HistogramOpts, HistogramVec, IntCounter,
+    IntCounterVec, IntGauge, Opts, Registry, TextEncoder,
+};
+
+use crate::epoch_update::ExpectedEpochUpdateOutputs;
+use crate::Error;
+
+static METRICS: LazyLock<Metrics> =
+    LazyLock::new(|| Metrics::new().expect("metric names and labels are valid and unique"));
+
+/// Returns the metrics of the process
+pub fn metrics() -> &'static Metrics {
+    &METRICS
+}
+
+/// Prometheus metrics of the proving pipeline.
+///
+/// Metrics are recorded where the work happens (trace generation, RPC calls, transactions).
+/// The chain state gauges are only updated by `update_chain_state`.
+pub struct Metrics {
+    pub registry: Registry,
+    pub beacon_head_slot: IntGauge,
+    pub latest_epoch_slot: IntGauge,
+    /// Slots between the beacon head and the latest epoch stored in the contract
+    pub head_lag_slots: IntGauge,
+    /// Committee period of the beacon head
+    pub committee_period: IntGauge,
+    /// Latest committee id stored in the contract
+    pub latest_committee_id: IntGauge,
+    pub epochs_proven: IntCounter,
+    pub epoch_signers: Histogram,
+    /// By proof type
+    pub trace_duration: HistogramVec,
+    /// By proof type
+    pub trace_steps: HistogramVec,
+    /// Time from submission until the prover reports the job as done, by proof type
+    pub prover_queue_time: HistogramVec,
+    /// Paid fees in ETH or STRK, by token
+    pub starknet_tx_fees: CounterVec,
+    /// Transactions by status: succeeded, reverted or failed
+    pub starknet_txs: IntCounterVec,
+    /// By endpoint
+    pub beacon_rpc_duration: HistogramVec,
+    /// By endpoint
+    pub beacon_rpc_errors: IntCounterVec,
+}
+
+impl Metrics {
+    fn new() -> Result<Self, prometheus::Error> {
+        let registry = Registry::new_custom(Some("bankai".to_string()), None)?;
+
+        let beacon_head_slot = IntGauge::new("beacon_head_slot", "Slot of the beacon head")?;
+        let latest_epoch_slot = IntGauge::new(
+            "latest_epoch_slot",
+            "Slot of the latest epoch stored in the contract",
+        )?;
+        let head_lag_slots = IntGauge::new(
+            "head_lag_slots",
+            "Slots between the beacon head and the latest epoch stored in the contract",
+        )?;
+        let committee_period = IntGauge::new(
+            "committee_period",
+            "Sync committee period of the beacon head",
+        )?;
+        let latest_committee_id = IntGauge::new(
+            "latest_committee_id",
+            "Latest sync committee id stored in the contract",
+        )?;
+        let epochs_proven =
+            IntCounter::new("epochs_proven_total", "Epochs submitted to the contract")?;
+        let epoch_signers = Histogram::with_opts(
+            HistogramOpts::new(
+                "epoch_signers",
+                "Sync committee participation of the submitted epochs",
+            )
+            .buckets(vec![342.0, 384.0, 416.0, 448.0, 480.0, 496.0, 504.0, 512.0]),
+        )?;
+        let trace_duration = HistogramVec::new(
+            HistogramOpts::new("trace_duration_seconds", "Duration of cairo-run")
+                .buckets(exponential_buckets(1.0, 2.0, 12)?),
+            &["proof_type"],
+        )?;
+        let trace_steps = HistogramVec::new(
+            HistogramOpts::new("trace_steps", "Steps of the circuit run")
+                .buckets(exponential_buckets((1 << 18) as f64, 2.0, 8)?),
+            &["proof_type"],
+        )?;
+        let prover_queue_time = HistogramVec::new(
+            HistogramOpts::new(
+                "prover_queue_seconds",
+                "Time from the submission of a job until the prover reported it as done",
+            )
+            .buckets(exponential_buckets(60.0, 2.0, 10)?),
+            &["proof_type"],
+        )?;
+        let starknet_tx_fees = CounterVec::new(
+            Opts::new("starknet_tx_fees_total", "Fees paid for transactions"),
+            &["token"],
+        )?;
+        let starknet_txs = IntCounterVec::new(
+            Opts::new("starknet_txs_total", "Transactions sent to Starknet"),
+            &["status"],
+        )?;
+        let beacon_rpc_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "beacon_rpc_duration_seconds",
+                "Latency of beacon RPC requests",
+            ),
+            &["endpoint"],
+        )?;
+        let beacon_rpc_errors = IntCounterVec::new(
+            Opts::new("beacon_rpc_errors_total", "Failed beacon RPC requests"),
+            &["endpoint"],
+        )?;
+
+        registry.register(Box::new(beacon_head_slot.clone()))?;
+        registry.register(Box::new(latest_epoch_slot.clone()))?;
+        registry.register(Box::new(head_lag_slots.clone()))?;
+        registry.register(Box::new(committee_period.clone()))?;
+        registry.register(Box::new(latest_committee_id.clone()))?;
+        registry.register(Box::new(epochs_proven.clone()))?;
+        registry.register(Box::new(epoch_signers.clone()))?;
+        registry.register(Box::new(trace_duration.clone()))?;
+        registry.register(Box::new(trace_steps.clone()))?;
+        registry.register(Box::new(prover_queue_time.clone()))?;
+        registry.register(Box::new(starknet_tx_fees.clone()))?;
+        registry.register(Box::new(starknet_txs.clone()))?;
+        registry.register(Box::new(beacon_rpc_duration.clone()))?;
+        registry.register(Box::new(beacon_rpc_errors.clone()))?;
+
+        Ok(Self {
+            registry,
+            beacon_head_slot,
+            latest_epoch_slot,
+            head_lag_slots,
+            committee_period,
+            latest_committee_id,
+            epochs_proven,
+            epoch_signers,
+            trace_duration,
+            trace_steps,
+            prover_queue_time,
+            starknet_tx_fees,
+            starknet_txs,
+            beacon_rpc_duration,
+            beacon_rpc_errors,
+        })
+    }
+
+    /// Sets the gauges comparing the beacon head with the state of the contract
+    pub fn update_chain_state(&self, head_slot: u64, latest_epoch: u64, latest_committee_id: u64) {
+        self.beacon_head_slot.set(head_slot as i64);
+        self.latest_epoch_slot.set(latest_epoch as i64);
+        self.head_lag_slots
+            .set(head_slot.saturating_sub(latest_epoch) as i64);
+        self.committee_period.set((head_slot / 0x2000) as i64);
+        self.latest_committee_id.set(latest_committee_id as i64);
+    }
+
+    /// Records epochs that were submitted to the contract
+    pub fn record_proven_epochs(&self, epochs: &[ExpectedEpochUpdateOutputs]) {
+        self.epochs_proven.inc_by(epochs.len() as u64);
+        for epoch in epochs {
+            self.epoch_signers.observe(epoch.n_signers as f64);
+        }
+    }
+
+    /// Replaces the metrics of the job in a Prometheus Pushgateway. Used by short-lived
+    /// commands, which are gone before they could be scraped.
+    pub async fn push(&self, gateway: &str, job: &str) -> Result<(), Error> {
+        let response = reqwest::Client::new()
+            .put(format!(
+                "{}/metrics/job/{}",
+                gateway.trim_end_matches('/'),
+                job
+            ))
+            .header("content-type", "text/plain; version=0.0.4")
+            .body(self.encode()?)
+            .send()
+            .await
+            .map_err(Error::RpcError)?;
+        if !response.status().is_success() {
+            return Err(Error::InvalidResponse(format!(
+                "Pushing metrics failed: {}",
+                response.status()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Encodes all metrics in the Prometheus text format
+    pub fn encode(&self) -> Result<String, Error> {
+        TextEncoder::new()
+            .encode_to_string(&self.registry.gather())
+            .map_err(|e| Error::DeserializeError(e.to_string()))
+    }
+}
@@ -0,0 +1,174 @@
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use starknet_crypto::{poseidon_hash, poseidon_hash_many, Felt};
+
+use crate::utils::merkle::poseidon::hash_path;
+use crate::Error;
+
+/// A leaf of the accumulator: the Poseidon hash of an epoch's outputs, as used for batch roots
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccumulatorLeaf {
+    pub slot: u64,
+    pub hash: Felt,
+}
+
+/// An append-only Poseidon Merkle mountain range over all epochs verified by the client.
+///
+/// Leaves are appended in the order the epochs are verified. The accumulator is a list of
+/// perfect trees (peaks), one for every set bit of the leaf count, largest first. The root
+/// commits to the leaf count and the peaks, so any earlier epoch can be proven against the
+/// latest root without knowing which batch contained it.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct EpochAccumulator {
+    pub leaves: Vec<AccumulatorLeaf>,
+    pub peaks: Vec<Felt>,
+}
+
+impl EpochAccumulator {
+    /// Loads the accumulator from disk, returning an empty one if the file does not exist yet.
+    pub fn load(path: &str) -> Result<Self, Error> {
+        if !Path::new(path).exists() {
+            return Ok(Self::default());
+        }
+        let json = fs::read_to_string(path).map_err(Error::IoError)?;
+        serde_json::from_str(&json).map_err(|e| Error::DeserializeError(e.to_string()))
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), Error> {
+        if let Some(parent) = Path::new(path).parent() {
+            fs::create_dir_all(parent).map_err(Error::IoError)?;
+        }
+        let json = serde_json::to_string_pretty(&self)
+            .map_err(|e| Error::DeserializeError(e.to_string()))?;
+        fs::write(path, json).map_err(Error::IoError)
+    }
+
+    /// Appends the epoch. Returns false if the slot was already accumulated.
+    pub fn append(&mut self, slot: u64, hash: Felt) -> bool {
+        if self.leaves.iter().any(|leaf| leaf.slot == slot) {
+            return false;
+        }
+
+        // Each trailing one bit of the leaf count is a peak of the same height as the new node
+        let mut node = hash;
+        let mut n_leaves = self.leaves.len();
+        while n_leaves % 2 == 1 {
+            let left = self.peaks.pop().expect("peaks match the leaf count");
+            node = poseidon_hash(left, node);
+            n_leaves /= 2;
+        }
+        self.peaks.push(node);
+        self.leaves.push(AccumulatorLeaf { slot, hash });
+        true
+    }
+
+    pub fn root(&self) -> Felt {
+        bag_peaks(self.leaves.len(), &self.peaks)
+    }
+
+    /// Proves the epoch at `slot` against the current root
+    pub fn prove(&self, slot: u64) -> Result<AccumulatorProof, Error> {
+        let leaf_index = self
+            .leaves
+            .iter()
+            .position(|leaf| leaf.slot == slot)
+            .ok_or(Error::EpochNotAccumulated(slot))?;
+        let (_, offset, height) = locate_peak(self.leaves.len(), leaf_index)?;
+
+        let mut level: Vec<Felt> = self.leaves[offset..offset + (1 << height)]
+            .iter()
+            .map(|leaf| leaf.hash)
+            .collect();
+        let mut index = leaf_index - offset;
+        let mut path = Vec::with_capacity(height as usize);
+        while level.len() > 1 {
+            path.push(level[index ^ 1]);
+            level = level
+                .chunks(2)
+                .map(|pair| poseidon_hash(pair[0], pair[1]))
+                .collect();
+            index /= 2;
+        }
+
+        let proof = AccumulatorProof {
+            slot,
+            leaf: self.leaves[leaf_index].hash,
+            leaf_index,
+            n_leaves: self.leaves.len(),
+            path,
+            peaks: self.peaks.clone(),
+            root: self.root(),
+        };
+        proof.verify(proof.root)?;
+        Ok(proof)
+    }
+}
+
+/// Inclusion proof of an epoch in the accumulator
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AccumulatorProof {
+    pub slot: u64,
+    /// Poseidon hash of the epoch outputs
+    pub leaf: Felt,
+    pub leaf_index: usize,
+    pub n_leaves: usize,
+    /// Siblings from the leaf up to its peak
+    pub path: Vec<Felt>,
+    pub peaks: Vec<Felt>,
+    pub root: Felt,
+}
+
+impl AccumulatorProof {
+    /// Verifies the proof against the given (e.g. published) accumulator root
+    pub fn verify(&self, root: Felt) -> Result<(), Error> {
+        let (peak_index, offset, height) = locate_peak(self.n_leaves, self.leaf_index)?;
+        if self.path.len() != height as usize
+            || self.peaks.len() != self.n_leaves.count_ones() as usize
+        {
+            return Err(Error::InvalidAccumulatorProof(format!(
+                "Path or peaks don't match the size of {} leaves",
+                self.n_leaves
+            )));
+        }
+
+        let peak = hash_path(self.leaf, &self.path, self.leaf_index - offset);
+        if peak != self.peaks[peak_index] {
+            return Err(Error::InvalidAccumulatorProof(format!(
+                "Epoch {} does not hash to peak {}",
+                self.slot, peak_index
+            )));
+        }
+        if self.root != root || bag_peaks(self.n_leaves, &self.peaks) != root {
+            return Err(Error::InvalidAccumulatorProof(format!(
+                "Peaks don't match root {:#x}",
+                root
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Hashes the leaf count and the peaks into the root
+fn bag_peaks(n_leaves: usize, peaks: &[Felt]) -> Felt {
+    let mut elements = vec![Felt::from(n_leaves)];
+    elements.extend_from_slice(peaks);
+    poseidon_hash_many(&elements)
+}
+
+/// Returns the index, first leaf and height of the peak containing the leaf
+fn locate_peak(n_leaves: usize, leaf_index: usize) -> Result<(usize, usize, u32), Error> {
+    let mut offset = 0;
+    let heights = (0..usize::BITS).rev().filter(|h| (n_leaves >> h) & 1 == 1);
+    for (peak_index, height) in heights.enumerate() {
+        if leaf_index < offset + (1 << height) {
+            return Ok((peak_index, offset, height));
+        }
+        offset += 1 << height;
+    }
+    Err(Error::InvalidAccumulatorProof(format!(
+        "Leaf {} is out of range for {} leaves",
+        leaf_index, n_leaves
+    )))
+}
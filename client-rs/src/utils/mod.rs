@@ -1,6 +1,24 @@
+pub mod artifact_store;
+#[cfg(feature = "atlantic")]
 pub mod atlantic_client;
+#[cfg(feature = "cairo-runner")]
 pub mod cairo_runner;
+pub mod event_index;
+pub mod execution_rpc;
 pub mod hashing;
+pub mod job_log;
 pub mod merkle;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+pub mod mmr;
+#[cfg(feature = "starknet-client")]
+pub mod program_hash;
+#[cfg(feature = "atlantic")]
+pub mod proof_archive;
 pub mod rpc;
+pub mod ssz_proof;
+#[cfg(feature = "starknet-client")]
 pub mod starknet_client;
+pub mod step_model;
+#[cfg(feature = "cairo-runner")]
+pub mod stone_prover;
@@ -0,0 +1,184 @@
+use std::fs;
+
+use serde::Deserialize;
+use starknet_crypto::{pedersen_hash, Felt};
+use tracing::warn;
+
+use crate::config::BankaiConfig;
+use crate::traits::ProofType;
+use crate::utils::starknet_client::StarknetClient;
+use crate::Error;
+
+/// The bootloader version used by SHARP when computing the program hash
+const BOOTLOADER_VERSION: u64 = 0;
+
+/// The fields of a compiled Cairo Zero program that contribute to its hash
+#[derive(Debug, Deserialize)]
+struct CompiledProgram {
+    builtins: Vec<String>,
+    data: Vec<String>,
+    main_scope: String,
+    identifiers: serde_json::Value,
+}
+
+/// Computes the program hash of a compiled Cairo Zero program, matching
+/// `compute_program_hash_chain` from cairo-lang (and `cairo-hash-program`).
+pub fn compute_program_hash(program_path: &str) -> Result<Felt, Error> {
+    let json = fs::read_to_string(program_path).map_err(Error::IoError)?;
+    let program: CompiledProgram =
+        serde_json::from_str(&json).map_err(|e| Error::DeserializeError(e.to_string()))?;
+
+    let main_identifier = format!("{}.main", program.main_scope);
+    let main_pc = program.identifiers[&main_identifier]["pc"]
+        .as_u64()
+        .ok_or_else(|| {
+            Error::DeserializeError(format!(
+                "Missing pc for {} in {}",
+                main_identifier, program_path
+            ))
+        })?;
+
+    // The program header is missing the data length, which is added as the first element of the chain
+    let mut data_chain = vec![
+        Felt::from(BOOTLOADER_VERSION),
+        Felt::from(main_pc),
+        Felt::from(program.builtins.len()),
+    ];
+    data_chain.extend(
+        program
+            .builtins
+            .iter()
+            .map(|builtin| Felt::from_bytes_be_slice(builtin.as_bytes())),
+    );
+    for word in program.data.iter() {
+        let felt = Felt::from_hex(word)
+            .map_err(|_| Error::DeserializeError(format!("Invalid program data: {}", word)))?;
+        data_chain.push(felt);
+    }
+
+    let mut chain = Vec::with_capacity(data_chain.len() + 1);
+    chain.push(Felt::from(data_chain.len()));
+    chain.extend(data_chain);
+
+    Ok(compute_hash_chain(&chain))
+}
+
+/// Computes h(x0, h(x1, h(..., h(x_{n-1}, x_n)))) using pedersen
+fn compute_hash_chain(data: &[Felt]) -> Felt {
+    let mut elements = data.iter().rev();
+    let last = *elements.next().unwrap_or(&Felt::ZERO);
+    elements.fold(last, |acc, x| pedersen_hash(x, &acc))
+}
+
+/// Compares the program hash of a local circuit build with the config and the contract
+#[derive(Debug)]
+pub struct ProgramHashReport {
+    pub proof_type: ProofType,
+    pub circuit_path: String,
+    /// Hash of the compiled circuit, or the reason it couldn't be computed
+    pub local: Result<Felt, String>,
+    pub config: Felt,
+    /// Hash stored in the contract, `None` if the contract has no epoch batch getter, or the
+    /// reason it couldn't be fetched
+    pub on_chain: Result<Option<Felt>, String>,
+}
+
+impl ProgramHashReport {
+    pub async fn new(
+        proof_type: ProofType,
+        starknet_client: &StarknetClient,
+        config: &BankaiConfig,
+    ) -> Self {
+        let circuit_path = config.circuit_path(proof_type).to_string();
+        let local = compute_program_hash(&circuit_path).map_err(|e| e.to_string());
+        let on_chain = starknet_client
+            .get_program_hash(proof_type, config)
+            .await
+            .map_err(|e| format!("{:?}", e));
+        Self {
+            proof_type,
+            circuit_path,
+            local,
+            config: config.program_hash(proof_type),
+            on_chain,
+        }
+    }
+
+    /// Whether no hash contradicts the config. A missing epoch batch getter is not a mismatch.
+    pub fn is_consistent(&self) -> bool {
+        matches!(self.local, Ok(local) if local == self.config)
+            && match self.on_chain {
+                Ok(Some(on_chain)) => on_chain == self.config,
+                Ok(None) => self.proof_type == ProofType::EpochBatch,
+                Err(_) => false,
+            }
+    }
+
+    /// Returns an error describing the first mismatch, if any
+    pub fn ensure_consistent(&self) -> Result<(), Error> {
+        let local = self
+            .local
+            .clone()
+            .map_err(|e| Error::ProgramHashUnavailable(self.circuit_path.clone(), e))?;
+        if local != self.config {
+            return Err(Error::ProgramHashMismatch(
+                self.proof_type,
+                self.config,
+                local,
+            ));
+        }
+        match self.on_chain.clone().map_err(Error::InvalidResponse)? {
+            Some(on_chain) if on_chain != self.config => Err(Error::ProgramHashMismatch(
+                self.proof_type,
+                self.config,
+                on_chain,
+            )),
+            Some(_) => Ok(()),
+            None if self.proof_type != ProofType::EpochBatch => {
+                Err(Error::InvalidResponse(format!(
+                    "Contract has no program hash getter for {:?}",
+                    self.proof_type
+                )))
+            }
+            None => {
+                // Contracts deployed before the getter was added need a redeploy to be checked
+                warn!(
+                    proof_type = ?self.proof_type,
+                    "Contract has no program hash getter, skipping the on-chain check"
+                );
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for ProgramHashReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let format_hash = |hash: &Result<Felt, String>| match hash {
+            Ok(hash) => format!("{:#x}", hash),
+            Err(e) => format!("unavailable ({})", e),
+        };
+        let on_chain = match &self.on_chain {
+            Ok(None) if self.proof_type == ProofType::EpochBatch => {
+                "unknown (contract has no getter, redeploy to check)".to_string()
+            }
+            Ok(None) => "missing (contract has no getter)".to_string(),
+            Ok(Some(hash)) => format!("{:#x}", hash),
+            Err(e) => format!("unavailable ({})", e),
+        };
+        writeln!(
+            f,
+            "{:?} [{}]",
+            self.proof_type,
+            if self.is_consistent() {
+                "OK"
+            } else {
+                "MISMATCH"
+            }
+        )?;
+        writeln!(f, "  circuit:  {}", self.circuit_path)?;
+        writeln!(f, "  local:    {}", format_hash(&self.local))?;
+        writeln!(f, "  config:   {:#x}", self.config)?;
+        write!(f, "  on-chain: {}", on_chain)
+    }
+}
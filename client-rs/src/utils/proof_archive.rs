@@ -0,0 +1,178 @@
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::traits::StarkProof;
+use crate::utils::artifact_store::ArtifactKind;
+use crate::Error;
+
+/// The artifacts Atlantic stores in the proof registry for a query.
+/// Only the proof is required, the other artifacts are not available for every query.
+pub const QUERY_ARTIFACTS: [(&str, bool); 3] = [
+    ("proof.json", true),
+    ("pie.cairo0.zip", false),
+    ("metadata.json", false),
+];
+
+/// A downloaded artifact and its checksum
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedArtifact {
+    /// Name of the artifact in the proof registry
+    pub name: String,
+    /// The query the artifact belongs to
+    pub query_id: String,
+    pub path: String,
+    pub sha256: String,
+    pub size: usize,
+}
+
+/// Manifest of all artifacts downloaded for a proof, stored next to the update inputs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProofArchive {
+    pub query_id: String,
+    pub wrapper_query_id: Option<String>,
+    /// Unix timestamp of the download
+    pub archived_at: u64,
+    pub artifacts: Vec<ArchivedArtifact>,
+}
+
+impl ProofArchive {
+    pub fn new(query_id: &str, wrapper_query_id: Option<&str>) -> Self {
+        Self {
+            query_id: query_id.to_string(),
+            wrapper_query_id: wrapper_query_id.map(String::from),
+            archived_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+            artifacts: vec![],
+        }
+    }
+
+    pub fn manifest_path(dir: &str, query_id: &str) -> String {
+        format!("{}/archive_{}.json", dir, query_id)
+    }
+
+    /// Local file name of an artifact. Artifacts of the wrapper query are prefixed.
+    pub fn artifact_path(&self, dir: &str, query_id: &str, name: &str) -> String {
+        if self.wrapper_query_id.as_deref() == Some(query_id) {
+            format!("{}/{}_wrapped_{}", dir, self.query_id, name)
+        } else {
+            format!("{}/{}_{}", dir, query_id, name)
+        }
+    }
+
+    /// Kind of the artifact in the `ArtifactStore`
+    pub fn artifact_kind(&self, artifact: &ArchivedArtifact) -> ArtifactKind {
+        match artifact.name.as_str() {
+            "proof.json" if self.wrapper_query_id.as_ref() == Some(&artifact.query_id) => {
+                ArtifactKind::WrappedProof
+            }
+            "proof.json" => ArtifactKind::Proof,
+            "pie.cairo0.zip" => ArtifactKind::Pie,
+            _ => ArtifactKind::Metadata,
+        }
+    }
+
+    /// Checks the artifact is well formed and writes it to disk
+    pub fn store(
+        &mut self,
+        dir: &str,
+        query_id: &str,
+        name: &str,
+        bytes: Vec<u8>,
+    ) -> Result<(), Error> {
+        validate_artifact(name, &bytes)?;
+
+        let path = self.artifact_path(dir, query_id, name);
+        fs::create_dir_all(dir).map_err(Error::IoError)?;
+        fs::write(&path, &bytes).map_err(Error::IoError)?;
+
+        self.artifacts.retain(|a| a.path != path);
+        self.artifacts.push(ArchivedArtifact {
+            name: name.to_string(),
+            query_id: query_id.to_string(),
+            path,
+            sha256: hex::encode(Sha256::digest(&bytes)),
+            size: bytes.len(),
+        });
+        Ok(())
+    }
+
+    pub fn save(&self, dir: &str) -> Result<String, Error> {
+        let path = Self::manifest_path(dir, &self.query_id);
+        let json = serde_json::to_string_pretty(&self)
+            .map_err(|e| Error::DeserializeError(e.to_string()))?;
+        fs::write(&path, json).map_err(Error::IoError)?;
+        Ok(path)
+    }
+
+    pub fn load(dir: &str, query_id: &str) -> Result<Self, Error> {
+        let json =
+            fs::read_to_string(Self::manifest_path(dir, query_id)).map_err(Error::IoError)?;
+        serde_json::from_str(&json).map_err(|e| Error::DeserializeError(e.to_string()))
+    }
+
+    /// Recomputes the checksums of all archived artifacts
+    pub fn verify(&self) -> Result<(), Error> {
+        for artifact in self.artifacts.iter() {
+            read_verified(artifact)?;
+        }
+        Ok(())
+    }
+
+    /// Loads the proof of the query (not the wrapped proof) from the archive.
+    /// The whole archive is verified, so a proof is never used next to modified artifacts.
+    pub fn load_proof(&self) -> Result<StarkProof, Error> {
+        self.verify()?;
+        self.load_json(&self.query_id, "proof.json")
+    }
+
+    fn load_json(&self, query_id: &str, name: &str) -> Result<StarkProof, Error> {
+        let artifact = self
+            .artifacts
+            .iter()
+            .find(|a| a.query_id == query_id && a.name == name)
+            .ok_or_else(|| {
+                Error::ArtifactIntegrityError(format!("{} of {} not archived", name, query_id))
+            })?;
+        let bytes = read_verified(artifact)?;
+        let proof =
+            serde_json::from_slice(&bytes).map_err(|e| Error::DeserializeError(e.to_string()))?;
+        Ok(StarkProof { proof })
+    }
+}
+
+/// Reads an archived artifact, checking it wasn't modified since it was downloaded
+fn read_verified(artifact: &ArchivedArtifact) -> Result<Vec<u8>, Error> {
+    let bytes = fs::read(&artifact.path).map_err(Error::IoError)?;
+    let sha256 = hex::encode(Sha256::digest(&bytes));
+    if sha256 != artifact.sha256 || bytes.len() != artifact.size {
+        return Err(Error::ArtifactIntegrityError(format!(
+            "{} has checksum {}, expected {}",
+            artifact.path, sha256, artifact.sha256
+        )));
+    }
+    Ok(bytes)
+}
+
+/// Ensures JSON artifacts parse and zip archives carry the zip signature
+fn validate_artifact(name: &str, bytes: &[u8]) -> Result<(), Error> {
+    if bytes.is_empty() {
+        return Err(Error::ArtifactIntegrityError(format!("{} is empty", name)));
+    }
+    match Path::new(name).extension().and_then(|e| e.to_str()) {
+        Some("json") => serde_json::from_slice::<serde_json::Value>(bytes)
+            .map(|_| ())
+            .map_err(|e| {
+                Error::ArtifactIntegrityError(format!("{} is not valid JSON: {}", name, e))
+            }),
+        Some("zip") if !bytes.starts_with(b"PK\x03\x04") => Err(Error::ArtifactIntegrityError(
+            format!("{} is not a zip archive", name),
+        )),
+        _ => Ok(()),
+    }
+}
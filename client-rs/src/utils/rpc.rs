@@ -1,16 +1,19 @@
 use crate::epoch_update::SyncCommitteeValidatorPubs;
+#[cfg(feature = "metrics")]
+use crate::utils::metrics::metrics;
 use crate::Error;
 use alloy_rpc_types_beacon::events::light_client_finality::SyncAggregate;
 use alloy_rpc_types_beacon::header::HeaderResponse;
 use itertools::Itertools;
 use reqwest::Client;
 use serde_json::Value;
+use tracing::{instrument, warn};
 use types::eth_spec::MainnetEthSpec;
-use types::{BeaconBlockBody, FullPayload};
+use types::{BeaconBlockBody, BeaconState, FullPayload};
 
 /// A client for interacting with the Ethereum Beacon Chain RPC endpoints.
 /// Provides methods to fetch headers, sync aggregates, and validator information.
-pub(crate) struct BeaconRpcClient {
+pub struct BeaconRpcClient {
     provider: Client,
     pub rpc_url: String,
 }
@@ -29,16 +32,32 @@ impl BeaconRpcClient {
 
     /// Makes an HTTP GET request and returns the JSON response.
     /// This is a helper method used by all other RPC calls.
+    /// Requests are recorded in the beacon RPC metrics, labelled by `endpoint_label`.
+    #[instrument(name = "beacon_request", level = "debug", skip(self))]
     async fn get_json(&self, route: &str) -> Result<Value, Error> {
         let url = format!("{}/{}", self.rpc_url, route);
-        self.provider
-            .get(url)
-            .send()
-            .await
-            .map_err(Error::RpcError)?
-            .json()
-            .await
-            .map_err(Error::RpcError)
+        #[cfg(feature = "metrics")]
+        let endpoint = endpoint_label(route);
+        #[cfg(feature = "metrics")]
+        let timer = metrics()
+            .beacon_rpc_duration
+            .with_label_values(&[endpoint.as_str()])
+            .start_timer();
+        let result = match self.provider.get(url).send().await {
+            Ok(response) => response.json().await.map_err(Error::RpcError),
+            Err(e) => Err(Error::RpcError(e)),
+        };
+        #[cfg(feature = "metrics")]
+        timer.observe_duration();
+        if let Err(e) = &result {
+            warn!(error = %e, "Beacon request failed");
+            #[cfg(feature = "metrics")]
+            metrics()
+                .beacon_rpc_errors
+                .with_label_values(&[endpoint.as_str()])
+                .inc();
+        }
+        result
     }
 
     // async fn get_ssz_blob(&self, route: &str) -> Result<Bytes, Error> {
@@ -72,6 +91,15 @@ impl BeaconRpcClient {
         serde_json::from_value(json).map_err(|e| Error::DeserializeError(e.to_string()))
     }
 
+    /// Fetches the slot of the current head of the beacon chain
+    pub async fn get_head_slot(&self) -> Result<u64, Error> {
+        let json = self.get_json("eth/v1/beacon/headers/head").await?;
+        json["data"]["header"]["message"]["slot"]
+            .as_str()
+            .and_then(|slot| slot.parse().ok())
+            .ok_or_else(|| Error::InvalidResponse("Missing slot of the head header".into()))
+    }
+
     /// Fetches the sync aggregate from the block AFTER the specified slot.
     /// Note: This intentionally fetches slot + 1 because sync aggregates reference
     /// the previous slot's header.
@@ -91,7 +119,11 @@ impl BeaconRpcClient {
                         return Err(Error::EmptySlotDetected(slot));
                     }
                     slot += 1;
-                    println!("Empty slot detected! Attempt {}/{}. Fetching slot: {}", attempts, MAX_ATTEMPTS, slot);
+                    warn!(
+                        slot,
+                        attempt = attempts,
+                        "Empty slot detected, fetching the next slot"
+                    );
                 }
                 Err(e) => return Err(e), // Propagate other errors immediately
             }
@@ -172,11 +204,22 @@ impl BeaconRpcClient {
             .await?;
 
         let block: BeaconBlockBody<MainnetEthSpec, FullPayload<MainnetEthSpec>> =
-            serde_json::from_value(json["data"]["message"]["body"].clone()).unwrap();
+            serde_json::from_value(json["data"]["message"]["body"].clone()).map_err(|e| {
+                Error::DeserializeError(format!("Block body at slot {}: {}", slot, e))
+            })?;
 
         Ok(block)
     }
 
+    /// Fetches the full beacon state at a slot. This requires the debug API of the beacon node.
+    pub async fn get_beacon_state(&self, slot: u64) -> Result<BeaconState<MainnetEthSpec>, Error> {
+        let json = self
+            .get_json(&format!("eth/v2/debug/beacon/states/{}", slot))
+            .await?;
+
+        serde_json::from_value(json["data"].clone()).map_err(|_| Error::FailedFetchingBeaconState)
+    }
+
     /// Fetches the public keys of validators in the sync committee for a given slot.
     /// Note: This actually fetches data for the next slot (slot + 1).
     ///
@@ -193,6 +236,22 @@ impl BeaconRpcClient {
         let slot = slot + 1;
         let indexes = self.fetch_sync_committee_indexes(slot).await?;
         let pubkeys = self.fetch_validator_pubkeys(&indexes).await?;
-        Ok(pubkeys.into())
+        SyncCommitteeValidatorPubs::try_from(pubkeys)
     }
 }
+
+/// The route without query and with slots and state ids replaced, to keep the metric labels
+/// bounded
+#[cfg(feature = "metrics")]
+fn endpoint_label(route: &str) -> String {
+    let path = route.split('?').next().unwrap_or(route);
+    path.split('/')
+        .map(|segment| {
+            if segment == "head" || segment.parse::<u64>().is_ok() {
+                "{id}"
+            } else {
+                segment
+            }
+        })
+        .join("/")
+}
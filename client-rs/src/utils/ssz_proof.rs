@@ -0,0 +1,669 @@
+//! Generalized index based merkle proofs over SSZ containers.
+//!
+//! A container is merkleized by hashing the roots of its fields, padded to the next power of two.
+//! Nodes are addressed by their generalized index: the root is 1 and the children of `i` are
+//! `2i` and `2i + 1`. Containers nested in a field can be attached to the tree of their parent,
+//! which allows proving fields of e.g. the execution payload against the block body root.
+//!
+//! See https://github.com/ethereum/consensus-specs/blob/dev/ssz/merkle-proofs.md
+use std::collections::{BTreeMap, BTreeSet};
+
+use alloy_primitives::FixedBytes;
+use beacon_state_proof::state_proof_fetcher::TreeHash as LighthouseTreeHash;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tree_hash::TreeHash;
+use types::{BeaconBlockBody, BeaconState, ExecutionPayloadHeaderRef, MainnetEthSpec};
+
+use crate::epoch_update::BeaconHeader;
+use crate::Error;
+
+/// Generalized index of `next_sync_committee` in the beacon state (Altair to Deneb)
+pub const NEXT_SYNC_COMMITTEE_GINDEX: GeneralizedIndex = GeneralizedIndex(55);
+
+/// Position of a node in a merkle tree, with the root at 1
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct GeneralizedIndex(pub u64);
+
+impl GeneralizedIndex {
+    /// Index of a field in a container with `field_count` fields
+    pub fn for_field(field_index: usize, field_count: usize) -> Self {
+        let depth = field_count.max(1).next_power_of_two().trailing_zeros();
+        Self((1 << depth) + field_index as u64)
+    }
+
+    /// Number of hashes between the node and the root
+    pub fn depth(self) -> u32 {
+        63 - self.0.leading_zeros()
+    }
+
+    /// Position of the node within its level, i.e. the leaf index for a node at the bottom
+    pub fn index_in_level(self) -> u64 {
+        self.0 - (1 << self.depth())
+    }
+
+    pub fn is_left(self) -> bool {
+        self.0 % 2 == 0
+    }
+
+    pub fn sibling(self) -> Self {
+        Self(self.0 ^ 1)
+    }
+
+    pub fn parent(self) -> Self {
+        Self(self.0 / 2)
+    }
+
+    /// Index of `child`, which is relative to the subtree rooted at `self`, in the full tree
+    pub fn concat(self, child: Self) -> Self {
+        let depth = child.depth();
+        Self((self.0 << depth) | child.index_in_level())
+    }
+
+    /// Ancestor of the node at the given depth
+    pub fn ancestor_at(self, depth: u32) -> Self {
+        Self(self.0 >> (self.depth() - depth))
+    }
+
+    /// Indices of the siblings on the path to the root, ordered from the node upwards
+    pub fn branch_indices(self) -> Vec<Self> {
+        let mut indices = vec![];
+        let mut index = self;
+        while index.0 > 1 {
+            indices.push(index.sibling());
+            index = index.parent();
+        }
+        indices
+    }
+
+    /// Indices of the node and its ancestors, excluding the root
+    pub fn path_indices(self) -> Vec<Self> {
+        let mut indices = vec![];
+        let mut index = self;
+        while index.0 > 1 {
+            indices.push(index);
+            index = index.parent();
+        }
+        indices
+    }
+}
+
+impl std::fmt::Display for GeneralizedIndex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Returns the nodes required to prove all `indices` at once, in descending order
+pub fn helper_indices(indices: &[GeneralizedIndex]) -> Vec<GeneralizedIndex> {
+    let mut helpers = BTreeSet::new();
+    let mut paths = BTreeSet::new();
+    for index in indices {
+        helpers.extend(index.branch_indices());
+        paths.extend(index.path_indices());
+    }
+    let mut helpers: Vec<_> = helpers.difference(&paths).copied().collect();
+    helpers.reverse();
+    helpers
+}
+
+fn hash_pair(left: &FixedBytes<32>, right: &FixedBytes<32>) -> FixedBytes<32> {
+    let mut hasher = Sha256::new();
+    hasher.update(left.as_slice());
+    hasher.update(right.as_slice());
+    FixedBytes::from_slice(&hasher.finalize())
+}
+
+/// Merkle tree of an SSZ container, with optional subtrees for nested containers
+#[derive(Debug, Clone)]
+pub struct SszTree {
+    /// Names of the container fields, in SSZ order
+    fields: Vec<&'static str>,
+    /// Nodes indexed by generalized index. Index 0 is unused.
+    nodes: Vec<FixedBytes<32>>,
+    depth: u32,
+    /// Trees of nested containers, keyed by field index
+    subtrees: BTreeMap<usize, SszTree>,
+}
+
+impl SszTree {
+    /// Builds the tree from the field names and hash tree roots of a container
+    pub fn new(fields: Vec<(&'static str, FixedBytes<32>)>) -> Self {
+        let width = fields.len().max(1).next_power_of_two();
+        let mut nodes = vec![FixedBytes::ZERO; 2 * width];
+        for (i, (_, root)) in fields.iter().enumerate() {
+            nodes[width + i] = *root;
+        }
+        for i in (1..width).rev() {
+            nodes[i] = hash_pair(&nodes[2 * i], &nodes[2 * i + 1]);
+        }
+
+        Self {
+            fields: fields.into_iter().map(|(name, _)| name).collect(),
+            nodes,
+            depth: width.trailing_zeros(),
+            subtrees: BTreeMap::new(),
+        }
+    }
+
+    pub fn root(&self) -> FixedBytes<32> {
+        self.nodes[1]
+    }
+
+    pub fn fields(&self) -> &[&'static str] {
+        &self.fields
+    }
+
+    /// Attaches the tree of the container stored in `field`. Its root must match the field root.
+    pub fn with_subtree(mut self, field: &str, subtree: SszTree) -> Result<Self, Error> {
+        let field_index = self.field_index(field)?;
+        let gindex = GeneralizedIndex::for_field(field_index, self.fields.len());
+        if self.nodes[gindex.0 as usize] != subtree.root() {
+            return Err(Error::InvalidMerkleTree);
+        }
+        self.subtrees.insert(field_index, subtree);
+        Ok(self)
+    }
+
+    fn field_index(&self, field: &str) -> Result<usize, Error> {
+        self.fields
+            .iter()
+            .position(|f| *f == field)
+            .ok_or_else(|| Error::InvalidSszPath(field.to_string()))
+    }
+
+    /// Resolves a dotted field path, e.g. `execution_payload.state_root`, to its generalized index
+    pub fn gindex(&self, path: &str) -> Result<GeneralizedIndex, Error> {
+        let (field, rest) = match path.split_once('.') {
+            Some((field, rest)) => (field, Some(rest)),
+            None => (path, None),
+        };
+        let field_index = self.field_index(field)?;
+        let gindex = GeneralizedIndex::for_field(field_index, self.fields.len());
+        match rest {
+            None => Ok(gindex),
+            Some(rest) => {
+                let subtree = self
+                    .subtrees
+                    .get(&field_index)
+                    .ok_or_else(|| Error::InvalidSszPath(path.to_string()))?;
+                Ok(gindex.concat(subtree.gindex(rest)?))
+            }
+        }
+    }
+
+    /// Returns the node at the generalized index, descending into subtrees where required
+    pub fn node(&self, gindex: GeneralizedIndex) -> Result<FixedBytes<32>, Error> {
+        if gindex.0 == 0 {
+            return Err(Error::InvalidMerkleTree);
+        }
+        let depth = gindex.depth();
+        if depth <= self.depth {
+            return Ok(self.nodes[gindex.0 as usize]);
+        }
+
+        let field_index = gindex.ancestor_at(self.depth).index_in_level() as usize;
+        let subtree = self
+            .subtrees
+            .get(&field_index)
+            .ok_or(Error::InvalidMerkleTree)?;
+        let relative_depth = depth - self.depth;
+        let relative = (1 << relative_depth) | (gindex.0 & ((1 << relative_depth) - 1));
+        subtree.node(GeneralizedIndex(relative))
+    }
+
+    /// Proves a single node
+    pub fn prove(&self, gindex: GeneralizedIndex) -> Result<SszProof, Error> {
+        Ok(SszProof {
+            root: self.root(),
+            gindex,
+            leaf: self.node(gindex)?,
+            branch: gindex
+                .branch_indices()
+                .into_iter()
+                .map(|i| self.node(i))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// Proves a single field, addressed by its dotted path
+    pub fn prove_field(&self, path: &str) -> Result<SszProof, Error> {
+        self.prove(self.gindex(path)?)
+    }
+
+    /// Proves several nodes at once, sharing the common part of their branches
+    pub fn prove_many(&self, gindices: &[GeneralizedIndex]) -> Result<SszMultiproof, Error> {
+        let helpers = helper_indices(gindices);
+        Ok(SszMultiproof {
+            root: self.root(),
+            gindices: gindices.to_vec(),
+            leaves: gindices
+                .iter()
+                .map(|i| self.node(*i))
+                .collect::<Result<_, _>>()?,
+            proof: helpers
+                .iter()
+                .map(|i| self.node(*i))
+                .collect::<Result<_, _>>()?,
+            helper_indices: helpers,
+        })
+    }
+
+    /// Proves several fields at once, addressed by their dotted paths
+    pub fn prove_fields(&self, paths: &[&str]) -> Result<SszMultiproof, Error> {
+        let gindices = paths
+            .iter()
+            .map(|path| self.gindex(path))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.prove_many(&gindices)
+    }
+}
+
+/// Proof of a single node. The branch is ordered from the leaf towards the root.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SszProof {
+    pub root: FixedBytes<32>,
+    pub gindex: GeneralizedIndex,
+    pub leaf: FixedBytes<32>,
+    pub branch: Vec<FixedBytes<32>>,
+}
+
+impl SszProof {
+    pub fn compute_root(&self) -> FixedBytes<32> {
+        let mut value = self.leaf;
+        let mut index = self.gindex;
+        for sibling in self.branch.iter() {
+            value = if index.is_left() {
+                hash_pair(&value, sibling)
+            } else {
+                hash_pair(sibling, &value)
+            };
+            index = index.parent();
+        }
+        value
+    }
+
+    pub fn verify(&self) -> Result<(), Error> {
+        if self.branch.len() != self.gindex.depth() as usize || self.compute_root() != self.root {
+            return Err(Error::InvalidMerkleTree);
+        }
+        Ok(())
+    }
+
+    /// Extends the proof with a proof of its root, e.g. a vector root proven against a state root
+    pub fn extend(mut self, outer: &SszProof) -> Result<SszProof, Error> {
+        if outer.leaf != self.root {
+            return Err(Error::InvalidMerkleTree);
+        }
+        self.gindex = outer.gindex.concat(self.gindex);
+        self.branch.extend(outer.branch.iter().copied());
+        self.root = outer.root;
+        Ok(self)
+    }
+}
+
+/// Merkle tree of an SSZ vector or list of 32 byte chunks, padded with zero subtrees up to
+/// `depth`. Only the nodes above actual chunks are stored, so large limits stay cheap.
+#[derive(Debug, Clone)]
+pub struct ChunkTree {
+    /// Nodes of every level, starting with the chunks
+    levels: Vec<Vec<FixedBytes<32>>>,
+    /// Roots of empty subtrees of every height
+    zero_hashes: Vec<FixedBytes<32>>,
+    /// Set for lists, whose root mixes in the length
+    length: Option<usize>,
+}
+
+impl ChunkTree {
+    pub fn vector(chunks: Vec<FixedBytes<32>>, depth: u32) -> Result<Self, Error> {
+        Self::new(chunks, depth, None)
+    }
+
+    pub fn list(chunks: Vec<FixedBytes<32>>, depth: u32) -> Result<Self, Error> {
+        let length = chunks.len();
+        Self::new(chunks, depth, Some(length))
+    }
+
+    fn new(chunks: Vec<FixedBytes<32>>, depth: u32, length: Option<usize>) -> Result<Self, Error> {
+        if chunks.len() as u64 > 1 << depth {
+            return Err(Error::InvalidMerkleTree);
+        }
+
+        let mut zero_hashes = vec![FixedBytes::ZERO];
+        for i in 0..depth as usize {
+            zero_hashes.push(hash_pair(&zero_hashes[i], &zero_hashes[i]));
+        }
+
+        let mut levels = vec![chunks];
+        for height in 0..depth as usize {
+            let level = &levels[height];
+            let next = level
+                .chunks(2)
+                .map(|pair| hash_pair(&pair[0], pair.get(1).unwrap_or(&zero_hashes[height])))
+                .collect();
+            levels.push(next);
+        }
+
+        Ok(Self {
+            levels,
+            zero_hashes,
+            length,
+        })
+    }
+
+    fn depth(&self) -> usize {
+        self.levels.len() - 1
+    }
+
+    fn data_root(&self) -> FixedBytes<32> {
+        self.levels[self.depth()]
+            .first()
+            .copied()
+            .unwrap_or(self.zero_hashes[self.depth()])
+    }
+
+    fn length_chunk(length: usize) -> FixedBytes<32> {
+        let mut chunk = [0u8; 32];
+        chunk[0..8].copy_from_slice(&(length as u64).to_le_bytes());
+        FixedBytes::from(chunk)
+    }
+
+    pub fn root(&self) -> FixedBytes<32> {
+        match self.length {
+            Some(length) => hash_pair(&self.data_root(), &Self::length_chunk(length)),
+            None => self.data_root(),
+        }
+    }
+
+    /// Proves the chunk at `index` against the root of the vector or list
+    pub fn prove(&self, index: usize) -> Result<SszProof, Error> {
+        let leaf = *self.levels[0].get(index).ok_or(Error::InvalidMerkleTree)?;
+        let mut branch = vec![];
+        let mut position = index;
+        for height in 0..self.depth() {
+            branch.push(
+                self.levels[height]
+                    .get(position ^ 1)
+                    .copied()
+                    .unwrap_or(self.zero_hashes[height]),
+            );
+            position /= 2;
+        }
+
+        let mut gindex = GeneralizedIndex((1 << self.depth()) + index as u64);
+        if let Some(length) = self.length {
+            branch.push(Self::length_chunk(length));
+            gindex = GeneralizedIndex(2).concat(gindex);
+        }
+
+        Ok(SszProof {
+            root: self.root(),
+            gindex,
+            leaf,
+            branch,
+        })
+    }
+}
+
+/// Proof of several nodes against the same root, following the consensus specs.
+/// `proof` holds the nodes at `helper_indices`, which are sorted in descending order.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SszMultiproof {
+    pub root: FixedBytes<32>,
+    pub gindices: Vec<GeneralizedIndex>,
+    pub leaves: Vec<FixedBytes<32>>,
+    pub helper_indices: Vec<GeneralizedIndex>,
+    pub proof: Vec<FixedBytes<32>>,
+}
+
+impl SszMultiproof {
+    pub fn compute_root(&self) -> Result<FixedBytes<32>, Error> {
+        if self.leaves.len() != self.gindices.len()
+            || self.proof.len() != self.helper_indices.len()
+            || helper_indices(&self.gindices) != self.helper_indices
+        {
+            return Err(Error::InvalidMerkleTree);
+        }
+
+        let mut objects: BTreeMap<u64, FixedBytes<32>> = self
+            .gindices
+            .iter()
+            .zip(self.leaves.iter())
+            .chain(self.helper_indices.iter().zip(self.proof.iter()))
+            .map(|(index, node)| (index.0, *node))
+            .collect();
+
+        // Hash siblings pairwise from the bottom of the tree upwards
+        let mut keys: Vec<u64> = objects.keys().rev().copied().collect();
+        let mut pos = 0;
+        while pos < keys.len() {
+            let key = keys[pos];
+            if key > 1 && !objects.contains_key(&(key / 2)) {
+                if let (Some(left), Some(right)) =
+                    (objects.get(&(key & !1)), objects.get(&(key | 1)))
+                {
+                    let parent = hash_pair(left, right);
+                    objects.insert(key / 2, parent);
+                    keys.push(key / 2);
+                }
+            }
+            pos += 1;
+        }
+
+        objects.get(&1).copied().ok_or(Error::InvalidMerkleTree)
+    }
+
+    pub fn verify(&self) -> Result<(), Error> {
+        if self.compute_root()? != self.root {
+            return Err(Error::InvalidMerkleTree);
+        }
+        Ok(())
+    }
+}
+
+/// A container that can be merkleized into an `SszTree`
+pub trait SszContainer {
+    /// Field names and hash tree roots, in SSZ order
+    fn field_roots(&self) -> Result<Vec<(&'static str, FixedBytes<32>)>, Error>;
+
+    /// Builds the tree of the container, including the trees of nested containers we support
+    fn ssz_tree(&self) -> Result<SszTree, Error> {
+        Ok(SszTree::new(self.field_roots()?))
+    }
+}
+
+fn lighthouse_root<T: LighthouseTreeHash>(value: &T) -> FixedBytes<32> {
+    FixedBytes::from_slice(value.tree_hash_root().as_bytes())
+}
+
+impl SszContainer for BeaconHeader {
+    fn field_roots(&self) -> Result<Vec<(&'static str, FixedBytes<32>)>, Error> {
+        Ok(vec![
+            ("slot", self.slot.tree_hash_root()),
+            ("proposer_index", self.proposer_index.tree_hash_root()),
+            ("parent_root", self.parent_root),
+            ("state_root", self.state_root),
+            ("body_root", self.body_root),
+        ])
+    }
+}
+
+impl SszContainer for ExecutionPayloadHeaderRef<'_, MainnetEthSpec> {
+    fn field_roots(&self) -> Result<Vec<(&'static str, FixedBytes<32>)>, Error> {
+        let mut fields = vec![
+            ("parent_hash", lighthouse_root(&self.parent_hash())),
+            ("fee_recipient", lighthouse_root(&self.fee_recipient())),
+            ("state_root", lighthouse_root(&self.state_root())),
+            ("receipts_root", lighthouse_root(&self.receipts_root())),
+            ("logs_bloom", lighthouse_root(self.logs_bloom())),
+            ("prev_randao", lighthouse_root(&self.prev_randao())),
+            ("block_number", lighthouse_root(&self.block_number())),
+            ("gas_limit", lighthouse_root(&self.gas_limit())),
+            ("gas_used", lighthouse_root(&self.gas_used())),
+            ("timestamp", lighthouse_root(&self.timestamp())),
+            ("extra_data", lighthouse_root(self.extra_data())),
+            (
+                "base_fee_per_gas",
+                lighthouse_root(&self.base_fee_per_gas()),
+            ),
+            ("block_hash", lighthouse_root(&self.block_hash())),
+            (
+                "transactions_root",
+                lighthouse_root(&self.transactions_root()),
+            ),
+        ];
+        // Fields are only ever appended by later forks
+        if let Ok(withdrawals_root) = self.withdrawals_root() {
+            fields.push(("withdrawals_root", lighthouse_root(&withdrawals_root)));
+        }
+        if let Ok(blob_gas_used) = self.blob_gas_used() {
+            fields.push(("blob_gas_used", lighthouse_root(&blob_gas_used)));
+        }
+        if let Ok(excess_blob_gas) = self.excess_blob_gas() {
+            fields.push(("excess_blob_gas", lighthouse_root(&excess_blob_gas)));
+        }
+        Ok(fields)
+    }
+}
+
+/// Field names of the beacon block body, in SSZ order (up to Electra)
+const BLOCK_BODY_FIELDS: [&str; 13] = [
+    "randao_reveal",
+    "eth1_data",
+    "graffiti",
+    "proposer_slashings",
+    "attester_slashings",
+    "attestations",
+    "deposits",
+    "voluntary_exits",
+    "sync_aggregate",
+    "execution_payload",
+    "bls_to_execution_changes",
+    "blob_kzg_commitments",
+    "execution_requests",
+];
+
+impl SszContainer for BeaconBlockBody<MainnetEthSpec> {
+    fn field_roots(&self) -> Result<Vec<(&'static str, FixedBytes<32>)>, Error> {
+        let leaves = self.to_ref().body_merkle_leaves();
+        if leaves.len() > BLOCK_BODY_FIELDS.len() {
+            return Err(Error::InvalidMerkleTree);
+        }
+        Ok(BLOCK_BODY_FIELDS
+            .iter()
+            .zip(leaves.iter())
+            .map(|(name, leaf)| (*name, FixedBytes::from_slice(leaf.as_bytes())))
+            .collect())
+    }
+
+    fn ssz_tree(&self) -> Result<SszTree, Error> {
+        let tree = SszTree::new(self.field_roots()?);
+        if tree.root() != lighthouse_root(self) {
+            return Err(Error::InvalidMerkleTree);
+        }
+        match self.execution_payload() {
+            Ok(payload) => {
+                let header = payload.to_execution_payload_header();
+                tree.with_subtree("execution_payload", header.to_ref().ssz_tree()?)
+            }
+            Err(_) => Ok(tree),
+        }
+    }
+}
+
+impl SszContainer for BeaconState<MainnetEthSpec> {
+    fn field_roots(&self) -> Result<Vec<(&'static str, FixedBytes<32>)>, Error> {
+        // Phase 0 tracks pending attestations instead of participation flags
+        let unsupported = |_| Error::InvalidSszPath("beacon state fork".to_string());
+        let mut fields = vec![
+            ("genesis_time", lighthouse_root(&self.genesis_time())),
+            (
+                "genesis_validators_root",
+                lighthouse_root(&self.genesis_validators_root()),
+            ),
+            ("slot", lighthouse_root(&self.slot())),
+            ("fork", lighthouse_root(&self.fork())),
+            (
+                "latest_block_header",
+                lighthouse_root(self.latest_block_header()),
+            ),
+            ("block_roots", lighthouse_root(self.block_roots())),
+            ("state_roots", lighthouse_root(self.state_roots())),
+            ("historical_roots", lighthouse_root(self.historical_roots())),
+            ("eth1_data", lighthouse_root(self.eth1_data())),
+            ("eth1_data_votes", lighthouse_root(self.eth1_data_votes())),
+            (
+                "eth1_deposit_index",
+                lighthouse_root(&self.eth1_deposit_index()),
+            ),
+            ("validators", lighthouse_root(self.validators())),
+            ("balances", lighthouse_root(self.balances())),
+            ("randao_mixes", lighthouse_root(self.randao_mixes())),
+            ("slashings", lighthouse_root(self.slashings())),
+            (
+                "previous_epoch_participation",
+                lighthouse_root(self.previous_epoch_participation().map_err(unsupported)?),
+            ),
+            (
+                "current_epoch_participation",
+                lighthouse_root(self.current_epoch_participation().map_err(unsupported)?),
+            ),
+            (
+                "justification_bits",
+                lighthouse_root(self.justification_bits()),
+            ),
+            (
+                "previous_justified_checkpoint",
+                lighthouse_root(&self.previous_justified_checkpoint()),
+            ),
+            (
+                "current_justified_checkpoint",
+                lighthouse_root(&self.current_justified_checkpoint()),
+            ),
+            (
+                "finalized_checkpoint",
+                lighthouse_root(&self.finalized_checkpoint()),
+            ),
+            (
+                "inactivity_scores",
+                lighthouse_root(self.inactivity_scores().map_err(unsupported)?),
+            ),
+            (
+                "current_sync_committee",
+                lighthouse_root(self.current_sync_committee().map_err(unsupported)?),
+            ),
+            (
+                "next_sync_committee",
+                lighthouse_root(self.next_sync_committee().map_err(unsupported)?),
+            ),
+        ];
+
+        // Fields added by later forks
+        if let Ok(header) = self.latest_execution_payload_header() {
+            fields.push(("latest_execution_payload_header", header.ssz_tree()?.root()));
+        }
+        if let Ok(index) = self.next_withdrawal_index() {
+            fields.push(("next_withdrawal_index", lighthouse_root(&index)));
+        }
+        if let Ok(index) = self.next_withdrawal_validator_index() {
+            fields.push(("next_withdrawal_validator_index", lighthouse_root(&index)));
+        }
+        if let Ok(summaries) = self.historical_summaries() {
+            fields.push(("historical_summaries", lighthouse_root(summaries)));
+        }
+        Ok(fields)
+    }
+
+    fn ssz_tree(&self) -> Result<SszTree, Error> {
+        // Catches fields of forks we don't know about yet
+        let tree = SszTree::new(self.field_roots()?);
+        if tree.root() != lighthouse_root(self) {
+            return Err(Error::InvalidMerkleTree);
+        }
+        match self.latest_execution_payload_header() {
+            Ok(header) => tree.with_subtree("latest_execution_payload_header", header.ssz_tree()?),
+            Err(_) => Ok(tree),
+        }
+    }
+}
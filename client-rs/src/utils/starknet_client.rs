@@ -1,5 +1,8 @@
 use starknet::accounts::{Account, ConnectedAccount};
-use starknet::core::types::{Call, FunctionCall};
+use starknet::core::types::{
+    Call, EventFilter, ExecutionResult, FunctionCall, PriceUnit,
+    StarknetError as ProviderStarknetError, TransactionReceiptWithBlockInfo,
+};
 use starknet::macros::selector;
 use starknet::providers::{Provider, ProviderError};
 use starknet::{
@@ -7,9 +10,11 @@ use starknet::{
     contract::ContractFactory,
     core::{
         chain_id,
-        types::{contract::SierraClass, BlockId, BlockTag, Felt},
+        types::{
+            contract::{CompiledClass, SierraClass},
+            BlockId, BlockTag, Felt,
+        },
     },
-    macros::felt,
     providers::{
         jsonrpc::{HttpTransport, JsonRpcClient},
         Url,
@@ -17,10 +22,23 @@ use starknet::{
     signers::{LocalWallet, SigningKey},
 };
 use std::sync::Arc;
+use std::time::Duration;
+
+use alloy_primitives::FixedBytes;
+use serde::Serialize;
+use tracing::{debug, info};
 
 use crate::contract_init::ContractInitializationData;
-use crate::traits::Submittable;
-use crate::BankaiConfig;
+use crate::epoch_batch::EpochDecommitment;
+use crate::traits::{ProofType, Submittable};
+use crate::utils::event_index::EventIndex;
+use crate::utils::metrics::metrics;
+use crate::{BankaiConfig, Error};
+
+const EVENTS_CHUNK_SIZE: u64 = 100;
+const TX_POLL_ATTEMPTS: u32 = 60;
+const TX_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
 pub struct StarknetClient {
     account: Arc<SingleOwnerAccount<JsonRpcClient<HttpTransport>, LocalWallet>>,
     // provider: Arc<JsonRpcClient<HttpTransport>>,
@@ -30,16 +48,97 @@ pub struct StarknetClient {
 pub enum StarknetError {
     ProviderError(ProviderError),
     AccountError(String),
+    /// Failed to read or process a contract artifact
+    ArtifactError(String),
+    /// Expected and found class hash
+    ClassHashMismatch(Felt, Felt),
+    ContractNotDeployed(Felt),
+    TransactionReverted(Felt, String),
+    TransactionTimeout(Felt),
+}
+
+impl std::fmt::Display for StarknetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StarknetError::ProviderError(e) => write!(f, "Provider error: {}", e),
+            StarknetError::AccountError(e) => write!(f, "Account error: {}", e),
+            StarknetError::ArtifactError(e) => write!(f, "Artifact error: {}", e),
+            StarknetError::ClassHashMismatch(expected, found) => {
+                write!(f, "Class hash is {:#x}, expected {:#x}", found, expected)
+            }
+            StarknetError::ContractNotDeployed(address) => {
+                write!(f, "No contract deployed at {:#x}", address)
+            }
+            StarknetError::TransactionReverted(tx, reason) => {
+                write!(f, "Transaction {:#x} reverted: {}", tx, reason)
+            }
+            StarknetError::TransactionTimeout(tx) => {
+                write!(f, "Transaction {:#x} was not accepted in time", tx)
+            }
+        }
+    }
+}
+
+impl std::error::Error for StarknetError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            StarknetError::ProviderError(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// An epoch as stored by the contract (`EpochProof`)
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct StoredEpochProof {
+    pub header_root: FixedBytes<32>,
+    pub beacon_state_root: FixedBytes<32>,
+    pub n_signers: u64,
+    pub execution_hash: FixedBytes<32>,
+    pub execution_height: u64,
+}
+
+/// Decodes a u256 returned by the contract as (low, high)
+fn felts_to_u256(low: &Felt, high: &Felt) -> FixedBytes<32> {
+    let mut bytes = [0u8; 32];
+    bytes[0..16].copy_from_slice(&high.to_bytes_be()[16..32]);
+    bytes[16..32].copy_from_slice(&low.to_bytes_be()[16..32]);
+    FixedBytes::from(bytes)
+}
+
+fn felt_to_u64(felt: &Felt) -> Result<u64, Error> {
+    u64::try_from(*felt).map_err(|_| Error::InvalidResponse(format!("{:#x} exceeds u64", felt)))
+}
+
+/// The result of a (possibly already existing) contract deployment
+#[derive(Debug)]
+pub struct ContractDeployment {
+    pub class_hash: Felt,
+    pub contract_address: Felt,
+    /// Set if the class was declared in this run
+    pub declare_tx: Option<Felt>,
+    /// Set if the contract was deployed in this run
+    pub deploy_tx: Option<Felt>,
+}
+
+fn read_artifact<T: serde::de::DeserializeOwned>(path: &str) -> Result<T, StarknetError> {
+    let file = std::fs::File::open(path)
+        .map_err(|e| StarknetError::ArtifactError(format!("{}: {}", path, e)))?;
+    serde_json::from_reader(file)
+        .map_err(|e| StarknetError::ArtifactError(format!("{}: {}", path, e)))
 }
 
 impl StarknetClient {
     pub async fn new(rpc_url: &str, address: &str, priv_key: &str) -> Result<Self, StarknetError> {
-        let provider = JsonRpcClient::new(HttpTransport::new(Url::parse(rpc_url).unwrap()));
+        let rpc_url = Url::parse(rpc_url)
+            .map_err(|e| StarknetError::AccountError(format!("Invalid RPC URL: {}", e)))?;
+        let provider = JsonRpcClient::new(HttpTransport::new(rpc_url));
 
-        let signer = LocalWallet::from(SigningKey::from_secret_scalar(
-            Felt::from_hex(priv_key).unwrap(),
-        ));
-        let address = Felt::from_hex(address).unwrap();
+        let priv_key = Felt::from_hex(priv_key)
+            .map_err(|_| StarknetError::AccountError("Invalid private key".into()))?;
+        let signer = LocalWallet::from(SigningKey::from_secret_scalar(priv_key));
+        let address = Felt::from_hex(address)
+            .map_err(|_| StarknetError::AccountError(format!("Invalid address: {}", address)))?;
         let mut account = SingleOwnerAccount::new(
             provider,
             signer,
@@ -55,66 +154,243 @@ impl StarknetClient {
         })
     }
 
+    /// Declares the contract class if required and deploys the contract.
+    /// If a contract with the same class is already deployed at the computed address,
+    /// the existing deployment is returned instead.
     pub async fn deploy_contract(
         &self,
         init_data: ContractInitializationData,
         config: &BankaiConfig,
-    ) -> Result<Felt, StarknetError> {
-        let contract_artifact: SierraClass =
-            serde_json::from_reader(std::fs::File::open(config.contract_path.clone()).unwrap())
-                .unwrap();
-        let class_hash = contract_artifact.class_hash().unwrap();
-        assert!(
-            class_hash == config.contract_class_hash,
-            "Contract class hash mismatch! Please update config: {:?}",
-            class_hash
-        );
+    ) -> Result<ContractDeployment, StarknetError> {
+        let sierra_class: SierraClass = read_artifact(&config.contract_path)?;
+        let class_hash = sierra_class
+            .class_hash()
+            .map_err(|e| StarknetError::ArtifactError(e.to_string()))?;
 
-        let contract_factory = ContractFactory::new(class_hash, self.account.clone());
-        let deploy_tx = contract_factory.deploy_v1(init_data.to_calldata(), felt!("1337"), false);
+        let declare_tx = if self.is_class_declared(class_hash).await? {
+            info!(class_hash = %format!("{:#x}", class_hash), "Class already declared");
+            None
+        } else {
+            let compiled_class: CompiledClass = read_artifact(&config.contract_casm_path)?;
+            let compiled_class_hash = compiled_class
+                .class_hash()
+                .map_err(|e| StarknetError::ArtifactError(e.to_string()))?;
+            let flattened_class = sierra_class
+                .flatten()
+                .map_err(|e| StarknetError::ArtifactError(e.to_string()))?;
 
-        let contract_address = deploy_tx.deployed_address();
+            let result = self
+                .account
+                .declare_v2(Arc::new(flattened_class), compiled_class_hash)
+                .send()
+                .await
+                .map_err(|e| StarknetError::AccountError(e.to_string()))?;
+            if result.class_hash != class_hash {
+                return Err(StarknetError::ClassHashMismatch(
+                    class_hash,
+                    result.class_hash,
+                ));
+            }
+            info!(class_hash = %format!("{:#x}", class_hash), "Class declared");
+            self.wait_for_transaction(result.transaction_hash).await?;
+            Some(result.transaction_hash)
+        };
 
-        assert!(
-            contract_address == config.contract_address,
-            "Contract address mismatch! Please update config: {:?}",
-            contract_address
-        );
+        let contract_factory = ContractFactory::new(class_hash, self.account.clone());
+        let deployment =
+            contract_factory.deploy_v1(init_data.to_calldata(), config.deployment_salt, false);
+        let contract_address = deployment.deployed_address();
+
+        // Check for an existing deployment at the computed address
+        if let Some(deployed_class_hash) = self.get_class_hash_at(contract_address).await? {
+            if deployed_class_hash != class_hash {
+                return Err(StarknetError::ClassHashMismatch(
+                    class_hash,
+                    deployed_class_hash,
+                ));
+            }
+            info!(
+                contract_address = %format!("{:#x}", contract_address),
+                "Contract already deployed"
+            );
+            return Ok(ContractDeployment {
+                class_hash,
+                contract_address,
+                declare_tx,
+                deploy_tx: None,
+            });
+        }
 
-        deploy_tx
+        let result = deployment
             .send()
             .await
             .map_err(|e| StarknetError::AccountError(e.to_string()))?;
+        self.wait_for_transaction(result.transaction_hash).await?;
+
+        match self.get_class_hash_at(contract_address).await? {
+            Some(deployed_class_hash) if deployed_class_hash == class_hash => {}
+            Some(deployed_class_hash) => {
+                return Err(StarknetError::ClassHashMismatch(
+                    class_hash,
+                    deployed_class_hash,
+                ))
+            }
+            None => return Err(StarknetError::ContractNotDeployed(contract_address)),
+        }
+
+        Ok(ContractDeployment {
+            class_hash,
+            contract_address,
+            declare_tx,
+            deploy_tx: Some(result.transaction_hash),
+        })
+    }
+
+    async fn is_class_declared(&self, class_hash: Felt) -> Result<bool, StarknetError> {
+        match self
+            .account
+            .provider()
+            .get_class(BlockId::Tag(BlockTag::Pending), class_hash)
+            .await
+        {
+            Ok(_) => Ok(true),
+            Err(ProviderError::StarknetError(ProviderStarknetError::ClassHashNotFound)) => {
+                Ok(false)
+            }
+            Err(e) => Err(StarknetError::ProviderError(e)),
+        }
+    }
+
+    /// Returns the class hash of the contract at the address, or None if nothing is deployed
+    async fn get_class_hash_at(&self, address: Felt) -> Result<Option<Felt>, StarknetError> {
+        match self
+            .account
+            .provider()
+            .get_class_hash_at(BlockId::Tag(BlockTag::Pending), address)
+            .await
+        {
+            Ok(class_hash) => Ok(Some(class_hash)),
+            Err(ProviderError::StarknetError(ProviderStarknetError::ContractNotFound)) => Ok(None),
+            Err(e) => Err(StarknetError::ProviderError(e)),
+        }
+    }
+
+    /// Polls the receipt of a transaction until it was executed
+    pub async fn wait_for_transaction(&self, tx_hash: Felt) -> Result<(), StarknetError> {
+        for _ in 0..TX_POLL_ATTEMPTS {
+            match self
+                .account
+                .provider()
+                .get_transaction_receipt(tx_hash)
+                .await
+            {
+                Ok(receipt) => {
+                    let fee = receipt.receipt.actual_fee();
+                    let unit = match fee.unit {
+                        PriceUnit::Wei => "eth",
+                        PriceUnit::Fri => "strk",
+                    };
+                    if let Ok(amount) = u128::try_from(fee.amount) {
+                        metrics()
+                            .starknet_tx_fees
+                            .with_label_values(&[unit])
+                            .inc_by(amount as f64 / 1e18);
+                    }
+                    return match receipt.receipt.execution_result() {
+                        ExecutionResult::Succeeded => {
+                            metrics()
+                                .starknet_txs
+                                .with_label_values(&["succeeded"])
+                                .inc();
+                            Ok(())
+                        }
+                        ExecutionResult::Reverted { reason } => {
+                            metrics()
+                                .starknet_txs
+                                .with_label_values(&["reverted"])
+                                .inc();
+                            Err(StarknetError::TransactionReverted(tx_hash, reason.clone()))
+                        }
+                    };
+                }
+                Err(ProviderError::StarknetError(
+                    ProviderStarknetError::TransactionHashNotFound,
+                )) => {
+                    tokio::time::sleep(TX_POLL_INTERVAL).await;
+                }
+                Err(e) => return Err(StarknetError::ProviderError(e)),
+            }
+        }
+        metrics().starknet_txs.with_label_values(&["failed"]).inc();
+        Err(StarknetError::TransactionTimeout(tx_hash))
+    }
 
-        Ok(contract_address)
+    pub async fn get_transaction_receipt(
+        &self,
+        tx_hash: Felt,
+    ) -> Result<TransactionReceiptWithBlockInfo, StarknetError> {
+        self.account
+            .provider()
+            .get_transaction_receipt(tx_hash)
+            .await
+            .map_err(StarknetError::ProviderError)
     }
 
+    /// Submits the update and waits for the transaction. Returns the transaction hash.
     pub async fn submit_update<T>(
         &self,
         update: impl Submittable<T>,
         config: &BankaiConfig,
-    ) -> Result<(), StarknetError> {
+    ) -> Result<Felt, StarknetError> {
+        self.execute(update.get_contract_selector(), update.to_calldata(), config)
+            .await
+    }
+
+    /// Stores an epoch of a verified batch by its merkle path to the batch root
+    pub async fn decommit_epoch(
+        &self,
+        decommitment: &EpochDecommitment,
+        config: &BankaiConfig,
+    ) -> Result<Felt, StarknetError> {
+        self.execute(
+            selector!("decommit_batched_epoch"),
+            decommitment.to_calldata(),
+            config,
+        )
+        .await
+    }
+
+    async fn execute(
+        &self,
+        selector: Felt,
+        calldata: Vec<Felt>,
+        config: &BankaiConfig,
+    ) -> Result<Felt, StarknetError> {
         let result = self
             .account
             .execute_v1(vec![Call {
                 to: config.contract_address,
-                selector: update.get_contract_selector(),
-                calldata: update.to_calldata(),
+                selector,
+                calldata,
             }])
             .send()
             .await
-            .map_err(|e| StarknetError::AccountError(e.to_string()))?;
+            .map_err(|e| {
+                metrics().starknet_txs.with_label_values(&["failed"]).inc();
+                StarknetError::AccountError(e.to_string())
+            })?;
 
-        println!("tx_hash: {:?}", result.transaction_hash);
-        Ok(())
+        info!(tx_hash = %format!("{:#x}", result.transaction_hash), "Update submitted");
+        self.wait_for_transaction(result.transaction_hash).await?;
+        Ok(result.transaction_hash)
     }
 
+    /// Returns the committee hash stored for the committee id, zero if none is stored
     pub async fn get_committee_hash(
         &self,
-        slot: u64,
+        committee_id: u64,
         config: &BankaiConfig,
-    ) -> Result<(), StarknetError> {
-        let committee_id = slot / 0x2000_u64;
+    ) -> Result<FixedBytes<32>, Error> {
         let committee_hash = self
             .account
             .provider()
@@ -128,15 +404,21 @@ impl StarknetClient {
             )
             .await
             .map_err(StarknetError::ProviderError)?;
-        println!("committee_hash: {:?}", committee_hash);
-        Ok(())
+        match committee_hash.as_slice() {
+            [low, high] => Ok(felts_to_u256(low, high)),
+            _ => Err(Error::InvalidResponse(format!(
+                "Invalid get_committee_hash response: {:?}",
+                committee_hash
+            ))),
+        }
     }
 
+    /// Returns the epoch the contract stores for the slot. Unknown slots are all zero.
     pub async fn get_epoch_proof(
         &self,
         slot: u64,
         config: &BankaiConfig,
-    ) -> Result<(), StarknetError> {
+    ) -> Result<StoredEpochProof, Error> {
         let epoch_proof = self
             .account
             .provider()
@@ -150,14 +432,25 @@ impl StarknetClient {
             )
             .await
             .map_err(StarknetError::ProviderError)?;
-        println!("epoch_proof: {:?}", epoch_proof);
-        Ok(())
+
+        match epoch_proof.as_slice() {
+            [header_root_low, header_root_high, state_root_low, state_root_high, n_signers, execution_hash_low, execution_hash_high, execution_height] => {
+                Ok(StoredEpochProof {
+                    header_root: felts_to_u256(header_root_low, header_root_high),
+                    beacon_state_root: felts_to_u256(state_root_low, state_root_high),
+                    n_signers: felt_to_u64(n_signers)?,
+                    execution_hash: felts_to_u256(execution_hash_low, execution_hash_high),
+                    execution_height: felt_to_u64(execution_height)?,
+                })
+            }
+            _ => Err(Error::InvalidResponse(format!(
+                "Invalid get_epoch_proof response: {:?}",
+                epoch_proof
+            ))),
+        }
     }
 
-    pub async fn get_latest_epoch_slot(
-        &self,
-        config: &BankaiConfig,
-    ) -> Result<Felt, StarknetError> {
+    pub async fn get_latest_epoch_slot(&self, config: &BankaiConfig) -> Result<u64, Error> {
         let latest_epoch = self
             .account
             .provider()
@@ -171,25 +464,25 @@ impl StarknetClient {
             )
             .await
             .map_err(StarknetError::ProviderError)?;
-        Ok(*latest_epoch.first().unwrap())
+        match latest_epoch.as_slice() {
+            [slot] => felt_to_u64(slot),
+            _ => Err(Error::InvalidResponse(format!(
+                "Invalid get_latest_epoch response: {:?}",
+                latest_epoch
+            ))),
+        }
     }
 
-    // Computes the slot numbers for the current term.
-    pub async fn get_batching_range(
-        &self,
-        config: &BankaiConfig,
-    ) -> Result<(u64, u64), StarknetError> {
+    // Computes the next epoch slot and the first slot after the term it belongs to.
+    pub async fn get_batching_range(&self, config: &BankaiConfig) -> Result<(u64, u64), Error> {
         let latest_epoch_slot = self.get_latest_epoch_slot(config).await?;
-        let next_epoch = (u64::try_from(latest_epoch_slot).unwrap() / 32) * 32 + 32;
+        let next_epoch = (latest_epoch_slot / 32) * 32 + 32;
         let term = next_epoch / 0x2000;
-        let terms_last_epoch = (term + 1) * 0x2000 - 32;
-        Ok((next_epoch, terms_last_epoch))
+        let terms_end = (term + 1) * 0x2000;
+        Ok((next_epoch, terms_end))
     }
 
-    pub async fn get_latest_committee_id(
-        &self,
-        config: &BankaiConfig,
-    ) -> Result<Felt, StarknetError> {
+    pub async fn get_latest_committee_id(&self, config: &BankaiConfig) -> Result<u64, Error> {
         let latest_committee_id = self
             .account
             .provider()
@@ -203,7 +496,114 @@ impl StarknetClient {
             )
             .await
             .map_err(StarknetError::ProviderError)?;
-        println!("latest_committee_id: {:?}", latest_committee_id);
-        Ok(*latest_committee_id.first().unwrap())
+        debug!(?latest_committee_id, "Latest committee id");
+        match latest_committee_id.as_slice() {
+            [committee_id] => felt_to_u64(committee_id),
+            _ => Err(Error::InvalidResponse(format!(
+                "Invalid get_latest_committee_id response: {:?}",
+                latest_committee_id
+            ))),
+        }
     }
+
+    /// Fetches the program hash the contract expects for the given proof type.
+    /// Returns `None` if the contract predates `get_epoch_batch_program_hash`. The getters of
+    /// the other proof types were always deployed, so missing ones are an error.
+    pub async fn get_program_hash(
+        &self,
+        proof_type: ProofType,
+        config: &BankaiConfig,
+    ) -> Result<Option<Felt>, StarknetError> {
+        let entry_point_selector = match proof_type {
+            ProofType::Epoch => selector!("get_epoch_update_program_hash"),
+            ProofType::SyncCommittee => selector!("get_committee_update_program_hash"),
+            ProofType::EpochBatch => selector!("get_epoch_batch_program_hash"),
+        };
+        let result = self
+            .account
+            .provider()
+            .call(
+                FunctionCall {
+                    contract_address: config.contract_address,
+                    entry_point_selector,
+                    calldata: vec![],
+                },
+                BlockId::Tag(BlockTag::Latest),
+            )
+            .await;
+        let program_hash = match result {
+            Ok(program_hash) => program_hash,
+            Err(ProviderError::StarknetError(ProviderStarknetError::ContractError(data)))
+                if proof_type == ProofType::EpochBatch
+                    && is_missing_entry_point(&data.revert_error) =>
+            {
+                return Ok(None)
+            }
+            Err(e) => return Err(StarknetError::ProviderError(e)),
+        };
+        program_hash
+            .first()
+            .copied()
+            .map(Some)
+            .ok_or_else(|| StarknetError::AccountError("Empty program hash response".into()))
+    }
+
+    /// Pages through all events of the Bankai contract emitted since the last indexed block
+    /// and applies them to the index. Returns the number of indexed events.
+    pub async fn index_events(
+        &self,
+        index: &mut EventIndex,
+        config: &BankaiConfig,
+    ) -> Result<usize, Error> {
+        let from_block = index.next_block();
+        let latest_block = self
+            .account
+            .provider()
+            .block_number()
+            .await
+            .map_err(|e| Error::StarknetError(StarknetError::ProviderError(e)))?;
+        if from_block > latest_block {
+            return Ok(0);
+        }
+
+        let filter = EventFilter {
+            from_block: Some(BlockId::Number(from_block)),
+            to_block: Some(BlockId::Number(latest_block)),
+            address: Some(config.contract_address),
+            keys: None,
+        };
+
+        let mut continuation_token = None;
+        let mut indexed = 0;
+        loop {
+            let page = self
+                .account
+                .provider()
+                .get_events(filter.clone(), continuation_token, EVENTS_CHUNK_SIZE)
+                .await
+                .map_err(|e| Error::StarknetError(StarknetError::ProviderError(e)))?;
+
+            for event in page.events.iter() {
+                if index.apply_emitted_event(event)? {
+                    indexed += 1;
+                }
+            }
+
+            continuation_token = page.continuation_token;
+            if continuation_token.is_none() {
+                break;
+            }
+        }
+
+        index.last_indexed_block = Some(latest_block);
+
+        Ok(indexed)
+    }
+}
+
+/// Nodes report calls to unknown selectors as a contract error, with a node specific message
+fn is_missing_entry_point(revert_error: &str) -> bool {
+    let revert_error = revert_error.to_lowercase();
+    revert_error.contains("entrypoint_not_found")
+        || (revert_error.contains("entry point") && revert_error.contains("not found"))
 }
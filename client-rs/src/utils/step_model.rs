@@ -0,0 +1,123 @@
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::BankaiConfig;
+use crate::Error;
+
+/// Steps of a single epoch verification in the batch circuit, used until the model is calibrated
+const DEFAULT_EPOCH_STEPS: u64 = 350_000;
+/// Fixed steps of a batch (batch root, output hashing), used until the model is calibrated
+const DEFAULT_BATCH_BASE_STEPS: u64 = 200_000;
+
+/// Which side of the trade-off between proof latency and proving cost per epoch to favour
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum BatchStrategy {
+    /// Small batches, which are proven and verified sooner
+    Latency,
+    /// The largest batches the prover accepts, which amortize the fixed costs of a proof
+    Cost,
+}
+
+impl BatchStrategy {
+    /// Maximum number of steps a batch may take with this strategy
+    pub fn step_budget(&self, config: &BankaiConfig) -> u64 {
+        match self {
+            BatchStrategy::Latency => config.latency_batch_steps,
+            BatchStrategy::Cost => config.max_batch_steps,
+        }
+    }
+}
+
+/// The step count of a batch, as measured by `cairo-run`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepSample {
+    /// Id of the batch the trace was generated for
+    pub id: String,
+    pub n_epochs: u64,
+    pub n_steps: u64,
+}
+
+/// Estimates the steps of an epoch batch as `base_steps + n_epochs * epoch_steps`.
+/// The model is fitted to the step counts recorded for previous batches and persisted as JSON.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct StepModel {
+    pub samples: Vec<StepSample>,
+}
+
+impl StepModel {
+    /// Loads the model from disk, returning an uncalibrated model if the file does not exist yet.
+    pub fn load(path: &str) -> Result<Self, Error> {
+        if !Path::new(path).exists() {
+            return Ok(Self::default());
+        }
+        let json = fs::read_to_string(path).map_err(Error::IoError)?;
+        serde_json::from_str(&json).map_err(|e| Error::DeserializeError(e.to_string()))
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), Error> {
+        if let Some(parent) = Path::new(path).parent() {
+            fs::create_dir_all(parent).map_err(Error::IoError)?;
+        }
+        let json = serde_json::to_string_pretty(&self)
+            .map_err(|e| Error::DeserializeError(e.to_string()))?;
+        fs::write(path, json).map_err(Error::IoError)
+    }
+
+    /// Records the measured steps of a batch, replacing earlier runs of the same batch
+    pub fn record(&mut self, id: String, n_epochs: u64, n_steps: u64) {
+        self.samples.retain(|sample| sample.id != id);
+        self.samples.push(StepSample {
+            id,
+            n_epochs,
+            n_steps,
+        });
+    }
+
+    /// Returns the fitted `(base_steps, epoch_steps)`.
+    ///
+    /// With samples of at least two batch sizes, both are fitted by least squares. With a
+    /// single batch size, only the steps per epoch are fitted to the samples.
+    pub fn coefficients(&self) -> (u64, u64) {
+        if self.samples.is_empty() {
+            return (DEFAULT_BATCH_BASE_STEPS, DEFAULT_EPOCH_STEPS);
+        }
+
+        let n = self.samples.len() as f64;
+        let mean_x = self.samples.iter().map(|s| s.n_epochs as f64).sum::<f64>() / n;
+        let mean_y = self.samples.iter().map(|s| s.n_steps as f64).sum::<f64>() / n;
+        let var_x: f64 = self
+            .samples
+            .iter()
+            .map(|s| (s.n_epochs as f64 - mean_x).powi(2))
+            .sum();
+
+        if var_x == 0.0 {
+            let epoch_steps = (mean_y - DEFAULT_BATCH_BASE_STEPS as f64) / mean_x.max(1.0);
+            return (DEFAULT_BATCH_BASE_STEPS, epoch_steps.max(1.0).ceil() as u64);
+        }
+
+        let cov_xy: f64 = self
+            .samples
+            .iter()
+            .map(|s| (s.n_epochs as f64 - mean_x) * (s.n_steps as f64 - mean_y))
+            .sum();
+        let epoch_steps = (cov_xy / var_x).max(1.0);
+        let base_steps = (mean_y - epoch_steps * mean_x).max(0.0);
+        (base_steps.ceil() as u64, epoch_steps.ceil() as u64)
+    }
+
+    /// Estimated steps of a batch with `n_epochs` epochs
+    pub fn estimate(&self, n_epochs: u64) -> u64 {
+        let (base_steps, epoch_steps) = self.coefficients();
+        base_steps + n_epochs * epoch_steps
+    }
+
+    /// The largest number of epochs whose estimated steps fit into the budget (at least one)
+    pub fn batch_size(&self, step_budget: u64) -> u64 {
+        let (base_steps, epoch_steps) = self.coefficients();
+        (step_budget.saturating_sub(base_steps) / epoch_steps).max(1)
+    }
+}
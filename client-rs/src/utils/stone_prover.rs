@@ -0,0 +1,153 @@
+use std::fs;
+use std::path::Path;
+
+use sha2::{Digest, Sha256};
+use tracing::info;
+
+use crate::config::BankaiConfig;
+use crate::traits::{Provable, ProverBackend, StarkProof};
+use crate::utils::artifact_store::ArtifactStore;
+use crate::Error;
+
+/// Proves updates on the local machine using the Stone prover (`cpu_air_prover`).
+///
+/// The circuit is run in proof mode with the dynamic layout described by `dynamic_params.json`
+/// and proven. Wrapping runs the recursive verifier program on the proof using the
+/// `recursive_with_poseidon` layout, which can be verified by Integrity.
+/// Jobs run to completion on submission, so their status is available right away.
+pub struct LocalStoneProver {
+    config: BankaiConfig,
+}
+
+impl LocalStoneProver {
+    pub fn new(config: BankaiConfig) -> Self {
+        Self { config }
+    }
+
+    fn job_dir(&self, job_id: &str) -> String {
+        format!("{}/{}", self.config.local_prover_dir, job_id)
+    }
+
+    /// Runs the program in proof mode and proves the resulting trace
+    fn prove(
+        &self,
+        job_id: &str,
+        program_path: &str,
+        input_path: &str,
+        layout_args: &str,
+    ) -> Result<(), Error> {
+        let job_dir = self.job_dir(job_id);
+        fs::create_dir_all(&job_dir).map_err(Error::IoError)?;
+        // Remove leftovers of a previous failed attempt
+        let _ = fs::remove_file(format!("{}/error.txt", job_dir));
+
+        let result = self
+            .run_step(
+                "cairo-run",
+                format!(
+                    "source ../venv/bin/activate && cairo-run --program {} --program_input {} {} --proof_mode --air_public_input {dir}/public_input.json --air_private_input {dir}/private_input.json --trace_file {dir}/trace.bin --memory_file {dir}/memory.bin",
+                    program_path,
+                    input_path,
+                    layout_args,
+                    dir = job_dir
+                ),
+            )
+            .and_then(|_| {
+                self.run_step(
+                    "cpu_air_prover",
+                    format!(
+                        "cpu_air_prover --out_file {dir}/proof.json --private_input_file {dir}/private_input.json --public_input_file {dir}/public_input.json --prover_config_file {} --parameter_file {} --generate_annotations",
+                        self.config.stone_prover_config_path,
+                        self.config.stone_prover_params_path,
+                        dir = job_dir
+                    ),
+                )
+            });
+
+        if let Err(Error::CairoRunError(e)) = &result {
+            fs::write(format!("{}/error.txt", job_dir), e).map_err(Error::IoError)?;
+        }
+        result
+    }
+
+    fn run_step(&self, name: &str, command: String) -> Result<(), Error> {
+        info!(step = name, "Running");
+        let start_time = std::time::Instant::now();
+        let output = std::process::Command::new("sh")
+            .arg("-c")
+            .arg(command)
+            .output()
+            .map_err(|e| Error::CairoRunError(format!("Failed to execute {}: {}", name, e)))?;
+
+        if !output.status.success() {
+            return Err(Error::CairoRunError(format!(
+                "{} failed: {}",
+                name,
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+        info!(step = name, duration = ?start_time.elapsed(), "Finished");
+        Ok(())
+    }
+}
+
+impl ProverBackend for LocalStoneProver {
+    async fn submit(&self, batch: &impl Provable) -> Result<String, Error> {
+        let job_id = batch.id();
+        let input_path = ArtifactStore::from_config(&self.config).put_inputs(batch)?;
+        let program_path = self.config.circuit_path(batch.proof_type());
+        self.prove(
+            &job_id,
+            program_path,
+            &input_path,
+            &format!(
+                "--layout=dynamic --cairo_layout_params_file {}",
+                self.config.dynamic_params_path
+            ),
+        )?;
+        Ok(job_id)
+    }
+
+    async fn status(&self, job_id: &str) -> Result<String, Error> {
+        let job_dir = self.job_dir(job_id);
+        if Path::new(&format!("{}/proof.json", job_dir)).exists() {
+            Ok("DONE".to_string())
+        } else if Path::new(&format!("{}/error.txt", job_dir)).exists() {
+            Ok("FAILED".to_string())
+        } else if Path::new(&job_dir).exists() {
+            Ok("IN_PROGRESS".to_string())
+        } else {
+            Err(Error::InvalidResponse(format!("Unknown job: {}", job_id)))
+        }
+    }
+
+    async fn fetch_proof(&self, job_id: &str) -> Result<StarkProof, Error> {
+        let json = fs::read_to_string(format!("{}/proof.json", self.job_dir(job_id)))
+            .map_err(Error::IoError)?;
+        let proof =
+            serde_json::from_str(&json).map_err(|e| Error::DeserializeError(e.to_string()))?;
+        Ok(StarkProof { proof })
+    }
+
+    async fn wrap(&self, proof: StarkProof) -> Result<String, Error> {
+        let proof_json =
+            serde_json::to_string(&proof).map_err(|e| Error::DeserializeError(e.to_string()))?;
+        let mut hasher = Sha256::new();
+        hasher.update(b"proof_wrapper");
+        hasher.update(proof_json.as_bytes());
+        let job_id = hex::encode(hasher.finalize().as_slice());
+
+        let job_dir = self.job_dir(&job_id);
+        fs::create_dir_all(&job_dir).map_err(Error::IoError)?;
+        let input_path = format!("{}/wrapper_input.json", job_dir);
+        fs::write(&input_path, proof_json).map_err(Error::IoError)?;
+
+        self.prove(
+            &job_id,
+            &self.config.proof_wrapper_program_path,
+            &input_path,
+            "--layout=recursive_with_poseidon",
+        )?;
+        Ok(job_id)
+    }
+}
@@ -0,0 +1,255 @@
+mod common;
+
+use std::path::{Path, PathBuf};
+use std::process::Output;
+
+use bankai_client::epoch_batch::EpochUpdateBatch;
+use bankai_client::sync_committee::SyncCommitteeUpdate;
+use bankai_client::utils::artifact_store::{ArtifactKind, ArtifactQuery};
+use bankai_client::{ArtifactStore, Error, ProofType, Provable};
+use common::{stdout, work_dir};
+use serde::Serialize;
+use serde_json::Value;
+use starknet::core::types::Felt;
+
+/// A batch covering `first_slot..=slot`, without any circuit inputs
+#[derive(Serialize)]
+struct TestBatch {
+    first_slot: u64,
+    slot: u64,
+    root: u64,
+}
+
+impl Provable for TestBatch {
+    fn id(&self) -> String {
+        format!("batch_{}", self.root)
+    }
+
+    fn proof_type(&self) -> ProofType {
+        ProofType::EpochBatch
+    }
+
+    fn slot(&self) -> u64 {
+        self.slot
+    }
+
+    fn first_slot(&self) -> u64 {
+        self.first_slot
+    }
+
+    fn batch_root(&self) -> Option<Felt> {
+        Some(Felt::from(self.root))
+    }
+
+    fn n_epochs(&self) -> u64 {
+        (self.slot - self.first_slot) / 32 + 1
+    }
+}
+
+fn committee_update() -> SyncCommitteeUpdate {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../cairo/tests/fixtures/committee_update_5800000.json");
+    serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+}
+
+fn open_store(dir: &Path, network: &str) -> ArtifactStore {
+    ArtifactStore::new(dir.join("artifacts").to_str().unwrap(), network)
+}
+
+/// Two batches ending at slot 320 and one before them
+fn store_batches(store: &ArtifactStore) {
+    for (first_slot, slot, root) in [(0, 96, 1), (128, 320, 2), (256, 320, 3)] {
+        let batch = TestBatch {
+            first_slot,
+            slot,
+            root,
+        };
+        store.put_inputs(&batch).unwrap();
+    }
+}
+
+#[test]
+fn inputs_are_keyed_by_id_and_network() {
+    let dir = work_dir("artifact-store-inputs");
+    let store = open_store(&dir, "sepolia");
+    let update = committee_update();
+
+    let path = store.put_inputs(&update).unwrap();
+    assert_eq!(
+        PathBuf::from(path),
+        dir.join("artifacts/sepolia")
+            .join(update.id())
+            .join("input.json")
+    );
+
+    let manifest = store
+        .find_by_slot(ProofType::SyncCommittee, 5800000)
+        .unwrap();
+    assert_eq!(manifest.id, update.id());
+    assert_eq!(manifest.network, "sepolia");
+    assert_eq!(manifest.artifacts.len(), 1);
+    assert_eq!(manifest.artifacts[0].kind, ArtifactKind::Inputs);
+
+    let loaded: SyncCommitteeUpdate = store
+        .load_by_slot(ProofType::SyncCommittee, 5800000)
+        .unwrap();
+    assert_eq!(
+        loaded.expected_circuit_outputs.committee_hash,
+        update.expected_circuit_outputs.committee_hash
+    );
+
+    // Neither another network nor another proof type sees the update
+    assert!(matches!(
+        open_store(&dir, "mainnet").find_by_slot(ProofType::SyncCommittee, 5800000),
+        Err(Error::ArtifactNotFound(_))
+    ));
+    assert!(matches!(
+        store.find_by_slot(ProofType::Epoch, 5800000),
+        Err(Error::ArtifactNotFound(_))
+    ));
+}
+
+#[test]
+fn modified_inputs_are_rejected() {
+    let dir = work_dir("artifact-store-modified");
+    let store = open_store(&dir, "sepolia");
+    let update = committee_update();
+    let path = store.put_inputs(&update).unwrap();
+
+    let mut json: Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    json["expected_circuit_outputs"]["slot"] = Value::from(5800001);
+    std::fs::write(&path, json.to_string()).unwrap();
+
+    assert!(matches!(
+        store.load_inputs::<SyncCommitteeUpdate>(&update.id()),
+        Err(Error::ArtifactIntegrityError(_))
+    ));
+}
+
+#[test]
+fn overlapping_batches_are_never_picked_arbitrarily() {
+    let dir = work_dir("artifact-store-batches");
+    let store = open_store(&dir, "sepolia");
+    store_batches(&store);
+
+    match store.find_by_slot(ProofType::EpochBatch, 320) {
+        Err(Error::AmbiguousArtifact(_, ids)) => assert_eq!(ids, ["batch_2", "batch_3"]),
+        other => panic!(
+            "expected an ambiguous lookup, got {:?}",
+            other.map(|m| m.id)
+        ),
+    }
+    assert!(matches!(
+        EpochUpdateBatch::find_containing(&store, 288),
+        Err(Error::AmbiguousArtifact(..))
+    ));
+    assert!(matches!(
+        EpochUpdateBatch::find_containing(&store, 100),
+        Err(Error::EpochNotBatched(100))
+    ));
+
+    assert_eq!(
+        store.find_by_slot(ProofType::EpochBatch, 96).unwrap().id,
+        "batch_1"
+    );
+    assert_eq!(
+        store.find_by_batch_root(Felt::from(3)).unwrap().id,
+        "batch_3"
+    );
+
+    let ids = |query: ArtifactQuery| -> Vec<String> {
+        store
+            .list(&query)
+            .unwrap()
+            .into_iter()
+            .map(|manifest| manifest.id)
+            .collect()
+    };
+    assert_eq!(ids(ArtifactQuery::default()).len(), 3);
+    assert_eq!(
+        ids(ArtifactQuery::containing(ProofType::EpochBatch, 200)),
+        ["batch_2"]
+    );
+    let query = ArtifactQuery {
+        to_slot: Some(127),
+        ..Default::default()
+    };
+    assert_eq!(ids(query), ["batch_1"]);
+}
+
+#[test]
+fn prune_removes_selected_kinds_or_updates() {
+    let dir = work_dir("artifact-store-prune");
+    let store = open_store(&dir, "sepolia");
+    store_batches(&store);
+    let proof_path = store
+        .put_bytes("batch_1", ArtifactKind::Proof, "proof.json", b"{}")
+        .unwrap();
+    let query = ArtifactQuery {
+        to_slot: Some(127),
+        ..Default::default()
+    };
+
+    let pruned = store.prune(&query, &[ArtifactKind::Proof], true).unwrap();
+    assert_eq!(pruned.len(), 1);
+    assert!(Path::new(&proof_path).exists());
+
+    store.prune(&query, &[ArtifactKind::Proof], false).unwrap();
+    assert!(!Path::new(&proof_path).exists());
+    let manifest = store.manifest("batch_1").unwrap();
+    assert_eq!(manifest.artifacts.len(), 1);
+    assert_eq!(manifest.artifacts[0].kind, ArtifactKind::Inputs);
+
+    // Nothing of the kind is left to prune
+    assert!(store
+        .prune(&query, &[ArtifactKind::Proof], false)
+        .unwrap()
+        .is_empty());
+
+    store.prune(&query, &[], false).unwrap();
+    assert!(!Path::new(&store.dir("batch_1")).exists());
+    assert_eq!(store.list(&ArtifactQuery::default()).unwrap().len(), 2);
+}
+
+/// Runs the CLI with only a config, so no endpoints are set
+async fn run_offline(dir: &Path, args: &[&str]) -> Output {
+    tokio::process::Command::new(env!("CARGO_BIN_EXE_client-rs"))
+        .args(["--output", "json"])
+        .args(args)
+        .current_dir(dir)
+        .env_clear()
+        .env("BANKAI_CONFIG_PATH", dir.join("bankai_config.json"))
+        .output()
+        .await
+        .unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn cli_lists_and_prunes_without_endpoints() {
+    let dir = work_dir("artifact-store-cli");
+    store_batches(&open_store(&dir, "sepolia"));
+    let config = serde_json::json!({ "artifact_store_dir": dir.join("artifacts") });
+    std::fs::write(dir.join("bankai_config.json"), config.to_string()).unwrap();
+
+    let args = [
+        "list-artifacts",
+        "--proof-type",
+        "epoch-batch",
+        "--from",
+        "300",
+    ];
+    let output = run_offline(&dir, &args).await;
+    assert!(output.status.success());
+    let report: Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(report["updates"].as_array().unwrap().len(), 2);
+
+    // Pruning needs a selection
+    let output = run_offline(&dir, &["prune-artifacts"]).await;
+    assert_eq!(output.status.code(), Some(1));
+
+    let output = run_offline(&dir, &["prune-artifacts", "--batch-root", "0x2"]).await;
+    assert!(output.status.success());
+    let report: Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(report["pruned"][0]["id"], "batch_2");
+    assert!(!dir.join("artifacts/sepolia/batch_2").exists());
+}
@@ -0,0 +1,181 @@
+mod common;
+
+use bankai_client::utils::proof_archive::ProofArchive;
+use bankai_client::Error;
+use common::atlantic_mock::{AtlanticMock, Endpoint, Failure};
+use common::{run_cli, stdout, work_dir};
+use serde_json::{json, Value};
+
+/// Exit code of commands waiting for a prover job that is not done yet
+const EXIT_NOT_READY: i32 = 3;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn check_batch_status_follows_transitions() {
+    let mock = AtlanticMock::start(&["RECEIVED", "IN_PROGRESS", "DONE"]).await;
+    mock.add_query("query-1", json!({}));
+    let dir = work_dir("status-transitions");
+
+    for expected in ["RECEIVED", "IN_PROGRESS"] {
+        let output = run_cli(
+            &mock,
+            &dir,
+            &["check-batch-status", "--batch-id", "query-1"],
+        )
+        .await;
+        assert_eq!(output.status.code(), Some(EXIT_NOT_READY));
+        assert!(stdout(&output).contains(&format!("Batch not completed yet. Status: {}", expected)));
+    }
+    for _ in 0..2 {
+        let output = run_cli(
+            &mock,
+            &dir,
+            &["check-batch-status", "--batch-id", "query-1"],
+        )
+        .await;
+        assert!(output.status.success());
+        assert!(stdout(&output).contains("Batch Status: DONE"));
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn json_output_reports_status() {
+    let mock = AtlanticMock::start(&["IN_PROGRESS", "DONE"]).await;
+    mock.add_query("query-1", json!({}));
+    let dir = work_dir("json-output");
+
+    let args = [
+        "--output",
+        "json",
+        "check-batch-status",
+        "--batch-id",
+        "query-1",
+    ];
+    let output = run_cli(&mock, &dir, &args).await;
+    assert_eq!(output.status.code(), Some(EXIT_NOT_READY));
+    let report: Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(report["status"], "not_ready");
+    assert_eq!(report["job_id"], "query-1");
+    assert_eq!(report["job_status"], "IN_PROGRESS");
+
+    let output = run_cli(&mock, &dir, &args).await;
+    assert!(output.status.success());
+    let report: Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(report["status"], "success");
+    assert_eq!(report["job_status"], "DONE");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn submit_wrapped_proof_uploads_registry_proof() {
+    let mock = AtlanticMock::start(&["DONE"]).await;
+    mock.add_query("query-1", json!({ "proof": "stark" }));
+    let dir = work_dir("wrap-done");
+
+    let output = run_cli(
+        &mock,
+        &dir,
+        &["submit-wrapped-proof", "--batch-id", "query-1"],
+    )
+    .await;
+    assert!(output.status.success());
+
+    let submissions = mock.submissions();
+    assert_eq!(submissions.len(), 1);
+    let submission = &submissions[0];
+    assert_eq!(submission.endpoint, Endpoint::L2Query);
+    assert_eq!(submission.fields["externalId"], "proof_wrapper");
+    assert_eq!(submission.fields["programHash"], "0x1");
+    assert!(submission.files["inputFile"] > 0);
+    assert!(stdout(&output).contains(&format!("Batch Submitted: {}", submission.query_id)));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn submit_wrapped_proof_waits_for_completion() {
+    let mock = AtlanticMock::start(&["IN_PROGRESS", "DONE"]).await;
+    mock.add_query("query-1", json!({}));
+    let dir = work_dir("wrap-pending");
+
+    let output = run_cli(
+        &mock,
+        &dir,
+        &["submit-wrapped-proof", "--batch-id", "query-1"],
+    )
+    .await;
+    assert_eq!(output.status.code(), Some(EXIT_NOT_READY));
+    assert!(stdout(&output).contains("Batch not completed yet. Status: IN_PROGRESS"));
+    assert!(mock.submissions().is_empty());
+
+    let output = run_cli(
+        &mock,
+        &dir,
+        &["submit-wrapped-proof", "--batch-id", "query-1"],
+    )
+    .await;
+    assert!(output.status.success());
+    assert_eq!(mock.submissions().len(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn injected_failures_are_reported() {
+    let mock = AtlanticMock::start(&["DONE"]).await;
+    mock.add_query("query-1", json!({}));
+    let dir = work_dir("failures");
+
+    mock.inject_failure(Endpoint::QueryStatus, Failure::MalformedBody);
+    let output = run_cli(
+        &mock,
+        &dir,
+        &["check-batch-status", "--batch-id", "query-1"],
+    )
+    .await;
+    assert!(!output.status.success());
+    mock.clear_failure(Endpoint::QueryStatus);
+
+    mock.inject_failure(Endpoint::L2Query, Failure::Status(500));
+    let output = run_cli(
+        &mock,
+        &dir,
+        &["submit-wrapped-proof", "--batch-id", "query-1"],
+    )
+    .await;
+    assert!(!output.status.success());
+    assert!(mock.submissions().is_empty());
+    mock.clear_failure(Endpoint::L2Query);
+
+    let output = run_cli(
+        &mock,
+        &dir,
+        &["check-batch-status", "--batch-id", "unknown"],
+    )
+    .await;
+    assert!(!output.status.success());
+}
+
+#[test]
+fn archived_proofs_are_verified_on_load() {
+    let dir = work_dir("archive-verify");
+    let dir = dir.to_str().unwrap();
+    let mut archive = ProofArchive::new("query-1", None);
+    archive
+        .store(
+            dir,
+            "query-1",
+            "proof.json",
+            b"{\"proof\": \"stark\"}".to_vec(),
+        )
+        .unwrap();
+    archive
+        .store(dir, "query-1", "metadata.json", b"{}".to_vec())
+        .unwrap();
+    assert_eq!(
+        archive.load_proof().unwrap().proof,
+        json!({ "proof": "stark" })
+    );
+
+    // A modified artifact next to the proof fails the load
+    let metadata = archive.artifact_path(dir, "query-1", "metadata.json");
+    std::fs::write(metadata, b"{\"steps\": 1}").unwrap();
+    assert!(matches!(
+        archive.load_proof(),
+        Err(Error::ArtifactIntegrityError(_))
+    ));
+}
@@ -0,0 +1,127 @@
+//! Offline tests of the input generation against recorded beacon node responses.
+//!
+//! The golden tests regenerate the circuit fixtures in `cairo/tests/fixtures` from the
+//! responses recorded in `tests/fixtures/beacon/<case>`. To record a case, point the mock at
+//! a beacon node with the debug API enabled:
+//!
+//! ```bash
+//! BEACON_RECORD_URL=<BEACON_RPC_URL> cargo test --test beacon_fixtures -- --ignored
+//! ```
+//!
+//! `UPDATE_GOLDEN=1` rewrites the circuit fixtures instead of comparing against them.
+
+mod common;
+
+use std::path::Path;
+
+use bankai_client::epoch_update::EpochUpdate;
+use bankai_client::sync_committee::SyncCommitteeUpdate;
+use bankai_client::utils::rpc::BeaconRpcClient;
+use bankai_client::Error;
+use common::beacon_mock::{write_fixture, BeaconMock};
+use common::work_dir;
+use serde::Serialize;
+use serde_json::ser::PrettyFormatter;
+use serde_json::{json, Serializer};
+
+/// Pretty JSON with the 4-space indentation of the circuit fixtures
+fn to_fixture_json(value: &impl Serialize) -> String {
+    let mut json = Vec::new();
+    let formatter = PrettyFormatter::with_indent(b"    ");
+    value
+        .serialize(&mut Serializer::with_formatter(&mut json, formatter))
+        .unwrap();
+    String::from_utf8(json).unwrap()
+}
+
+/// Compares the pretty JSON of the value with the circuit fixture, byte for byte
+fn assert_golden(value: &impl Serialize, fixture: &str) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../cairo/tests/fixtures")
+        .join(fixture);
+    let json = to_fixture_json(value);
+    if std::env::var("UPDATE_GOLDEN").is_ok() {
+        std::fs::write(&path, json).unwrap();
+        return;
+    }
+    let expected = std::fs::read_to_string(&path).unwrap();
+    assert!(
+        json == expected,
+        "{} differs from the regenerated inputs, run with UPDATE_GOLDEN=1 to rewrite it",
+        fixture
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn head_slot_is_read_from_the_head_header() {
+    let dir = work_dir("beacon-head");
+    write_fixture(
+        &dir,
+        "/eth/v1/beacon/headers/head",
+        &json!({ "data": { "header": { "message": { "slot": "6463489" } } } }),
+    );
+    let mock = BeaconMock::replay(&dir).await;
+    let client = BeaconRpcClient::new(mock.endpoint());
+
+    assert_eq!(client.get_head_slot().await.unwrap(), 6463489);
+    assert_eq!(mock.requests(), vec!["/eth/v1/beacon/headers/head"]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn missing_block_is_an_empty_slot() {
+    let dir = work_dir("beacon-empty-slot");
+    write_fixture(
+        &dir,
+        "/eth/v1/beacon/headers/6463490",
+        &json!({ "code": 404, "message": "NOT_FOUND: beacon block at slot 6463490" }),
+    );
+    let mock = BeaconMock::replay(&dir).await;
+    let client = BeaconRpcClient::new(mock.endpoint());
+
+    assert!(matches!(
+        client.get_header(6463490).await,
+        Err(Error::EmptySlotDetected(6463490))
+    ));
+    assert!(mock.misses().is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn unrecorded_routes_are_reported() {
+    let dir = work_dir("beacon-miss");
+    let mock = BeaconMock::replay(&dir).await;
+    let client = BeaconRpcClient::new(mock.endpoint());
+
+    assert!(client.get_header(1).await.is_err());
+    assert!(mock.misses().contains("/eth/v1/beacon/headers/1"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "needs the responses recorded in tests/fixtures/beacon/epoch_6463489"]
+async fn epoch_update_matches_fixture() {
+    let mock = BeaconMock::start("epoch_6463489").await;
+    let client = BeaconRpcClient::new(mock.endpoint());
+
+    let update = EpochUpdate::new(&client, 6463489).await.unwrap();
+    assert!(mock.misses().is_empty(), "{:?}", mock.misses());
+    assert_golden(&update, "epoch_6463489_proof.json");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "needs the responses recorded in tests/fixtures/beacon/committee_update_5800000"]
+async fn committee_update_matches_fixture() {
+    let mock = BeaconMock::start("committee_update_5800000").await;
+    let client = BeaconRpcClient::new(mock.endpoint());
+
+    let update = SyncCommitteeUpdate::new(&client, 5800000).await.unwrap();
+    assert!(mock.misses().is_empty(), "{:?}", mock.misses());
+    assert_golden(&update, "committee_update_5800000.json");
+}
+
+#[test]
+fn committee_fixture_round_trips() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../cairo/tests/fixtures/committee_update_5800000.json");
+    let expected = std::fs::read_to_string(path).unwrap();
+    let update: SyncCommitteeUpdate = serde_json::from_str(&expected).unwrap();
+    assert!(to_fixture_json(&update) == expected);
+}
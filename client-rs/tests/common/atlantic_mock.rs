@@ -0,0 +1,305 @@
+//! An in-process stand-in for the Atlantic API and the proof registry.
+//!
+//! Queries move through a configurable list of statuses, advancing one step on every
+//! status request. Failures can be injected per endpoint.
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+use axum::extract::{Multipart, Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde_json::{json, Value};
+
+pub const API_KEY: &str = "mock-api-key";
+
+/// The endpoints of the mock that failures can be injected into
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Endpoint {
+    ProofGeneration,
+    L2Query,
+    QueryStatus,
+    ProofRegistry,
+}
+
+/// How an endpoint should misbehave
+#[derive(Debug, Clone)]
+pub enum Failure {
+    /// Respond with the given status code and an error body
+    Status(u16),
+    /// Respond with 200 but leave out the expected fields
+    MalformedBody,
+}
+
+/// A submission received by the mock
+#[derive(Debug, Clone)]
+pub struct Submission {
+    pub query_id: String,
+    pub endpoint: Endpoint,
+    /// Text fields of the multipart form
+    pub fields: HashMap<String, String>,
+    /// Names and sizes of the uploaded files
+    pub files: HashMap<String, usize>,
+}
+
+#[derive(Debug)]
+struct MockQuery {
+    polls: usize,
+}
+
+#[derive(Debug)]
+struct MockState {
+    status_transitions: Vec<String>,
+    queries: HashMap<String, MockQuery>,
+    proofs: HashMap<String, Value>,
+    failures: HashMap<Endpoint, Failure>,
+    submissions: Vec<Submission>,
+    next_id: usize,
+}
+
+impl MockState {
+    fn register_query(&mut self) -> String {
+        self.next_id += 1;
+        let query_id = format!("mock-query-{}", self.next_id);
+        self.queries
+            .insert(query_id.clone(), MockQuery { polls: 0 });
+        query_id
+    }
+}
+
+/// Handle to a running mock server
+#[derive(Clone)]
+pub struct AtlanticMock {
+    pub addr: SocketAddr,
+    state: Arc<Mutex<MockState>>,
+}
+
+impl AtlanticMock {
+    /// Starts the mock on a random local port. Queries pass through
+    /// `status_transitions`, staying in the last status once it is reached.
+    pub async fn start(status_transitions: &[&str]) -> Self {
+        let state = Arc::new(Mutex::new(MockState {
+            status_transitions: status_transitions.iter().map(|s| s.to_string()).collect(),
+            queries: HashMap::new(),
+            proofs: HashMap::new(),
+            failures: HashMap::new(),
+            submissions: Vec::new(),
+            next_id: 0,
+        }));
+
+        let app = Router::new()
+            .route("/v1/proof-generation", post(proof_generation))
+            .route("/v1/l2/atlantic-query", post(l2_query))
+            .route("/v1/atlantic-query/:id", get(query_status))
+            .route("/proofs/:query_dir/proof.json", get(registry_proof))
+            .with_state(state.clone());
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        Self { addr, state }
+    }
+
+    pub fn endpoint(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Value for the `PROOF_REGISTRY` environment variable
+    pub fn proof_registry(&self) -> String {
+        format!("http://{}/proofs", self.addr)
+    }
+
+    /// Registers an existing query, e.g. one submitted in a previous run, with a stored proof
+    pub fn add_query(&self, query_id: &str, proof: Value) {
+        let mut state = self.state.lock().unwrap();
+        state
+            .queries
+            .insert(query_id.to_string(), MockQuery { polls: 0 });
+        state.proofs.insert(query_id.to_string(), proof);
+    }
+
+    pub fn inject_failure(&self, endpoint: Endpoint, failure: Failure) {
+        self.state
+            .lock()
+            .unwrap()
+            .failures
+            .insert(endpoint, failure);
+    }
+
+    pub fn clear_failure(&self, endpoint: Endpoint) {
+        self.state.lock().unwrap().failures.remove(&endpoint);
+    }
+
+    pub fn submissions(&self) -> Vec<Submission> {
+        self.state.lock().unwrap().submissions.clone()
+    }
+}
+
+type SharedState = State<Arc<Mutex<MockState>>>;
+
+fn check_api_key(params: &HashMap<String, String>) -> Result<(), Response> {
+    match params.get("apiKey") {
+        Some(key) if key == API_KEY => Ok(()),
+        _ => Err((
+            StatusCode::UNAUTHORIZED,
+            Json(json!({ "message": "Invalid API key" })),
+        )
+            .into_response()),
+    }
+}
+
+fn injected_failure(state: &MockState, endpoint: Endpoint) -> Option<Response> {
+    state.failures.get(&endpoint).map(|failure| match failure {
+        Failure::Status(code) => (
+            StatusCode::from_u16(*code).unwrap(),
+            Json(json!({ "message": "Injected failure" })),
+        )
+            .into_response(),
+        Failure::MalformedBody => Json(json!({ "unexpected": true })).into_response(),
+    })
+}
+
+async fn read_form(
+    mut multipart: Multipart,
+) -> Result<(HashMap<String, String>, HashMap<String, usize>), Response> {
+    let mut fields = HashMap::new();
+    let mut files = HashMap::new();
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()).into_response())?
+    {
+        let name = field.name().unwrap_or_default().to_string();
+        let is_file = field.file_name().is_some();
+        let bytes = field
+            .bytes()
+            .await
+            .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()).into_response())?;
+        if is_file {
+            files.insert(name, bytes.len());
+        } else {
+            fields.insert(name, String::from_utf8_lossy(&bytes).to_string());
+        }
+    }
+    Ok((fields, files))
+}
+
+async fn submit(
+    state: Arc<Mutex<MockState>>,
+    endpoint: Endpoint,
+    params: HashMap<String, String>,
+    multipart: Multipart,
+    required_file: &str,
+) -> Response {
+    if let Err(response) = check_api_key(&params) {
+        return response;
+    }
+    if let Some(response) = injected_failure(&state.lock().unwrap(), endpoint) {
+        return response;
+    }
+
+    let (fields, files) = match read_form(multipart).await {
+        Ok(form) => form,
+        Err(response) => return response,
+    };
+    if !files.contains_key(required_file) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "message": format!("Missing {}", required_file) })),
+        )
+            .into_response();
+    }
+
+    let mut state = state.lock().unwrap();
+    let query_id = state.register_query();
+    state.proofs.insert(
+        query_id.clone(),
+        json!({ "mock_proof": query_id, "fields": fields }),
+    );
+    state.submissions.push(Submission {
+        query_id: query_id.clone(),
+        endpoint,
+        fields,
+        files,
+    });
+
+    Json(json!({ "atlanticQueryId": query_id })).into_response()
+}
+
+async fn proof_generation(
+    State(state): SharedState,
+    Query(params): Query<HashMap<String, String>>,
+    multipart: Multipart,
+) -> Response {
+    submit(
+        state,
+        Endpoint::ProofGeneration,
+        params,
+        multipart,
+        "pieFile",
+    )
+    .await
+}
+
+async fn l2_query(
+    State(state): SharedState,
+    Query(params): Query<HashMap<String, String>>,
+    multipart: Multipart,
+) -> Response {
+    submit(state, Endpoint::L2Query, params, multipart, "inputFile").await
+}
+
+async fn query_status(
+    State(state): SharedState,
+    Path(id): Path<String>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Response {
+    if let Err(response) = check_api_key(&params) {
+        return response;
+    }
+
+    let mut state = state.lock().unwrap();
+    if let Some(response) = injected_failure(&state, Endpoint::QueryStatus) {
+        return response;
+    }
+
+    let transitions = state.status_transitions.clone();
+    let Some(query) = state.queries.get_mut(&id) else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(json!({ "message": "Query not found" })),
+        )
+            .into_response();
+    };
+
+    let status = transitions
+        .get(query.polls.min(transitions.len().saturating_sub(1)))
+        .cloned()
+        .unwrap_or_else(|| "DONE".to_string());
+    query.polls += 1;
+
+    Json(json!({ "atlanticQuery": { "id": id, "status": status } })).into_response()
+}
+
+async fn registry_proof(State(state): SharedState, Path(query_dir): Path<String>) -> Response {
+    let state = state.lock().unwrap();
+    if let Some(response) = injected_failure(&state, Endpoint::ProofRegistry) {
+        return response;
+    }
+
+    let id = query_dir.strip_prefix("query_").unwrap_or(&query_dir);
+    match state.proofs.get(id) {
+        Some(proof) => Json(proof.clone()).into_response(),
+        None => (
+            StatusCode::NOT_FOUND,
+            Json(json!({ "message": "Proof not found" })),
+        )
+            .into_response(),
+    }
+}
@@ -0,0 +1,202 @@
+//! An in-process stand-in for a beacon node, serving responses recorded from a real one.
+//!
+//! Every response is stored as one file, named after the route of the request. In record
+//! mode the mock proxies all requests to the upstream node and writes the responses to the
+//! fixture directory, so a test recorded once replays offline from then on.
+
+use std::collections::HashSet;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::{header, HeaderMap, StatusCode, Uri};
+use axum::response::{IntoResponse, Response};
+use axum::{Json, Router};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+/// Setting this to the URL of a beacon node records the fixtures instead of replaying them
+pub const RECORD_ENV: &str = "BEACON_RECORD_URL";
+
+const SSZ: &str = "application/octet-stream";
+
+#[derive(Debug)]
+struct MockState {
+    dir: PathBuf,
+    /// Upstream beacon node in record mode
+    upstream: Option<String>,
+    requests: Vec<String>,
+    misses: HashSet<String>,
+}
+
+/// Handle to a running mock server
+#[derive(Clone)]
+pub struct BeaconMock {
+    pub addr: SocketAddr,
+    state: Arc<Mutex<MockState>>,
+}
+
+impl BeaconMock {
+    /// Starts the mock on a random local port, replaying the fixtures in
+    /// `tests/fixtures/beacon/<case>`. Records them if `BEACON_RECORD_URL` is set.
+    pub async fn start(case: &str) -> Self {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/beacon")
+            .join(case);
+        let upstream = std::env::var(RECORD_ENV).ok();
+        Self::serve(dir, upstream).await
+    }
+
+    /// Starts the mock replaying the fixtures in `dir`
+    pub async fn replay(dir: &Path) -> Self {
+        Self::serve(dir.to_path_buf(), None).await
+    }
+
+    async fn serve(dir: PathBuf, upstream: Option<String>) -> Self {
+        let state = Arc::new(Mutex::new(MockState {
+            dir,
+            upstream: upstream.map(|url| url.trim_end_matches('/').to_string()),
+            requests: Vec::new(),
+            misses: HashSet::new(),
+        }));
+
+        let app = Router::new()
+            .fallback(beacon_request)
+            .with_state(state.clone());
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        Self { addr, state }
+    }
+
+    pub fn endpoint(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Routes requested so far, in order
+    pub fn requests(&self) -> Vec<String> {
+        self.state.lock().unwrap().requests.clone()
+    }
+
+    /// Routes that were requested but have no recorded response
+    pub fn misses(&self) -> HashSet<String> {
+        self.state.lock().unwrap().misses.clone()
+    }
+}
+
+/// Name of the fixture of a route. Queries, such as the validator ids of a request, are
+/// replaced with their hash to keep the names short.
+pub fn fixture_name(route: &str, ssz: bool) -> String {
+    let route = route.trim_start_matches('/');
+    let (path, query) = route.split_once('?').unwrap_or((route, ""));
+    let mut name = path.replace('/', "_");
+    if !query.is_empty() {
+        let query_hash = hex::encode(Sha256::digest(query.as_bytes()));
+        name = format!("{}_{}", name, &query_hash[..16]);
+    }
+    format!("{}.{}", name, if ssz { "ssz" } else { "json" })
+}
+
+/// Writes the response of a route to the fixture directory
+pub fn write_fixture(dir: &Path, route: &str, body: &Value) {
+    std::fs::create_dir_all(dir).unwrap();
+    std::fs::write(
+        dir.join(fixture_name(route, false)),
+        serde_json::to_string_pretty(body).unwrap(),
+    )
+    .unwrap();
+}
+
+fn wants_ssz(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::ACCEPT)
+        .and_then(|accept| accept.to_str().ok())
+        .is_some_and(|accept| accept.starts_with(SSZ))
+}
+
+/// Beacon API errors carry their status code in the body
+fn response_status(body: &[u8], ssz: bool) -> StatusCode {
+    if ssz {
+        return StatusCode::OK;
+    }
+    serde_json::from_slice::<Value>(body)
+        .ok()
+        .and_then(|json| json.get("code").and_then(Value::as_u64))
+        .and_then(|code| StatusCode::from_u16(code as u16).ok())
+        .unwrap_or(StatusCode::OK)
+}
+
+async fn record(upstream: &str, route: &str, ssz: bool, path: &Path) -> Result<Bytes, String> {
+    let mut request = reqwest::Client::new().get(format!("{}{}", upstream, route));
+    if ssz {
+        request = request.header(header::ACCEPT.as_str(), SSZ);
+    }
+    let body = request
+        .send()
+        .await
+        .map_err(|e| e.to_string())?
+        .bytes()
+        .await
+        .map_err(|e| e.to_string())?;
+    std::fs::create_dir_all(path.parent().unwrap()).map_err(|e| e.to_string())?;
+    std::fs::write(path, &body).map_err(|e| e.to_string())?;
+    Ok(body)
+}
+
+async fn beacon_request(
+    State(state): State<Arc<Mutex<MockState>>>,
+    uri: Uri,
+    headers: HeaderMap,
+) -> Response {
+    let route = uri
+        .path_and_query()
+        .map(|route| route.as_str().to_string())
+        .unwrap_or_default();
+    let ssz = wants_ssz(&headers);
+    let (path, upstream) = {
+        let mut state = state.lock().unwrap();
+        state.requests.push(route.clone());
+        (
+            state.dir.join(fixture_name(&route, ssz)),
+            state.upstream.clone(),
+        )
+    };
+
+    let body = match upstream {
+        Some(upstream) => match record(&upstream, &route, ssz, &path).await {
+            Ok(body) => body,
+            Err(e) => {
+                return (
+                    StatusCode::BAD_GATEWAY,
+                    Json(json!({ "message": format!("Recording failed: {}", e) })),
+                )
+                    .into_response()
+            }
+        },
+        None => match std::fs::read(&path) {
+            Ok(body) => Bytes::from(body),
+            Err(_) => {
+                state.lock().unwrap().misses.insert(route.clone());
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({ "message": format!("No fixture for {}", route) })),
+                )
+                    .into_response();
+            }
+        },
+    };
+
+    let content_type = if ssz { SSZ } else { "application/json" };
+    (
+        response_status(&body, ssz),
+        [(header::CONTENT_TYPE, content_type)],
+        body,
+    )
+        .into_response()
+}
@@ -0,0 +1,132 @@
+//! A local Starknet devnet with a deployed Bankai contract.
+//!
+//! The contract is built with the `mock_integrity` feature, which accepts every fact hash, so
+//! updates can be submitted without proofs registered in Integrity. Requires `scarb` and
+//! `starknet-devnet` (or the binary set in `STARKNET_DEVNET`).
+
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::time::Duration;
+
+use bankai_client::config::BankaiConfig;
+use bankai_client::contract_init::ContractInitializationData;
+use bankai_client::utils::starknet_client::StarknetClient;
+use serde_json::{json, Value};
+use starknet::core::types::Felt;
+
+const STARTUP_ATTEMPTS: u32 = 50;
+
+/// Handle to a running devnet process, killed on drop
+pub struct Devnet {
+    process: Child,
+    pub rpc_url: String,
+    /// Address and private key of the first predeployed account
+    pub account: (String, String),
+}
+
+impl Devnet {
+    pub async fn start() -> Self {
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let binary =
+            std::env::var("STARKNET_DEVNET").unwrap_or_else(|_| "starknet-devnet".to_string());
+        let process = Command::new(binary)
+            .args(["--host", "127.0.0.1", "--port", &port.to_string()])
+            .args(["--seed", "0", "--accounts", "1"])
+            .stdout(Stdio::null())
+            .spawn()
+            .expect("starknet-devnet is installed");
+        let mut devnet = Self {
+            process,
+            rpc_url: format!("http://127.0.0.1:{}/rpc", port),
+            account: (String::new(), String::new()),
+        };
+
+        let client = reqwest::Client::new();
+        let is_alive = format!("http://127.0.0.1:{}/is_alive", port);
+        for _ in 0..STARTUP_ATTEMPTS {
+            if client.get(&is_alive).send().await.is_ok() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(200)).await;
+        }
+
+        let accounts = devnet.rpc("devnet_getPredeployedAccounts", json!({})).await;
+        devnet.account = (
+            accounts[0]["address"].as_str().unwrap().to_string(),
+            accounts[0]["private_key"].as_str().unwrap().to_string(),
+        );
+        devnet
+    }
+
+    async fn rpc(&self, method: &str, params: Value) -> Value {
+        let response: Value = reqwest::Client::new()
+            .post(&self.rpc_url)
+            .json(&json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        response["result"].clone()
+    }
+
+    /// A client signing with the predeployed account
+    pub async fn client(&self) -> StarknetClient {
+        StarknetClient::new(&self.rpc_url, &self.account.0, &self.account.1)
+            .await
+            .unwrap()
+    }
+
+    /// Declares and deploys the contract. Returns a config pointing to the deployment.
+    pub async fn deploy(
+        &self,
+        client: &StarknetClient,
+        init_data: ContractInitializationData,
+    ) -> BankaiConfig {
+        let artifacts = build_contract();
+        let mut config = BankaiConfig {
+            contract_path: artifacts
+                .join("bankai_BankaiContract.contract_class.json")
+                .to_string_lossy()
+                .to_string(),
+            contract_casm_path: artifacts
+                .join("bankai_BankaiContract.compiled_contract_class.json")
+                .to_string_lossy()
+                .to_string(),
+            deployment_salt: Felt::from(std::process::id()),
+            ..Default::default()
+        };
+        let deployment = client.deploy_contract(init_data, &config).await.unwrap();
+        config.contract_class_hash = deployment.class_hash;
+        config.contract_address = deployment.contract_address;
+        config
+    }
+}
+
+impl Drop for Devnet {
+    fn drop(&mut self) {
+        let _ = self.process.kill();
+        let _ = self.process.wait();
+    }
+}
+
+/// Builds the contract with the `mock_integrity` feature into a separate target directory,
+/// returning the directory of the artifacts
+fn build_contract() -> PathBuf {
+    let manifest = Path::new(env!("CARGO_MANIFEST_DIR")).join("../contract/Scarb.toml");
+    let target_dir = std::env::temp_dir().join("bankai-devnet-contract");
+    let status = Command::new("scarb")
+        .arg("--manifest-path")
+        .arg(&manifest)
+        .args(["--release", "build", "--features", "mock_integrity"])
+        .env("SCARB_TARGET_DIR", &target_dir)
+        .status()
+        .expect("scarb is installed");
+    assert!(status.success(), "scarb build failed");
+    target_dir.join("release")
+}
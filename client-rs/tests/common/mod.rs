@@ -0,0 +1,44 @@
+#![allow(dead_code)]
+
+pub mod atlantic_mock;
+pub mod beacon_mock;
+pub mod devnet;
+
+use std::path::PathBuf;
+use std::process::Output;
+
+use atlantic_mock::{AtlanticMock, API_KEY};
+
+/// Creates an empty working directory for a CLI run, so no local `.env` or config is picked up
+pub fn work_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("bankai-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Runs the CLI against the mock Atlantic server. Starknet and beacon endpoints point to
+/// an unreachable address, so only flows that don't need them can succeed.
+pub async fn run_cli(mock: &AtlanticMock, dir: &PathBuf, args: &[&str]) -> Output {
+    let config = serde_json::json!({ "atlantic_endpoint": mock.endpoint() });
+    std::fs::write(dir.join("bankai_config.json"), config.to_string()).unwrap();
+
+    tokio::process::Command::new(env!("CARGO_BIN_EXE_client-rs"))
+        .args(args)
+        .current_dir(dir)
+        .env("BANKAI_CONFIG_PATH", dir.join("bankai_config.json"))
+        .env("BEACON_RPC_URL", "http://127.0.0.1:1")
+        .env("STARKNET_RPC_URL", "http://127.0.0.1:1")
+        .env("STARKNET_ADDRESS", "0x1")
+        .env("STARKNET_PRIVATE_KEY", "0x1")
+        .env("ATLANTIC_API_KEY", API_KEY)
+        .env("PROOF_REGISTRY", mock.proof_registry())
+        .env("PROOF_WRAPPER_PROGRAM_HASH", "0x1")
+        .output()
+        .await
+        .unwrap()
+}
+
+pub fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
@@ -0,0 +1,85 @@
+//! Property tests of the JSON encoding of the BLS12-381 points in the circuit inputs
+
+use std::path::Path;
+
+use bankai_client::epoch_update::{G1Point, G2Point};
+use bls12_381::{G1Affine, G1Projective, G2Affine, G2Projective, Scalar};
+use proptest::prelude::*;
+use serde_json::Value;
+
+fn scalars() -> impl Strategy<Value = Scalar> {
+    any::<[u64; 4]>().prop_map(Scalar::from_raw)
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn g1_points_round_trip(scalar in scalars()) {
+        let point = G1Affine::from(G1Projective::generator() * scalar);
+        let json = serde_json::to_string(&G1Point(point)).unwrap();
+        let decoded: G1Point = serde_json::from_str(&json).unwrap();
+        prop_assert_eq!(decoded.0, point);
+    }
+
+    #[test]
+    fn g2_points_round_trip(scalar in scalars()) {
+        let point = G2Affine::from(G2Projective::generator() * scalar);
+        let json = serde_json::to_string(&G2Point(point)).unwrap();
+        let decoded: G2Point = serde_json::from_str(&json).unwrap();
+        prop_assert_eq!(decoded.0, point);
+    }
+
+    /// The circuit reads `x0`/`y0` as the real and `x1`/`y1` as the imaginary part
+    #[test]
+    fn g2_coordinates_are_ordered_real_first(scalar in scalars()) {
+        let point = G2Affine::from(G2Projective::generator() * scalar);
+        prop_assume!(!bool::from(point.is_identity()));
+        let json = serde_json::to_value(G2Point(point)).unwrap();
+        let coordinate = |key: &str| json[key].as_str().unwrap().to_string();
+        let uncompressed = point.to_uncompressed();
+        // The uncompressed encoding starts with the imaginary part of x and carries flags
+        // in its top bits
+        prop_assert_eq!(coordinate("x0"), format!("0x{}", hex::encode(&uncompressed[48..96])));
+        prop_assert_eq!(coordinate("y1"), format!("0x{}", hex::encode(&uncompressed[96..144])));
+        prop_assert_eq!(coordinate("y0"), format!("0x{}", hex::encode(&uncompressed[144..192])));
+    }
+
+    #[test]
+    fn points_off_the_curve_are_rejected(x in any::<[u8; 48]>(), y in any::<[u8; 48]>()) {
+        let json = serde_json::json!({
+            "x": format!("0x{}", hex::encode(x)),
+            "y": format!("0x{}", hex::encode(y)),
+        });
+        let decoded = serde_json::from_value::<G1Point>(json);
+        let mut uncompressed = [0u8; 96];
+        uncompressed[..48].copy_from_slice(&x);
+        uncompressed[48..].copy_from_slice(&y);
+        let valid = bool::from(G1Affine::from_uncompressed(&uncompressed).is_some());
+        prop_assert_eq!(decoded.is_ok(), valid);
+    }
+}
+
+#[test]
+fn fixture_points_round_trip() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../cairo/tests/fixtures/epoch_6463489_proof.json");
+    let fixture: Value = serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+    let inputs = &fixture["circuit_inputs"];
+
+    let signature: G2Point = serde_json::from_value(inputs["signature_point"].clone()).unwrap();
+    assert_eq!(
+        serde_json::to_value(signature).unwrap(),
+        inputs["signature_point"]
+    );
+    let committee: G1Point = serde_json::from_value(inputs["committee_pub"].clone()).unwrap();
+    assert_eq!(
+        serde_json::to_value(committee).unwrap(),
+        inputs["committee_pub"]
+    );
+    let non_signers: Vec<G1Point> = serde_json::from_value(inputs["non_signers"].clone()).unwrap();
+    assert_eq!(
+        serde_json::to_value(non_signers).unwrap(),
+        inputs["non_signers"]
+    );
+}
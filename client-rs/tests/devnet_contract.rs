@@ -0,0 +1,189 @@
+//! End-to-end tests of `StarknetClient` against the contract deployed on a local devnet.
+//!
+//! ```bash
+//! cargo test --test devnet_contract -- --ignored
+//! ```
+
+mod common;
+
+use alloy_primitives::FixedBytes;
+use bankai_client::contract_init::ContractInitializationData;
+use bankai_client::epoch_batch::{EpochDecommitment, ExpectedEpochBatchOutputs};
+use bankai_client::epoch_update::ExpectedEpochUpdateOutputs;
+use bankai_client::sync_committee::ExpectedCircuitOutputs;
+use bankai_client::traits::Submittable;
+use bankai_client::utils::merkle::poseidon::compute_paths;
+use common::devnet::Devnet;
+use starknet::core::types::Felt;
+
+const COMMITTEE_ID: u64 = 700;
+const SLOTS_PER_PERIOD: u64 = 0x2000;
+
+/// A u256 with distinct halves, so swapped limbs are noticed
+fn u256(high: u128, low: u128) -> FixedBytes<32> {
+    let mut bytes = [0u8; 32];
+    bytes[..16].copy_from_slice(&high.to_be_bytes());
+    bytes[16..].copy_from_slice(&low.to_be_bytes());
+    FixedBytes::from(bytes)
+}
+
+/// An epoch signed by the committee, with all roots derived from the slot
+fn epoch(slot: u64, committee_hash: FixedBytes<32>) -> ExpectedEpochUpdateOutputs {
+    let seed = slot as u128;
+    ExpectedEpochUpdateOutputs {
+        beacon_header_root: u256(seed + 1, seed + 2),
+        beacon_state_root: u256(seed + 3, seed + 4),
+        slot,
+        committee_hash,
+        n_signers: 480,
+        execution_header_hash: u256(seed + 5, seed + 6),
+        execution_header_height: slot + 1000,
+    }
+}
+
+fn epoch_calldata(epoch: &ExpectedEpochUpdateOutputs) -> Vec<Felt> {
+    let seed = epoch.slot as u128;
+    vec![
+        Felt::from(seed + 2),
+        Felt::from(seed + 1),
+        Felt::from(seed + 4),
+        Felt::from(seed + 3),
+        Felt::from(epoch.slot),
+        Felt::from_bytes_be_slice(&epoch.committee_hash[16..]),
+        Felt::from_bytes_be_slice(&epoch.committee_hash[..16]),
+        Felt::from(480u64),
+        Felt::from(seed + 6),
+        Felt::from(seed + 5),
+        Felt::from(epoch.slot + 1000),
+    ]
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "needs starknet-devnet and scarb"]
+async fn updates_are_stored_by_the_contract() {
+    let devnet = Devnet::start().await;
+    let client = devnet.client().await;
+    let committee_hash = u256(0xc0, 0xc1);
+    let init_data = ContractInitializationData {
+        committee_id: COMMITTEE_ID,
+        committee_hash,
+        committee_update_program_hash: Felt::from(1u64),
+        epoch_update_program_hash: Felt::from(2u64),
+        epoch_batch_program_hash: Felt::from(3u64),
+    };
+    assert_eq!(
+        init_data.to_calldata(),
+        vec![
+            Felt::from(COMMITTEE_ID),
+            Felt::from(0xc1u64),
+            Felt::from(0xc0u64),
+            Felt::from(1u64),
+            Felt::from(2u64),
+            Felt::from(3u64),
+        ]
+    );
+    let config = devnet.deploy(&client, init_data).await;
+    assert_eq!(
+        client
+            .get_committee_hash(COMMITTEE_ID, &config)
+            .await
+            .unwrap(),
+        committee_hash
+    );
+
+    // verify_epoch_update
+    let slot = COMMITTEE_ID * SLOTS_PER_PERIOD + 32 * 5;
+    let update = epoch(slot, committee_hash);
+    assert_eq!(update.to_calldata(), epoch_calldata(&update));
+    client.submit_update(update.clone(), &config).await.unwrap();
+    let stored = client.get_epoch_proof(slot, &config).await.unwrap();
+    assert_eq!(stored.header_root, update.beacon_header_root);
+    assert_eq!(stored.beacon_state_root, update.beacon_state_root);
+    assert_eq!(stored.n_signers, update.n_signers);
+    assert_eq!(stored.execution_hash, update.execution_header_hash);
+    assert_eq!(stored.execution_height, update.execution_header_height);
+    assert_eq!(client.get_latest_epoch_slot(&config).await.unwrap(), slot);
+
+    // verify_committee_update, proven against the state root of the stored epoch
+    let next_committee_hash = u256(0xd0, 0xd1);
+    let committee_update = ExpectedCircuitOutputs {
+        state_root: update.beacon_state_root,
+        slot,
+        committee_hash: next_committee_hash,
+    };
+    assert_eq!(
+        committee_update.to_calldata(),
+        vec![
+            Felt::from(slot as u128 + 4),
+            Felt::from(slot as u128 + 3),
+            Felt::from(0xd1u64),
+            Felt::from(0xd0u64),
+            Felt::from(slot),
+        ]
+    );
+    client
+        .submit_update(committee_update, &config)
+        .await
+        .unwrap();
+    assert_eq!(
+        client
+            .get_committee_hash(COMMITTEE_ID + 1, &config)
+            .await
+            .unwrap(),
+        next_committee_hash
+    );
+    assert_eq!(
+        client.get_latest_committee_id(&config).await.unwrap(),
+        COMMITTEE_ID + 1
+    );
+
+    // verify_epoch_batch, storing the latest epoch of the batch
+    let epochs: Vec<ExpectedEpochUpdateOutputs> = (6..10)
+        .map(|i| epoch(COMMITTEE_ID * SLOTS_PER_PERIOD + 32 * i, committee_hash))
+        .collect();
+    let (batch_root, paths) =
+        compute_paths(epochs.iter().map(|epoch| epoch.hash()).collect()).unwrap();
+    let latest = epochs.last().unwrap().clone();
+    let batch = ExpectedEpochBatchOutputs {
+        batch_root,
+        latest_batch_output: latest.clone(),
+    };
+    let mut expected = vec![batch_root];
+    expected.extend(epoch_calldata(&latest));
+    assert_eq!(batch.to_calldata(), expected);
+    client.submit_update(batch, &config).await.unwrap();
+    let stored = client.get_epoch_proof(latest.slot, &config).await.unwrap();
+    assert_eq!(stored.header_root, latest.beacon_header_root);
+    assert_eq!(
+        client.get_latest_epoch_slot(&config).await.unwrap(),
+        latest.slot
+    );
+
+    // decommit_batched_epoch of an epoch inside the batch
+    let decommitment = EpochDecommitment {
+        batch_root,
+        merkle_index: 1,
+        merkle_path: paths[1].clone(),
+        epoch: epochs[1].clone(),
+    };
+    let mut expected = vec![
+        batch_root,
+        Felt::from(1u64),
+        Felt::from(paths[1].len() as u64),
+    ];
+    expected.extend(paths[1].iter().copied());
+    expected.extend(epoch_calldata(&epochs[1]));
+    assert_eq!(decommitment.to_calldata(), expected);
+    client.decommit_epoch(&decommitment, &config).await.unwrap();
+    let stored = client
+        .get_epoch_proof(epochs[1].slot, &config)
+        .await
+        .unwrap();
+    assert_eq!(stored.header_root, epochs[1].beacon_header_root);
+    assert_eq!(stored.execution_hash, epochs[1].execution_header_hash);
+
+    // A wrong merkle path is rejected
+    let mut invalid = decommitment;
+    invalid.merkle_index = 2;
+    assert!(client.decommit_epoch(&invalid, &config).await.is_err());
+}
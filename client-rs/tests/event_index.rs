@@ -0,0 +1,169 @@
+//! Decoding of the contract events and lookups in the event index
+
+use alloy_primitives::FixedBytes;
+use bankai_client::utils::event_index::{BankaiEvent, EpochSource, EventIndex};
+use bankai_client::Error;
+use starknet::core::types::Felt;
+use starknet::macros::selector;
+
+fn root(byte: u8) -> FixedBytes<32> {
+    FixedBytes::from([byte; 32])
+}
+
+/// Serializes a u256 as (low, high) 128 bit limbs
+fn u256_limbs(value: FixedBytes<32>) -> [Felt; 2] {
+    [
+        Felt::from_bytes_be_slice(&value[16..32]),
+        Felt::from_bytes_be_slice(&value[0..16]),
+    ]
+}
+
+/// Event data of an `EpochBatch` event, as emitted by the contract
+fn batch_data(batch_root: u64, slot: u64) -> Vec<Felt> {
+    let mut data = vec![Felt::from(batch_root)];
+    data.extend(u256_limbs(root(0xb0)));
+    data.push(Felt::from(slot));
+    data.extend(u256_limbs(root(0xe0)));
+    data.push(Felt::from(slot + 1000));
+    data
+}
+
+fn batch(batch_root: u64, slot: u64) -> BankaiEvent {
+    BankaiEvent::decode(&[selector!("EpochBatch")], &batch_data(batch_root, slot))
+        .unwrap()
+        .unwrap()
+}
+
+fn epoch_update(slot: u64) -> BankaiEvent {
+    BankaiEvent::EpochUpdated {
+        beacon_root: root(0xb1),
+        slot,
+        execution_hash: root(0xe1),
+        execution_height: slot + 1000,
+    }
+}
+
+#[test]
+fn events_are_decoded() {
+    match batch(7, 160) {
+        BankaiEvent::EpochBatch {
+            batch_root,
+            beacon_root,
+            slot,
+            execution_hash,
+            execution_height,
+        } => {
+            assert_eq!(batch_root, Felt::from(7));
+            assert_eq!(beacon_root, root(0xb0));
+            assert_eq!(slot, 160);
+            assert_eq!(execution_hash, root(0xe0));
+            assert_eq!(execution_height, 1160);
+        }
+        other => panic!("expected an epoch batch, got {:?}", other),
+    }
+
+    let mut data = vec![Felt::from(3)];
+    data.extend(u256_limbs(root(0xc0)));
+    let event = BankaiEvent::decode(&[selector!("CommitteeUpdated")], &data)
+        .unwrap()
+        .unwrap();
+    assert!(matches!(
+        event,
+        BankaiEvent::CommitteeUpdated { committee_id: 3, committee_hash } if committee_hash == root(0xc0)
+    ));
+
+    // Events of other contracts are skipped
+    assert!(BankaiEvent::decode(&[selector!("Transfer")], &data)
+        .unwrap()
+        .is_none());
+    assert!(BankaiEvent::decode(&[], &data).unwrap().is_none());
+}
+
+#[test]
+fn malformed_events_are_rejected() {
+    // The execution height is missing
+    let mut data = batch_data(7, 160);
+    data.pop();
+    assert!(matches!(
+        BankaiEvent::decode(&[selector!("EpochBatch")], &data),
+        Err(Error::InvalidEvent(_))
+    ));
+
+    // The slot does not fit into a u64
+    let mut data = batch_data(7, 160);
+    data[3] = Felt::from(u128::MAX);
+    assert!(matches!(
+        BankaiEvent::decode(&[selector!("EpochBatch")], &data),
+        Err(Error::InvalidEvent(_))
+    ));
+
+    // A u256 limb exceeds 128 bits
+    let mut data = batch_data(7, 160);
+    data[1] = Felt::from(u128::MAX) + Felt::ONE;
+    assert!(matches!(
+        BankaiEvent::decode(&[selector!("EpochBatch")], &data),
+        Err(Error::InvalidEvent(_))
+    ));
+}
+
+#[test]
+fn batches_are_ordered_by_slot() {
+    let mut index = EventIndex::default();
+    index.apply(batch(2, 480), Some(20));
+    index.apply(batch(1, 160), Some(21));
+    index.apply(batch(2, 480), Some(22));
+
+    let roots: Vec<Felt> = index.batches.iter().map(|b| b.batch_root).collect();
+    assert_eq!(roots, [Felt::from(1), Felt::from(2)]);
+    let first_slots: Vec<u64> = index.batches.iter().map(|b| b.first_slot).collect();
+    assert_eq!(first_slots, [0, 192]);
+    assert!(matches!(
+        index.epochs[&160].source,
+        EpochSource::Batch { batch_root } if batch_root == Felt::from(1)
+    ));
+}
+
+#[test]
+fn batch_roots_are_looked_up_by_slot_range() {
+    let mut index = EventIndex::default();
+    index.apply(batch(1, 160), Some(10));
+    index.apply(epoch_update(192), Some(11));
+    index.apply(batch(3, 640), Some(12));
+    assert_eq!(index.batch_root_for_slot(320), Some(Felt::from(3)));
+
+    // A backfilled batch, submitted after a later one, ends the range of the later batch
+    index.apply(batch(2, 384), Some(13));
+    let ranges: Vec<(u64, u64)> = index
+        .batches
+        .iter()
+        .map(|b| (b.first_slot, b.slot))
+        .collect();
+    assert_eq!(ranges, [(0, 160), (224, 384), (416, 640)]);
+
+    assert_eq!(index.batch_root_for_slot(0), Some(Felt::from(1)));
+    assert_eq!(index.batch_root_for_slot(96), Some(Felt::from(1)));
+    assert_eq!(index.batch_root_for_slot(160), Some(Felt::from(1)));
+    assert_eq!(index.batch_root_for_slot(224), Some(Felt::from(2)));
+    assert_eq!(index.batch_root_for_slot(320), Some(Felt::from(2)));
+    assert_eq!(index.batch_root_for_slot(416), Some(Felt::from(3)));
+    assert_eq!(index.batch_root_for_slot(544), Some(Felt::from(3)));
+
+    // Verified directly, so not part of any batch
+    assert_eq!(index.batch_root_for_slot(192), None);
+    // After the latest batch
+    assert_eq!(index.batch_root_for_slot(672), None);
+
+    // Decommitted epochs keep pointing to their batch and don't split it
+    index.apply(
+        BankaiEvent::EpochDecommitted {
+            batch_root: Felt::from(3),
+            slot: 576,
+            execution_hash: root(0xe2),
+            execution_height: 1576,
+        },
+        Some(14),
+    );
+    assert_eq!(index.batch_root_for_slot(576), Some(Felt::from(3)));
+    assert_eq!(index.batch_root_for_slot(608), Some(Felt::from(3)));
+    assert_eq!(index.decommitted_slots(0..=1000), [576]);
+}
@@ -0,0 +1,230 @@
+//! Proofs of older blocks against an anchor state, through `block_roots` and through
+//! `historical_summaries`. The anchor states are built from the same SSZ trees the prover uses.
+
+use alloy_primitives::FixedBytes;
+use bankai_client::epoch_update::BeaconHeader;
+use bankai_client::historical_proof::{HistoricalBlockProof, HistoricalProofPath};
+use bankai_client::utils::ssz_proof::{ChunkTree, SszTree};
+use bankai_client::Error;
+use tree_hash::TreeHash;
+
+const SLOTS_PER_HISTORICAL_ROOT: u64 = 8192;
+
+/// Fields of the Deneb beacon state
+const STATE_FIELDS: [&str; 28] = [
+    "genesis_time",
+    "genesis_validators_root",
+    "slot",
+    "fork",
+    "latest_block_header",
+    "block_roots",
+    "state_roots",
+    "historical_roots",
+    "eth1_data",
+    "eth1_data_votes",
+    "eth1_deposit_index",
+    "validators",
+    "balances",
+    "randao_mixes",
+    "slashings",
+    "previous_epoch_participation",
+    "current_epoch_participation",
+    "justification_bits",
+    "previous_justified_checkpoint",
+    "current_justified_checkpoint",
+    "finalized_checkpoint",
+    "inactivity_scores",
+    "current_sync_committee",
+    "next_sync_committee",
+    "latest_execution_payload_header",
+    "next_withdrawal_index",
+    "next_withdrawal_validator_index",
+    "historical_summaries",
+];
+
+fn node(seed: u64) -> FixedBytes<32> {
+    let mut bytes = [0u8; 32];
+    bytes[24..].copy_from_slice(&seed.to_be_bytes());
+    FixedBytes::from(bytes)
+}
+
+fn header(slot: u64) -> BeaconHeader {
+    BeaconHeader {
+        slot,
+        proposer_index: 42,
+        parent_root: node(1),
+        state_root: node(2),
+        body_root: node(3),
+    }
+}
+
+/// `block_roots` of a period that contains the target block. The slot after the target is
+/// empty, so it repeats the target root.
+fn block_roots(target: &BeaconHeader) -> ChunkTree {
+    let index = target.slot % SLOTS_PER_HISTORICAL_ROOT;
+    let roots = (0..SLOTS_PER_HISTORICAL_ROOT)
+        .map(|i| {
+            if i == index || i == index + 1 {
+                target.tree_hash_root()
+            } else {
+                node(1000 + i)
+            }
+        })
+        .collect();
+    ChunkTree::vector(roots, 13).unwrap()
+}
+
+/// A state with the given roots of `block_roots` and `historical_summaries`
+fn anchor_state(block_roots: FixedBytes<32>, summaries: FixedBytes<32>) -> SszTree {
+    SszTree::new(
+        STATE_FIELDS
+            .iter()
+            .enumerate()
+            .map(|(i, name)| match *name {
+                "block_roots" => (*name, block_roots),
+                "historical_summaries" => (*name, summaries),
+                _ => (*name, node(i as u64)),
+            })
+            .collect(),
+    )
+}
+
+/// A proof of a block within the last 8192 slots, and the tree it was proven from
+fn recent_block_proof() -> (HistoricalBlockProof, ChunkTree, SszTree) {
+    let target_header = header(3 * SLOTS_PER_HISTORICAL_ROOT + 400);
+    let block_roots = block_roots(&target_header);
+    let state = anchor_state(block_roots.root(), node(500));
+    let proof = block_roots
+        .prove(400)
+        .unwrap()
+        .extend(&state.prove_field("block_roots").unwrap())
+        .unwrap();
+    let proof = HistoricalBlockProof {
+        target_header,
+        anchor_slot: 3 * SLOTS_PER_HISTORICAL_ROOT + 500,
+        anchor_state_root: state.root(),
+        path: HistoricalProofPath::BlockRoots,
+        proof,
+    };
+    (proof, block_roots, state)
+}
+
+/// Summaries of periods 0 to 4, with the block roots of period 2 holding the target
+fn summaries(block_summary_root: FixedBytes<32>) -> (ChunkTree, Vec<ChunkTree>) {
+    let summaries: Vec<ChunkTree> = (0..5)
+        .map(|period| {
+            let block_summary_root = match period {
+                2 => block_summary_root,
+                _ => node(2000 + period),
+            };
+            ChunkTree::vector(vec![block_summary_root, node(3000 + period)], 1).unwrap()
+        })
+        .collect();
+    let list = ChunkTree::list(summaries.iter().map(|s| s.root()).collect(), 24).unwrap();
+    (list, summaries)
+}
+
+fn summary_block_proof() -> (HistoricalBlockProof, SszTree) {
+    let target_header = header(2 * SLOTS_PER_HISTORICAL_ROOT + 77);
+    let block_roots = block_roots(&target_header);
+    let (list, summaries) = summaries(block_roots.root());
+    let state = anchor_state(node(400), list.root());
+    let proof = block_roots
+        .prove(77)
+        .unwrap()
+        .extend(&summaries[2].prove(0).unwrap())
+        .unwrap()
+        .extend(&list.prove(2).unwrap())
+        .unwrap()
+        .extend(&state.prove_field("historical_summaries").unwrap())
+        .unwrap();
+    let proof = HistoricalBlockProof {
+        target_header,
+        anchor_slot: 5 * SLOTS_PER_HISTORICAL_ROOT + 10,
+        anchor_state_root: state.root(),
+        path: HistoricalProofPath::HistoricalSummaries {
+            summary_index: 2,
+            summary_slot: 3 * SLOTS_PER_HISTORICAL_ROOT,
+        },
+        proof,
+    };
+    (proof, state)
+}
+
+fn assert_rejected(proof: &HistoricalBlockProof, anchor_state_root: FixedBytes<32>) {
+    assert!(proof.verify(anchor_state_root).is_err());
+}
+
+#[test]
+fn blocks_are_proven_through_block_roots() {
+    let (proof, _, state) = recent_block_proof();
+    proof.verify(state.root()).unwrap();
+
+    // The proof survives a round trip through the exported JSON
+    let json = serde_json::to_string(&proof).unwrap();
+    let decoded: HistoricalBlockProof = serde_json::from_str(&json).unwrap();
+    decoded.verify(state.root()).unwrap();
+}
+
+#[test]
+fn blocks_are_proven_through_historical_summaries() {
+    let (proof, state) = summary_block_proof();
+    proof.verify(state.root()).unwrap();
+}
+
+#[test]
+fn proofs_of_another_index_are_rejected() {
+    // The empty slot after the target repeats its root, so the leaf matches
+    let (mut proof, block_roots, state) = recent_block_proof();
+    proof.proof = block_roots
+        .prove(401)
+        .unwrap()
+        .extend(&state.prove_field("block_roots").unwrap())
+        .unwrap();
+    assert_eq!(proof.proof.leaf, proof.target_header.tree_hash_root());
+    proof.proof.verify().unwrap();
+    assert!(matches!(
+        proof.verify(state.root()),
+        Err(Error::InvalidHistoricalProof(_))
+    ));
+
+    // A summary of a period the target is not part of
+    let (mut proof, state) = summary_block_proof();
+    proof.path = HistoricalProofPath::HistoricalSummaries {
+        summary_index: 2,
+        summary_slot: 4 * SLOTS_PER_HISTORICAL_ROOT,
+    };
+    assert_rejected(&proof, state.root());
+
+    // The proof of a state field that is not `historical_summaries`
+    let (mut proof, state) = summary_block_proof();
+    proof.path = HistoricalProofPath::BlockRoots;
+    assert_rejected(&proof, state.root());
+}
+
+#[test]
+fn proofs_with_a_wrong_summary_root_are_rejected() {
+    let (proof, state) = summary_block_proof();
+
+    // The block roots of the period are not the ones stored in the summary
+    let target_header = header(2 * SLOTS_PER_HISTORICAL_ROOT + 77);
+    let (_, summaries) = summaries(node(9999));
+    let block_roots = block_roots(&target_header);
+    assert!(matches!(
+        block_roots
+            .prove(77)
+            .unwrap()
+            .extend(&summaries[2].prove(0).unwrap()),
+        Err(Error::InvalidMerkleTree)
+    ));
+
+    // A modified sibling on the way to the summary root
+    let (mut tampered, _) = summary_block_proof();
+    tampered.proof.branch[13] = node(9999);
+    assert!(tampered.verify(state.root()).is_err());
+
+    // An anchor root other than the one the proof was built for
+    let (recent, _, recent_state) = recent_block_proof();
+    assert_rejected(&proof, recent_state.root());
+    assert_rejected(&recent, state.root());
+}
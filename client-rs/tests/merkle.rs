@@ -0,0 +1,173 @@
+//! Property tests of the SHA-256 and Poseidon merkle trees
+
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use alloy_primitives::FixedBytes;
+use bankai_client::epoch_batch::{
+    EpochUpdateBatch, EpochUpdateBatchInputs, ExpectedEpochBatchOutputs,
+};
+use bankai_client::traits::Submittable;
+use bankai_client::utils::merkle::{poseidon, sha256};
+use bankai_client::Error;
+use proptest::collection::vec;
+use proptest::prelude::*;
+use starknet::core::types::Felt;
+
+fn sha256_leaves(max: usize) -> impl Strategy<Value = Vec<FixedBytes<32>>> {
+    vec(any::<[u8; 32]>().prop_map(FixedBytes::from), 1..=max)
+}
+
+fn poseidon_leaves(max: usize) -> impl Strategy<Value = Vec<Felt>> {
+    vec(
+        any::<[u8; 32]>().prop_map(|bytes| Felt::from_bytes_be(&bytes)),
+        1..=max,
+    )
+}
+
+/// Root of the SHA-256 tree, computed from the path of the first leaf
+fn sha256_root(leaves: &[FixedBytes<32>]) -> FixedBytes<32> {
+    let path = sha256::generate_path(leaves.to_vec(), 0).unwrap();
+    sha256::hash_path(path, leaves[0], 0)
+}
+
+/// Roots of `merkleize` in `cairo/py/ssz.py`, one per leaf set
+fn ssz_merkleize(leaf_sets: &[Vec<FixedBytes<32>>]) -> Vec<FixedBytes<32>> {
+    let py_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../cairo/py");
+    let script = format!(
+        "import json, sys\n\
+         sys.path.insert(0, {:?})\n\
+         from ssz import merkleize\n\
+         for leaves in json.load(sys.stdin):\n    \
+             print(merkleize([bytes.fromhex(leaf[2:]) for leaf in leaves]).hex())",
+        py_dir.to_string_lossy()
+    );
+    let mut python = Command::new("python3")
+        .args(["-c", &script])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("python3 is installed");
+    python
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(serde_json::to_string(leaf_sets).unwrap().as_bytes())
+        .unwrap();
+    let output = python.wait_with_output().unwrap();
+    assert!(output.status.success(), "ssz.py failed");
+    String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|root| root.parse().unwrap())
+        .collect()
+}
+
+proptest! {
+    #[test]
+    fn sha256_paths_lead_to_the_same_root(
+        leaves in sha256_leaves(64),
+        index in any::<prop::sample::Index>(),
+    ) {
+        let index = index.index(leaves.len());
+        let path = sha256::generate_path(leaves.clone(), index).unwrap();
+        prop_assert_eq!(path.len(), leaves.len().next_power_of_two().trailing_zeros() as usize);
+        prop_assert_eq!(
+            sha256::hash_path(path, leaves[index], index as u64),
+            sha256_root(&leaves)
+        );
+    }
+
+    #[test]
+    fn sha256_paths_reject_other_leaves(
+        leaves in sha256_leaves(64),
+        index in any::<prop::sample::Index>(),
+        other in any::<[u8; 32]>(),
+    ) {
+        let index = index.index(leaves.len());
+        let other = FixedBytes::from(other);
+        prop_assume!(other != leaves[index]);
+        let path = sha256::generate_path(leaves.clone(), index).unwrap();
+        prop_assert_ne!(
+            sha256::hash_path(path, other, index as u64),
+            sha256_root(&leaves)
+        );
+    }
+
+    #[test]
+    fn sha256_path_of_missing_leaf_is_an_error(leaves in sha256_leaves(16), extra in 0usize..16) {
+        prop_assert!(sha256::generate_path(leaves.clone(), leaves.len() + extra).is_err());
+    }
+
+    #[test]
+    fn poseidon_paths_lead_to_the_root(leaves in poseidon_leaves(32)) {
+        let (root, paths) = poseidon::compute_paths(leaves.clone()).unwrap();
+        prop_assert_eq!(root, poseidon::compute_root(leaves.clone()).unwrap());
+        prop_assert_eq!(paths.len(), leaves.len());
+        for (index, path) in paths.iter().enumerate() {
+            prop_assert_eq!(poseidon::hash_path(leaves[index], path, index), root);
+        }
+    }
+
+    #[test]
+    fn poseidon_paths_reject_other_indexes(
+        leaves in poseidon_leaves(32),
+        index in any::<prop::sample::Index>(),
+    ) {
+        prop_assume!(leaves.len() > 1);
+        let (root, paths) = poseidon::compute_paths(leaves.clone()).unwrap();
+        let index = index.index(leaves.len());
+        let other = (index + 1) % leaves.len();
+        prop_assume!(leaves[index] != leaves[other]);
+        prop_assert_ne!(poseidon::hash_path(leaves[index], &paths[other], other), root);
+    }
+}
+
+#[test]
+fn poseidon_trees_need_a_leaf() {
+    assert!(matches!(
+        poseidon::compute_root(vec![]),
+        Err(Error::InvalidMerkleTree)
+    ));
+    assert!(matches!(
+        poseidon::compute_paths(vec![]),
+        Err(Error::InvalidMerkleTree)
+    ));
+
+    // A single leaf is its own root
+    let leaf = Felt::from(7);
+    assert_eq!(poseidon::compute_root(vec![leaf]).unwrap(), leaf);
+    assert_eq!(
+        poseidon::compute_paths(vec![leaf]).unwrap(),
+        (leaf, vec![vec![]])
+    );
+}
+
+#[test]
+fn empty_batches_are_rejected() {
+    let inputs = EpochUpdateBatchInputs {
+        committee_hash: FixedBytes::ZERO,
+        epochs: vec![],
+    };
+    assert!(matches!(
+        ExpectedEpochBatchOutputs::from_inputs(&inputs),
+        Err(Error::EmptyBatch)
+    ));
+    assert!(matches!(
+        EpochUpdateBatch::from_epochs(vec![]),
+        Err(Error::EmptyBatch)
+    ));
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(16))]
+
+    /// Leaf counts that are not a power of two are padded with zero chunks, as in SSZ
+    #[test]
+    fn sha256_root_matches_ssz_merkleize(leaf_sets in vec(sha256_leaves(40), 1..=16)) {
+        let roots: Vec<FixedBytes<32>> =
+            leaf_sets.iter().map(|leaves| sha256_root(leaves)).collect();
+        prop_assert_eq!(roots, ssz_merkleize(&leaf_sets));
+    }
+}
@@ -0,0 +1,99 @@
+//! Epoch accumulator roots and inclusion proofs for leaf counts around powers of two
+
+use bankai_client::utils::mmr::EpochAccumulator;
+use bankai_client::Error;
+use starknet_crypto::{poseidon_hash, poseidon_hash_many, Felt};
+
+fn leaf(i: u64) -> Felt {
+    Felt::from(1000 + i)
+}
+
+fn accumulator(n_leaves: u64) -> EpochAccumulator {
+    let mut accumulator = EpochAccumulator::default();
+    for i in 0..n_leaves {
+        assert!(accumulator.append(32 * i, leaf(i)));
+    }
+    accumulator
+}
+
+#[test]
+fn roots_bag_the_leaf_count_and_peaks() {
+    let (h0, h1, h2) = (leaf(0), leaf(1), leaf(2));
+    let h01 = poseidon_hash(h0, h1);
+
+    assert_eq!(accumulator(1).root(), poseidon_hash_many(&[Felt::ONE, h0]));
+    assert_eq!(accumulator(2).root(), poseidon_hash_many(&[Felt::TWO, h01]));
+    assert_eq!(
+        accumulator(3).root(),
+        poseidon_hash_many(&[Felt::THREE, h01, h2])
+    );
+
+    let h23 = poseidon_hash(h2, leaf(3));
+    let h0123 = poseidon_hash(h01, h23);
+    assert_eq!(
+        accumulator(5).root(),
+        poseidon_hash_many(&[Felt::from(5), h0123, leaf(4)])
+    );
+}
+
+#[test]
+fn every_leaf_is_proven_against_the_root() {
+    for n_leaves in [1, 2, 3, 5, 9, 17, 33, 65] {
+        let accumulator = accumulator(n_leaves);
+        assert_eq!(accumulator.peaks.len(), n_leaves.count_ones() as usize);
+        for i in 0..n_leaves {
+            let proof = accumulator.prove(32 * i).unwrap();
+            assert_eq!(proof.leaf, leaf(i));
+            proof.verify(accumulator.root()).unwrap();
+        }
+    }
+}
+
+#[test]
+fn duplicate_and_unknown_epochs_are_handled() {
+    let mut accumulator = accumulator(3);
+    let root = accumulator.root();
+    assert!(!accumulator.append(32, Felt::from(7)));
+    assert_eq!(accumulator.root(), root);
+
+    assert!(matches!(
+        accumulator.prove(16),
+        Err(Error::EpochNotAccumulated(16))
+    ));
+}
+
+#[test]
+fn invalid_proofs_are_rejected() {
+    let mut accumulator = accumulator(9);
+    let proof = accumulator.prove(32 * 5).unwrap();
+
+    // Proofs are bound to the root they were generated for
+    accumulator.append(32 * 9, leaf(9));
+    assert!(proof.verify(accumulator.root()).is_err());
+
+    let proof = accumulator.prove(32 * 5).unwrap();
+    let root = accumulator.root();
+
+    let mut tampered = accumulator.prove(32 * 5).unwrap();
+    tampered.path[1] = Felt::from(7);
+    assert!(matches!(
+        tampered.verify(root),
+        Err(Error::InvalidAccumulatorProof(_))
+    ));
+
+    let mut tampered = accumulator.prove(32 * 5).unwrap();
+    tampered.leaf_index = 4;
+    assert!(tampered.verify(root).is_err());
+
+    let mut tampered = accumulator.prove(32 * 5).unwrap();
+    tampered.n_leaves = 11;
+    assert!(tampered.verify(root).is_err());
+
+    let mut tampered = accumulator.prove(32 * 5).unwrap();
+    tampered.peaks[1] = Felt::from(7);
+    assert!(tampered.verify(root).is_err());
+
+    let mut tampered = proof;
+    tampered.leaf_index = 10;
+    assert!(tampered.verify(root).is_err());
+}
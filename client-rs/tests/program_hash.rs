@@ -0,0 +1,81 @@
+//! Consistency checks of the program hashes of the local circuits, the config and the contract
+
+use bankai_client::traits::ProofType;
+use bankai_client::utils::program_hash::ProgramHashReport;
+use bankai_client::Error;
+use starknet_crypto::Felt;
+
+fn report(
+    proof_type: ProofType,
+    local: Result<Felt, String>,
+    on_chain: Result<Option<Felt>, String>,
+) -> ProgramHashReport {
+    ProgramHashReport {
+        proof_type,
+        circuit_path: "cairo/build/circuit.json".to_string(),
+        local,
+        config: Felt::from(7),
+        on_chain,
+    }
+}
+
+#[test]
+fn matching_hashes_are_consistent() {
+    let report = report(ProofType::Epoch, Ok(Felt::from(7)), Ok(Some(Felt::from(7))));
+    assert!(report.is_consistent());
+    report.ensure_consistent().unwrap();
+}
+
+#[test]
+fn mismatches_are_reported() {
+    let local = report(ProofType::Epoch, Ok(Felt::from(8)), Ok(Some(Felt::from(7))));
+    assert!(!local.is_consistent());
+    assert!(matches!(
+        local.ensure_consistent(),
+        Err(Error::ProgramHashMismatch(ProofType::Epoch, expected, found))
+            if expected == Felt::from(7) && found == Felt::from(8)
+    ));
+
+    let on_chain = report(
+        ProofType::SyncCommittee,
+        Ok(Felt::from(7)),
+        Ok(Some(Felt::from(9))),
+    );
+    assert!(matches!(
+        on_chain.ensure_consistent(),
+        Err(Error::ProgramHashMismatch(ProofType::SyncCommittee, _, found)) if found == Felt::from(9)
+    ));
+}
+
+#[test]
+fn unavailable_local_hashes_are_reported() {
+    let report = report(
+        ProofType::Epoch,
+        Err("No such file or directory".to_string()),
+        Ok(Some(Felt::from(7))),
+    );
+    assert!(!report.is_consistent());
+    match report.ensure_consistent() {
+        Err(Error::ProgramHashUnavailable(path, reason)) => {
+            assert_eq!(path, "cairo/build/circuit.json");
+            assert_eq!(reason, "No such file or directory");
+        }
+        other => panic!("expected an unavailable program hash, got {:?}", other),
+    }
+}
+
+#[test]
+fn only_the_epoch_batch_getter_may_be_missing() {
+    let batch = report(ProofType::EpochBatch, Ok(Felt::from(7)), Ok(None));
+    assert!(batch.is_consistent());
+    batch.ensure_consistent().unwrap();
+
+    for proof_type in [ProofType::Epoch, ProofType::SyncCommittee] {
+        let report = report(proof_type, Ok(Felt::from(7)), Ok(None));
+        assert!(!report.is_consistent());
+        assert!(matches!(
+            report.ensure_consistent(),
+            Err(Error::InvalidResponse(_))
+        ));
+    }
+}
@@ -0,0 +1,255 @@
+//! Generalized indices and proofs of the SSZ containers, checked against the values of the
+//! consensus specs
+
+use alloy_primitives::FixedBytes;
+use bankai_client::utils::ssz_proof::{
+    ChunkTree, GeneralizedIndex, SszMultiproof, SszTree, NEXT_SYNC_COMMITTEE_GINDEX,
+};
+use bankai_client::Error;
+
+/// Fields of the Deneb beacon state
+const STATE_FIELDS: [&str; 28] = [
+    "genesis_time",
+    "genesis_validators_root",
+    "slot",
+    "fork",
+    "latest_block_header",
+    "block_roots",
+    "state_roots",
+    "historical_roots",
+    "eth1_data",
+    "eth1_data_votes",
+    "eth1_deposit_index",
+    "validators",
+    "balances",
+    "randao_mixes",
+    "slashings",
+    "previous_epoch_participation",
+    "current_epoch_participation",
+    "justification_bits",
+    "previous_justified_checkpoint",
+    "current_justified_checkpoint",
+    "finalized_checkpoint",
+    "inactivity_scores",
+    "current_sync_committee",
+    "next_sync_committee",
+    "latest_execution_payload_header",
+    "next_withdrawal_index",
+    "next_withdrawal_validator_index",
+    "historical_summaries",
+];
+
+/// Fields of the Deneb beacon block body
+const BODY_FIELDS: [&str; 12] = [
+    "randao_reveal",
+    "eth1_data",
+    "graffiti",
+    "proposer_slashings",
+    "attester_slashings",
+    "attestations",
+    "deposits",
+    "voluntary_exits",
+    "sync_aggregate",
+    "execution_payload",
+    "bls_to_execution_changes",
+    "blob_kzg_commitments",
+];
+
+/// Fields of the Deneb execution payload header
+const PAYLOAD_FIELDS: [&str; 17] = [
+    "parent_hash",
+    "fee_recipient",
+    "state_root",
+    "receipts_root",
+    "logs_bloom",
+    "prev_randao",
+    "block_number",
+    "gas_limit",
+    "gas_used",
+    "timestamp",
+    "extra_data",
+    "base_fee_per_gas",
+    "block_hash",
+    "transactions_root",
+    "withdrawals_root",
+    "blob_gas_used",
+    "excess_blob_gas",
+];
+
+fn node(seed: u64) -> FixedBytes<32> {
+    let mut bytes = [0u8; 32];
+    bytes[24..].copy_from_slice(&seed.to_be_bytes());
+    FixedBytes::from(bytes)
+}
+
+/// A tree with a distinct root for every field
+fn container(fields: &[&'static str], seed: u64) -> SszTree {
+    SszTree::new(
+        fields
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (*name, node(seed + i as u64)))
+            .collect(),
+    )
+}
+
+fn block_body() -> SszTree {
+    let payload = container(&PAYLOAD_FIELDS, 100);
+    let body = SszTree::new(
+        BODY_FIELDS
+            .iter()
+            .enumerate()
+            .map(|(i, name)| match *name {
+                "execution_payload" => (*name, payload.root()),
+                _ => (*name, node(i as u64)),
+            })
+            .collect(),
+    );
+    body.with_subtree("execution_payload", payload).unwrap()
+}
+
+#[test]
+fn state_fields_have_spec_gindices() {
+    let state = container(&STATE_FIELDS, 0);
+    assert_eq!(
+        state.gindex("next_sync_committee").unwrap(),
+        NEXT_SYNC_COMMITTEE_GINDEX
+    );
+    assert_eq!(
+        state.gindex("current_sync_committee").unwrap(),
+        GeneralizedIndex(54)
+    );
+    assert_eq!(
+        state.gindex("finalized_checkpoint").unwrap(),
+        GeneralizedIndex(52)
+    );
+    assert_eq!(state.gindex("block_roots").unwrap(), GeneralizedIndex(37));
+
+    let proof = state.prove(NEXT_SYNC_COMMITTEE_GINDEX).unwrap();
+    assert_eq!(proof.leaf, node(23));
+    assert_eq!(proof.branch.len(), 5);
+    proof.verify().unwrap();
+
+    assert!(matches!(
+        state.gindex("next_sync_committee.pubkeys"),
+        Err(Error::InvalidSszPath(_))
+    ));
+}
+
+#[test]
+fn execution_payload_fields_are_proven_against_the_body() {
+    let body = block_body();
+    assert_eq!(
+        body.gindex("execution_payload").unwrap(),
+        GeneralizedIndex(25)
+    );
+    assert_eq!(
+        body.gindex("execution_payload.state_root").unwrap(),
+        GeneralizedIndex(802)
+    );
+    assert_eq!(
+        body.gindex("execution_payload.block_hash").unwrap(),
+        GeneralizedIndex(812)
+    );
+
+    let proof = body.prove_field("execution_payload.block_number").unwrap();
+    assert_eq!(proof.root, body.root());
+    assert_eq!(proof.leaf, node(106));
+    assert_eq!(proof.branch.len(), 9);
+    proof.verify().unwrap();
+
+    // The subtree has to match the field it is attached to
+    let body = container(&BODY_FIELDS, 0);
+    assert!(body
+        .with_subtree("execution_payload", container(&PAYLOAD_FIELDS, 100))
+        .is_err());
+}
+
+#[test]
+fn block_roots_are_proven_against_the_state() {
+    let roots: Vec<FixedBytes<32>> = (0..8192).map(|i| node(1000 + i)).collect();
+    let block_roots = ChunkTree::vector(roots, 13).unwrap();
+    let state = SszTree::new(
+        STATE_FIELDS
+            .iter()
+            .enumerate()
+            .map(|(i, name)| match *name {
+                "block_roots" => (*name, block_roots.root()),
+                _ => (*name, node(i as u64)),
+            })
+            .collect(),
+    );
+
+    let index = 1234;
+    let outer = state.prove_field("block_roots").unwrap();
+    let proof = block_roots.prove(index).unwrap().extend(&outer).unwrap();
+    assert_eq!(proof.gindex, GeneralizedIndex(303104 + index as u64));
+    assert_eq!(proof.root, state.root());
+    assert_eq!(proof.leaf, node(1000 + index as u64));
+    proof.verify().unwrap();
+
+    // The vector root matches a container of the same chunks
+    let chunks: Vec<FixedBytes<32>> = (0..8).map(node).collect();
+    let names = ["a", "b", "c", "d", "e", "f", "g", "h"];
+    let tree = SszTree::new(names.into_iter().zip(chunks.iter().copied()).collect());
+    assert_eq!(ChunkTree::vector(chunks, 3).unwrap().root(), tree.root());
+}
+
+#[test]
+fn multiproofs_round_trip() {
+    let body = block_body();
+    let proof = body
+        .prove_fields(&[
+            "execution_payload.state_root",
+            "execution_payload.block_number",
+            "execution_payload.block_hash",
+            "sync_aggregate",
+        ])
+        .unwrap();
+    assert_eq!(
+        proof.gindices,
+        [
+            GeneralizedIndex(802),
+            GeneralizedIndex(806),
+            GeneralizedIndex(812),
+            GeneralizedIndex(24),
+        ]
+    );
+    proof.verify().unwrap();
+
+    let json = serde_json::to_string(&proof).unwrap();
+    let decoded: SszMultiproof = serde_json::from_str(&json).unwrap();
+    assert_eq!(proof, decoded);
+    decoded.verify().unwrap();
+
+    // Proving every node of a branch on its own gives the same root
+    for gindex in proof.gindices.iter() {
+        assert_eq!(body.prove(*gindex).unwrap().compute_root(), proof.root);
+    }
+}
+
+#[test]
+fn tampered_proofs_are_rejected() {
+    let body = block_body();
+    let proof = body
+        .prove_fields(&["execution_payload.state_root", "sync_aggregate"])
+        .unwrap();
+
+    let mut tampered = proof.clone();
+    tampered.proof[0] = node(9999);
+    assert!(matches!(tampered.verify(), Err(Error::InvalidMerkleTree)));
+
+    let mut tampered = proof.clone();
+    tampered.leaves[1] = node(9999);
+    assert!(tampered.verify().is_err());
+
+    // Helpers have to be exactly the ones of the proven indices
+    let mut tampered = proof;
+    tampered.helper_indices.pop();
+    tampered.proof.pop();
+    assert!(tampered.verify().is_err());
+
+    let mut single = body.prove_field("execution_payload.block_hash").unwrap();
+    single.branch[3] = node(9999);
+    assert!(single.verify().is_err());
+}
@@ -0,0 +1,69 @@
+mod common;
+
+use std::path::Path;
+use std::process::{Command, Output};
+
+use common::{stdout, work_dir};
+use serde_json::Value;
+
+/// Runs a command that needs the endpoints, with the given config and environment
+fn run_online(dir: &Path, config: &str, env: &[(&str, &str)]) -> Output {
+    std::fs::write(dir.join("bankai_config.json"), config).unwrap();
+    Command::new(env!("CARGO_BIN_EXE_client-rs"))
+        .args(["--output", "json", "doctor"])
+        .current_dir(dir)
+        .env_clear()
+        .env("BANKAI_CONFIG_PATH", dir.join("bankai_config.json"))
+        .envs(env.iter().copied())
+        .output()
+        .unwrap()
+}
+
+fn failure(output: &Output) -> String {
+    assert_eq!(output.status.code(), Some(1));
+    let report: Value = serde_json::from_str(&stdout(output)).unwrap();
+    assert_eq!(report["status"], "failed");
+    report["error"].as_str().unwrap().to_string()
+}
+
+#[test]
+fn invalid_config_is_reported() {
+    let dir = work_dir("startup-config");
+    let output = run_online(&dir, "{ \"network\": ", &[]);
+    assert!(failure(&output).starts_with("Failed to (de)serialize"));
+}
+
+#[test]
+fn missing_or_invalid_environment_is_reported() {
+    let dir = work_dir("startup-env");
+    let output = run_online(&dir, "{}", &[("BEACON_RPC_URL", "http://127.0.0.1:1")]);
+    assert_eq!(
+        failure(&output),
+        "Environment variable STARKNET_RPC_URL is not set"
+    );
+
+    let mut env = vec![
+        ("BEACON_RPC_URL", "http://127.0.0.1:1"),
+        ("STARKNET_RPC_URL", "http://127.0.0.1:1"),
+        ("STARKNET_ADDRESS", "not an address"),
+        ("STARKNET_PRIVATE_KEY", "0x1"),
+        ("ATLANTIC_API_KEY", "key"),
+    ];
+    let output = run_online(&dir, "{}", &env);
+    assert!(failure(&output).contains("Invalid address"));
+
+    // The Atlantic settings are resolved when the client is set up, not on first use
+    env[2] = ("STARKNET_ADDRESS", "0x1");
+    let output = run_online(&dir, "{}", &env);
+    assert_eq!(
+        failure(&output),
+        "Environment variable PROOF_WRAPPER_PROGRAM_HASH is not set"
+    );
+
+    env.push(("PROOF_WRAPPER_PROGRAM_HASH", "0x1"));
+    let output = run_online(&dir, "{}", &env);
+    assert_eq!(
+        failure(&output),
+        "Environment variable PROOF_REGISTRY is not set"
+    );
+}
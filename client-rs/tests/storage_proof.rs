@@ -0,0 +1,105 @@
+//! Verification of account and storage proofs against a committed execution header.
+//!
+//! `tests/fixtures/execution/get_proof_21000000.json` holds an `eth_getBlockByNumber` header
+//! and an `eth_getProof` response for the account, with an inclusion proof of slots 0 and 4
+//! and an exclusion proof of slot 100.
+
+use std::path::Path;
+
+use alloy_primitives::{Bytes, U256};
+use bankai_client::storage_proof::{AccountStorageProof, CommittedExecutionHeader};
+use bankai_client::utils::execution_rpc::{AccountProofResponse, ExecutionBlockHeader};
+use bankai_client::Error;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct Fixture {
+    header: ExecutionBlockHeader,
+    proof: AccountProofResponse,
+}
+
+fn recorded_proof() -> AccountStorageProof {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/execution/get_proof_21000000.json");
+    let fixture: Fixture = serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+    AccountStorageProof {
+        committed_header: CommittedExecutionHeader {
+            hash: fixture.header.hash,
+            height: 21000000,
+        },
+        header: fixture.header,
+        account: fixture.proof,
+    }
+}
+
+/// Flips a bit in the last byte of the node
+fn tamper(node: &mut Bytes) {
+    let mut bytes = node.to_vec();
+    *bytes.last_mut().unwrap() ^= 1;
+    *node = Bytes::from(bytes);
+}
+
+fn assert_rejected(proof: &AccountStorageProof) {
+    assert!(matches!(
+        proof.verify(),
+        Err(Error::InvalidExecutionProof(_))
+    ));
+}
+
+#[test]
+fn recorded_proof_verifies() {
+    let proof = recorded_proof();
+    assert_eq!(proof.header.compute_hash(), proof.header.hash);
+    proof.verify().unwrap();
+
+    let values: Vec<U256> = proof
+        .account
+        .storage_proof
+        .iter()
+        .map(|slot| slot.value)
+        .collect();
+    assert_eq!(values, [U256::from(0x2a), U256::from(1) << 200, U256::ZERO]);
+}
+
+#[test]
+fn tampered_proofs_are_rejected() {
+    // A node of the account proof
+    let mut proof = recorded_proof();
+    tamper(proof.account.account_proof.last_mut().unwrap());
+    assert_rejected(&proof);
+
+    // A node of a storage proof
+    let mut proof = recorded_proof();
+    tamper(&mut proof.account.storage_proof[1].proof[0]);
+    assert_rejected(&proof);
+
+    // A storage value that differs from the proven one
+    let mut proof = recorded_proof();
+    proof.account.storage_proof[0].value = U256::from(0x2b);
+    assert_rejected(&proof);
+
+    // A slot claimed to be empty
+    let mut proof = recorded_proof();
+    proof.account.storage_proof[0].value = U256::ZERO;
+    assert_rejected(&proof);
+
+    // An account field that differs from the proven account
+    let mut proof = recorded_proof();
+    proof.account.balance += U256::from(1);
+    assert_rejected(&proof);
+
+    // A header that doesn't match the committed block
+    let mut proof = recorded_proof();
+    proof.committed_header.height += 1;
+    assert_rejected(&proof);
+    let mut proof = recorded_proof();
+    proof.header.gas_used += U256::from(1);
+    assert_rejected(&proof);
+}
+
+#[test]
+fn nonces_beyond_64_bits_are_rejected() {
+    let mut proof = recorded_proof();
+    proof.account.nonce = U256::from(u64::MAX) + U256::from(1);
+    assert_rejected(&proof);
+}
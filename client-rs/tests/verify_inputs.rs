@@ -0,0 +1,422 @@
+//! Offline checks of exported updates.
+//!
+//! Besides the circuit fixtures, the epoch and batch updates are built in the exported schema
+//! from a test committee with known secret keys, so their signatures and paths are valid.
+
+mod common;
+
+use std::path::{Path, PathBuf};
+use std::process::Output;
+
+use alloy_primitives::FixedBytes;
+use bankai_client::epoch_batch::EpochUpdateBatch;
+use bankai_client::epoch_update::{
+    BeaconHeader, EpochCircuitInputs, EpochUpdate, ExpectedEpochUpdateOutputs, G1Point, G2Point,
+};
+use bankai_client::execution_header::{ExecutionHeaderProof, EXECUTION_PAYLOAD_LEAF_INDEX};
+use bankai_client::input_verifier::{
+    signing_root, verify_signature, Check, ExportedUpdate, VerificationReport,
+    ELECTRA_ACTIVATION_SLOT,
+};
+use bankai_client::traits::Submittable;
+use bankai_client::utils::merkle::sha256::{generate_path, hash_path};
+use bankai_client::Error;
+use bls12_381::hash_to_curve::{ExpandMsgXmd, HashToCurve};
+use bls12_381::{G1Affine, G2Affine, G2Projective, Scalar};
+use common::{stdout, work_dir};
+use serde_json::{json, Value};
+use sha2::Sha256;
+use starknet_crypto::Felt;
+use types::{ExecutionPayloadHeader, MainnetEthSpec};
+
+const SIGNATURE_DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
+const COMMITTEE_SIZE: u64 = 512;
+
+/// Members of the test committee that don't sign
+const NON_SIGNERS: [u64; 3] = [3, 100, 511];
+
+/// Slots of the epochs in the test batch
+const BATCH_SLOTS: [u64; 3] = [6463488, 6463520, 6463552];
+
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../cairo/tests/fixtures")
+        .join(name)
+}
+
+fn read_json(path: &Path) -> Value {
+    serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+}
+
+fn node(seed: u64) -> FixedBytes<32> {
+    let mut bytes = [0u8; 32];
+    bytes[24..].copy_from_slice(&seed.to_be_bytes());
+    FixedBytes::from(bytes)
+}
+
+/// Public key of a secret key of the test committee
+fn public_key(secret: Scalar) -> G1Point {
+    G1Point(G1Affine::from(G1Affine::generator() * secret))
+}
+
+/// Sum of the secret keys of the members, member `i` holding the key `i + 1`
+fn secret_key(members: impl Iterator<Item = u64>) -> Scalar {
+    members.fold(Scalar::from(0u64), |acc, member| {
+        acc + Scalar::from(member + 1)
+    })
+}
+
+/// Deneb execution payload header of the block at `height`, as served by the beacon API
+fn payload_header(height: u64) -> ExecutionPayloadHeader<MainnetEthSpec> {
+    serde_json::from_value(json!({
+        "parent_hash": node(height - 1),
+        "fee_recipient": format!("0x{}", "42".repeat(20)),
+        "state_root": node(10),
+        "receipts_root": node(11),
+        "logs_bloom": format!("0x{}", "00".repeat(256)),
+        "prev_randao": node(12),
+        "block_number": height.to_string(),
+        "gas_limit": "30000000",
+        "gas_used": "12000000",
+        "timestamp": (1700000000 + height).to_string(),
+        "extra_data": "0x",
+        "base_fee_per_gas": "7",
+        "block_hash": node(height),
+        "transactions_root": node(13),
+        "withdrawals_root": node(14),
+        "blob_gas_used": "0",
+        "excess_blob_gas": "0",
+    }))
+    .unwrap()
+}
+
+/// An epoch update at `slot`, signed by all members of the test committee but the non-signers
+fn epoch_update(slot: u64) -> EpochUpdate {
+    let execution_payload_header = payload_header(21000000 + slot / 32);
+    let leaf = FixedBytes::from_slice(
+        beacon_state_proof::state_proof_fetcher::TreeHash::tree_hash_root(
+            &execution_payload_header,
+        )
+        .as_bytes(),
+    );
+    let body_leaves = (0..12)
+        .map(|i| match i {
+            EXECUTION_PAYLOAD_LEAF_INDEX => leaf,
+            _ => node(20 + i as u64),
+        })
+        .collect();
+    let path = generate_path(body_leaves, EXECUTION_PAYLOAD_LEAF_INDEX).unwrap();
+    let body_root = hash_path(path.clone(), leaf, EXECUTION_PAYLOAD_LEAF_INDEX as u64);
+
+    let header = BeaconHeader {
+        slot,
+        proposer_index: 640,
+        parent_root: node(slot - 1),
+        state_root: node(slot + 1),
+        body_root,
+    };
+    let message = <G2Projective as HashToCurve<ExpandMsgXmd<Sha256>>>::hash_to_curve(
+        signing_root(tree_hash::TreeHash::tree_hash_root(&header), slot)
+            .unwrap()
+            .as_slice(),
+        SIGNATURE_DST,
+    );
+    let signers = (0..COMMITTEE_SIZE).filter(|member| !NON_SIGNERS.contains(member));
+
+    let circuit_inputs = EpochCircuitInputs {
+        header,
+        signature_point: G2Point(G2Affine::from(message * secret_key(signers))),
+        aggregate_pub: public_key(secret_key(0..COMMITTEE_SIZE)),
+        non_signers: NON_SIGNERS
+            .iter()
+            .map(|member| public_key(secret_key([*member].into_iter())))
+            .collect(),
+        execution_header_proof: ExecutionHeaderProof {
+            root: body_root,
+            path,
+            leaf,
+            index: EXECUTION_PAYLOAD_LEAF_INDEX,
+            execution_payload_header,
+            slot,
+        },
+    };
+    let expected_circuit_outputs =
+        ExpectedEpochUpdateOutputs::from_inputs(&circuit_inputs).unwrap();
+    EpochUpdate {
+        circuit_inputs,
+        expected_circuit_outputs,
+    }
+}
+
+fn epoch_batch() -> EpochUpdateBatch {
+    EpochUpdateBatch::from_epochs(BATCH_SLOTS.into_iter().map(epoch_update).collect()).unwrap()
+}
+
+/// Writes the update as exported by the client and loads it back
+fn export(dir: &Path, update: &impl serde::Serialize) -> ExportedUpdate {
+    let path = dir.join("update.json");
+    std::fs::write(&path, serde_json::to_string_pretty(update).unwrap()).unwrap();
+    ExportedUpdate::load(path.to_str().unwrap()).unwrap()
+}
+
+fn check<'a>(report: &'a VerificationReport, name: &str) -> &'a Check {
+    report
+        .checks
+        .iter()
+        .find(|check| check.name == name)
+        .unwrap_or_else(|| panic!("no check named {}", name))
+}
+
+/// Asserts that exactly the named checks failed
+fn assert_failed(report: &VerificationReport, names: &[&str]) {
+    let failed: Vec<&str> = report
+        .checks
+        .iter()
+        .filter(|check| !check.passed)
+        .map(|check| check.name.as_str())
+        .collect();
+    assert_eq!(failed, names, "{:?}", report.checks);
+}
+
+/// Writes the committee fixture with a different committee hash into `dir`
+fn tampered_committee_update(dir: &Path) -> PathBuf {
+    let mut update = read_json(&fixture("committee_update_5800000.json"));
+    update["expected_circuit_outputs"]["committee_hash"] =
+        Value::from(format!("0x{}", "11".repeat(32)));
+    let path = dir.join("input_5800000.json");
+    std::fs::write(&path, update.to_string()).unwrap();
+    path
+}
+
+/// Runs `verify-inputs` without any environment, so neither a config nor endpoints are set
+async fn run_verify_inputs(dir: &Path, path: &Path) -> Output {
+    tokio::process::Command::new(env!("CARGO_BIN_EXE_client-rs"))
+        .args(["--output", "json", "verify-inputs"])
+        .arg(path)
+        .current_dir(dir)
+        .env_clear()
+        .output()
+        .await
+        .unwrap()
+}
+
+#[test]
+fn committee_fixture_passes_all_checks() {
+    let path = fixture("committee_update_5800000.json");
+    let update = ExportedUpdate::load(path.to_str().unwrap()).unwrap();
+    let report = update.verify();
+    assert!(report.passed(), "{:?}", report.checks);
+    assert_eq!(report.slot, 5800000);
+    let names: Vec<&str> = report
+        .checks
+        .iter()
+        .map(|check| check.name.as_str())
+        .collect();
+    assert_eq!(names, ["next sync committee branch", "expected outputs"]);
+}
+
+#[test]
+fn tampered_outputs_are_reported() {
+    let dir = work_dir("verify-inputs-tampered");
+    let path = tampered_committee_update(&dir);
+    let report = ExportedUpdate::load(path.to_str().unwrap())
+        .unwrap()
+        .verify();
+    assert!(!report.passed());
+    assert!(report.checks[0].passed);
+    assert!(!report.checks[1].passed);
+    assert_eq!(
+        report.checks[1].detail.as_deref(),
+        Some("mismatched committee_hash")
+    );
+}
+
+#[test]
+fn fixture_signature_verifies() {
+    let inputs = read_json(&fixture("epoch_6463489_proof.json"))["circuit_inputs"].clone();
+    let mut header: BeaconHeader = serde_json::from_value(inputs["header"].clone()).unwrap();
+    let signature: G2Point = serde_json::from_value(inputs["signature_point"].clone()).unwrap();
+    let committee: G1Point = serde_json::from_value(inputs["committee_pub"].clone()).unwrap();
+    let non_signers: Vec<G1Point> = serde_json::from_value(inputs["non_signers"].clone()).unwrap();
+
+    assert!(verify_signature(&header, &signature, &committee, &non_signers).unwrap());
+    // The signers must be exactly the committee minus the non-signers
+    assert!(!verify_signature(&header, &signature, &committee, &non_signers[1..]).unwrap());
+    header.proposer_index += 1;
+    assert!(!verify_signature(&header, &signature, &committee, &non_signers).unwrap());
+}
+
+#[test]
+fn forks_after_deneb_are_unsupported() {
+    let root = node(1);
+    assert!(signing_root(root, ELECTRA_ACTIVATION_SLOT - 32).is_ok());
+    assert!(matches!(
+        signing_root(root, ELECTRA_ACTIVATION_SLOT),
+        Err(Error::UnsupportedFork(ELECTRA_ACTIVATION_SLOT))
+    ));
+
+    // Reported as such instead of a signature mismatch
+    let dir = work_dir("verify-inputs-electra");
+    let mut update = epoch_update(BATCH_SLOTS[0]);
+    update.circuit_inputs.header.slot = ELECTRA_ACTIVATION_SLOT;
+    let report = export(&dir, &update).verify();
+    assert_eq!(
+        check(&report, "sync committee signature").detail.as_deref(),
+        Some("Slot 7118848 is after Deneb, later forks are not supported")
+    );
+}
+
+#[test]
+fn exported_epoch_passes_all_checks() {
+    let dir = work_dir("verify-inputs-epoch");
+    let update = export(&dir, &epoch_update(BATCH_SLOTS[0]));
+    assert!(matches!(update, ExportedUpdate::Epoch(_)));
+
+    let report = update.verify();
+    assert!(report.passed(), "{:?}", report.checks);
+    assert_eq!(report.slot, BATCH_SLOTS[0]);
+    let names: Vec<&str> = report
+        .checks
+        .iter()
+        .map(|check| check.name.as_str())
+        .collect();
+    assert_eq!(
+        names,
+        [
+            "execution header branch",
+            "sync committee signature",
+            "expected outputs"
+        ]
+    );
+}
+
+#[test]
+fn tampered_epochs_are_reported() {
+    let dir = work_dir("verify-inputs-epoch-tampered");
+
+    // A sibling on the way from the payload header to the body root
+    let mut update = epoch_update(BATCH_SLOTS[0]);
+    update.circuit_inputs.execution_header_proof.path[1] = node(9999);
+    let report = export(&dir, &update).verify();
+    assert_failed(&report, &["execution header branch"]);
+    assert_eq!(
+        check(&report, "execution header branch").detail.as_deref(),
+        Some("path does not lead to the body root")
+    );
+
+    // A payload header of another block
+    let mut update = epoch_update(BATCH_SLOTS[0]);
+    update
+        .circuit_inputs
+        .execution_header_proof
+        .execution_payload_header = payload_header(20999999);
+    let report = export(&dir, &update).verify();
+    assert_failed(&report, &["execution header branch", "expected outputs"]);
+
+    // The signature of another header
+    let mut update = epoch_update(BATCH_SLOTS[0]);
+    update.circuit_inputs.signature_point =
+        epoch_update(BATCH_SLOTS[1]).circuit_inputs.signature_point;
+    let report = export(&dir, &update).verify();
+    assert_failed(&report, &["sync committee signature"]);
+
+    // A signer claimed to be a non-signer
+    let mut update = epoch_update(BATCH_SLOTS[0]);
+    update
+        .circuit_inputs
+        .non_signers
+        .push(public_key(secret_key([7].into_iter())));
+    update.expected_circuit_outputs.n_signers -= 1;
+    let report = export(&dir, &update).verify();
+    assert_failed(&report, &["sync committee signature"]);
+}
+
+#[test]
+fn exported_batch_passes_all_checks() {
+    let dir = work_dir("verify-inputs-batch");
+    let batch = export(&dir, &epoch_batch());
+    assert!(matches!(batch, ExportedUpdate::EpochBatch(_)));
+
+    let report = batch.verify();
+    assert!(report.passed(), "{:?}", report.checks);
+    assert_eq!(report.slot, BATCH_SLOTS[2]);
+    assert_eq!(report.checks.len(), 3 * BATCH_SLOTS.len() + 3);
+    for slot in BATCH_SLOTS {
+        assert!(
+            check(
+                &report,
+                &format!("epoch {}: sync committee signature", slot)
+            )
+            .passed
+        );
+    }
+}
+
+#[test]
+fn tampered_batches_are_reported() {
+    let dir = work_dir("verify-inputs-batch-tampered");
+
+    // An epoch signed by another committee than the batch one
+    let mut batch = epoch_batch();
+    batch.circuit_inputs.committee_hash = node(9999);
+    let report = export(&dir, &batch).verify();
+    assert_failed(&report, &["batch committee"]);
+    assert_eq!(
+        check(&report, "batch committee").detail.as_deref(),
+        Some("signed by another committee: 6463488, 6463520, 6463552")
+    );
+
+    // A sibling of a Poseidon path
+    let mut batch = epoch_batch();
+    batch.merkle_paths[1][0] = Felt::from(7);
+    let report = export(&dir, &batch).verify();
+    assert_failed(&report, &["batch merkle paths"]);
+    assert_eq!(
+        check(&report, "batch merkle paths").detail.as_deref(),
+        Some("paths of epochs 1 do not lead to the batch root")
+    );
+
+    // A missing path
+    let mut batch = epoch_batch();
+    batch.merkle_paths.pop();
+    let report = export(&dir, &batch).verify();
+    assert_failed(&report, &["batch merkle paths"]);
+
+    // Outputs that don't commit to the epochs of the batch
+    let mut batch = epoch_batch();
+    batch.expected_circuit_outputs.latest_batch_output.n_signers -= 1;
+    let report = export(&dir, &batch).verify();
+    assert_failed(&report, &["expected outputs"]);
+    assert_eq!(
+        check(&report, "expected outputs").detail.as_deref(),
+        Some("mismatched latest_batch_output")
+    );
+
+    // An epoch whose execution header branch was modified after batching
+    let mut batch = epoch_batch();
+    batch.circuit_inputs.epochs[2]
+        .circuit_inputs
+        .execution_header_proof
+        .path[0] = node(9999);
+    let report = export(&dir, &batch).verify();
+    assert_failed(&report, &["epoch 6463552: execution header branch"]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn cli_verifies_without_config_or_endpoints() {
+    let dir = work_dir("verify-inputs-cli");
+
+    let output = run_verify_inputs(&dir, &fixture("committee_update_5800000.json")).await;
+    assert!(output.status.success());
+    let report: Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(report["status"], "success");
+    assert_eq!(report["proof_type"], "sync_committee");
+    assert_eq!(report["checks"].as_array().unwrap().len(), 2);
+
+    let output = run_verify_inputs(&dir, &tampered_committee_update(&dir)).await;
+    assert_eq!(output.status.code(), Some(1));
+    let report: Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(report["status"], "failed");
+    assert_eq!(report["checks"][1]["passed"], false);
+}
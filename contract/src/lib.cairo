@@ -19,6 +19,7 @@ pub trait IBankaiContract<TContractState> {
     fn get_latest_committee_id(self: @TContractState) -> u64;
     fn get_committee_update_program_hash(self: @TContractState) -> felt252;
     fn get_epoch_update_program_hash(self: @TContractState) -> felt252;
+    fn get_epoch_batch_program_hash(self: @TContractState) -> felt252;
     fn get_epoch_proof(self: @TContractState, slot: u64) -> EpochProof;
     fn verify_committee_update(
         ref self: TContractState, beacon_state_root: u256, committee_hash: u256, slot: u64,
@@ -179,6 +180,10 @@ pub mod BankaiContract {
             self.epoch_update_program_hash.read()
         }
 
+        fn get_epoch_batch_program_hash(self: @ContractState) -> felt252 {
+            self.epoch_batch_program_hash.read()
+        }
+
         fn get_epoch_proof(self: @ContractState, slot: u64) -> EpochProof {
             self.epochs.read(slot)
         }
@@ -386,6 +391,7 @@ pub mod BankaiContract {
         return fact_hash;
     }
 
+    #[cfg(not(feature: 'mock_integrity'))]
     fn is_valid_fact_hash(fact_hash: felt252) -> bool {
         let config = VerifierConfiguration {
             layout: 'recursive_with_poseidon',
@@ -398,4 +404,9 @@ pub mod BankaiContract {
         let integrity = Integrity::new().with_config(config, SECURITY_BITS);
         integrity.is_fact_hash_valid(fact_hash)
     }
+
+    #[cfg(feature: 'mock_integrity')]
+    fn is_valid_fact_hash(_fact_hash: felt252) -> bool {
+        true
+    }
 }
\ No newline at end of file